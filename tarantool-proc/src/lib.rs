@@ -409,11 +409,12 @@ mod msgpack {
         let code: TokenStream = fields
             .named
             .iter()
-            .map(|f| {
+            .enumerate()
+            .map(|(i, f)| {
                 if f.ty.is_option() {
                     met_option = true;
                     fields_passed -= 1;
-                    decode_named_optional_field(f, tarantool_crate, &mut var_names, allow_array_optionals, fields_amount, fields_passed)
+                    decode_named_optional_field(f, i, tarantool_crate, &mut var_names, allow_array_optionals, fields_amount, fields_passed)
                 } else {
                     if met_option && allow_array_optionals {
                         return syn::Error::new(
@@ -444,6 +445,7 @@ mod msgpack {
     #[inline]
     fn decode_named_optional_field(
         field: &Field,
+        index: usize,
         tarantool_crate: &Path,
         names: &mut Vec<Ident>,
         allow_array_optionals: bool,
@@ -457,6 +459,7 @@ mod msgpack {
         let field_repr = format_ident!("{}", field_ident).to_string();
         let field_name = proc_macro2::Literal::byte_string(field_repr.as_bytes());
         let var_name = format_ident!("_field_{}", field_ident);
+        let index = index as u32;
 
         let read_key = quote_spanned! {field.span()=>
             if as_map {
@@ -480,7 +483,8 @@ mod msgpack {
             Some(FieldAttr::Vec) => unimplemented!("`as_vec` is not currently supported"),
             Some(FieldAttr::Raw) => quote_spanned! {field.span()=>
                     let mut #var_name: #field_type = None;
-                    let mut is_none = false;
+                    // The trailing optional fields may be missing from the array
+                    let mut is_none = !as_map && len <= #index;
 
                     #read_key
                     if !is_none {
@@ -489,7 +493,8 @@ mod msgpack {
             },
             None => quote_spanned! {field.span()=>
                 let mut #var_name: #field_type = None;
-                let mut is_none = false;
+                // The trailing optional fields may be missing from the array
+                let mut is_none = !as_map && len <= #index;
 
                 #read_key
                 if !is_none {
@@ -627,6 +632,7 @@ mod msgpack {
 
         let field_index = Index::from(index);
         let var_name = quote::format_ident!("_field_{}", field_index);
+        let index = index as u32;
 
         let out = match field_attr {
             Some(FieldAttr::Map) => unimplemented!("`as_map` is not currently supported"),
@@ -636,20 +642,23 @@ mod msgpack {
             },
             None => quote_spanned! {field.span()=>
                 let mut #var_name: #field_type = None;
-                match #tarantool_crate::msgpack::Decode::decode(r, context) {
-                    Ok(val) => #var_name = Some(val),
-                    Err(err) => {
-                        let markered = err.source.get(err.source.len() - 33..).unwrap_or("")== "failed to read MessagePack marker";
-                        let nulled = if err.part.is_some() {
-                            err.part.as_ref().expect("Can't fail after a conditional check") == "got Null"
-                        } else {
-                            false
-                        };
+                // The trailing optional fields may be missing from the array
+                if len > #index {
+                    match #tarantool_crate::msgpack::Decode::decode(r, context) {
+                        Ok(val) => #var_name = Some(val),
+                        Err(err) => {
+                            let markered = err.source.get(err.source.len() - 33..).unwrap_or("")== "failed to read MessagePack marker";
+                            let nulled = if err.part.is_some() {
+                                err.part.as_ref().expect("Can't fail after a conditional check") == "got Null"
+                            } else {
+                                false
+                            };
 
-                        if !nulled && !markered {
-                            Err(#tarantool_crate::msgpack::DecodeError::new::<Self>(err).with_part(format!("{}", stringify!(#field_index))))?;
-                        }
-                    },
+                            if !nulled && !markered {
+                                Err(#tarantool_crate::msgpack::DecodeError::new::<Self>(err).with_part(format!("{}", stringify!(#field_index))))?;
+                            }
+                        },
+                    }
                 }
             },
         };
@@ -688,6 +697,57 @@ mod msgpack {
         out
     }
 
+    /// Generates code which checks that the array length stored in variable
+    /// `len` matches the number of `fields`. Trailing optional fields may be
+    /// missing if `allow_array_optionals` is set.
+    fn check_array_len<'a>(
+        fields: impl ExactSizeIterator<Item = &'a Field> + DoubleEndedIterator,
+        tarantool_crate: &Path,
+        allow_array_optionals: bool,
+    ) -> TokenStream {
+        let max = fields.len();
+        let mut has_optionals = false;
+        let mut trailing_optionals = 0;
+        for field in fields.rev() {
+            if !field.ty.is_option() {
+                break;
+            }
+            has_optionals = true;
+            trailing_optionals += 1;
+        }
+        let min = if allow_array_optionals {
+            max - trailing_optionals
+        } else {
+            max
+        };
+
+        let not_enough = if min == max {
+            format!("not enough fields, expected {}, got {{}}", min)
+        } else {
+            format!("not enough fields, expected at least {}, got {{}}", min)
+        };
+        let not_enough = if has_optionals && !allow_array_optionals {
+            format!("{} (note: optional fields must be explicitly null unless `allow_array_optionals` attribute is passed)", not_enough)
+        } else {
+            not_enough
+        };
+        let too_many = if min == max {
+            format!("too many fields, expected {}, got {{}}", max)
+        } else {
+            format!("too many fields, expected at most {}, got {{}}", max)
+        };
+        let min = min as u32;
+        let max = max as u32;
+        quote! {
+            if len < #min {
+                return Err(#tarantool_crate::msgpack::DecodeError::new::<Self>(format!(#not_enough, len)));
+            }
+            if len > #max {
+                return Err(#tarantool_crate::msgpack::DecodeError::new::<Self>(format!(#too_many, len)));
+            }
+        }
+    }
+
     pub fn decode_fields(
         data: &Data,
         tarantool_crate: &Path,
@@ -713,6 +773,11 @@ mod msgpack {
                             .as_ref()
                             .expect("not an unnamed struct")
                             .to_string();
+                        let check_len = check_array_len(
+                            fields.named.iter(),
+                            tarantool_crate,
+                            args.allow_array_optionals,
+                        );
                         let fields = decode_named_fields(fields, tarantool_crate, None, args);
                         quote! {
                             let as_map = match context.struct_style() {
@@ -720,14 +785,17 @@ mod msgpack {
                                 StructStyle::ForceAsMap => true,
                                 StructStyle::ForceAsArray => false,
                             };
-                            // TODO: Assert map and array len with number of struct fields
-                            if as_map {
+                            // TODO: Assert map len with number of struct fields
+                            #[allow(unused_variables)]
+                            let len = if as_map {
                                 #tarantool_crate::msgpack::rmp::decode::read_map_len(r)
-                                    .map_err(|err| #tarantool_crate::msgpack::DecodeError::from_vre::<Self>(err))?;
+                                    .map_err(|err| #tarantool_crate::msgpack::DecodeError::from_vre::<Self>(err))?
                             } else {
-                                #tarantool_crate::msgpack::rmp::decode::read_array_len(r)
+                                let len = #tarantool_crate::msgpack::rmp::decode::read_array_len(r)
                                     .map_err(|err| #tarantool_crate::msgpack::DecodeError::from_vre_with_field::<Self>(err, #first_field_name))?;
-                            }
+                                #check_len
+                                len
+                            };
                             #fields
                         }
                     }
@@ -752,11 +820,17 @@ mod msgpack {
                             }
                         }
 
+                        let check_len = check_array_len(
+                            fields.unnamed.iter(),
+                            tarantool_crate,
+                            args.allow_array_optionals,
+                        );
                         let fields = decode_unnamed_fields(fields, tarantool_crate, None, args);
                         quote! {
                             #option_key
-                            #tarantool_crate::msgpack::rmp::decode::read_array_len(r)
+                            let len = #tarantool_crate::msgpack::rmp::decode::read_array_len(r)
                                 .map_err(|err| #tarantool_crate::msgpack::DecodeError::from_vre::<Self>(err))?;
+                            #check_len
                             #fields
                         }
                     }
@@ -791,7 +865,8 @@ mod msgpack {
                                 // TODO: allow `#[encode(as_map)]` for struct variants
                                 quote! {
                                     #variant_repr => {
-                                        #tarantool_crate::msgpack::rmp::decode::read_array_len(r)
+                                        #[allow(unused_variables)]
+                                        let len = #tarantool_crate::msgpack::rmp::decode::read_array_len(r)
                                             .map_err(|err| #tarantool_crate::msgpack::DecodeError::from_vre::<Self>(err))?;
                                         let as_map = false;
                                         #fields
@@ -802,7 +877,8 @@ mod msgpack {
                                 let fields = decode_unnamed_fields(fields, tarantool_crate, Some(&variant.ident), args);
                                 quote! {
                                     #variant_repr => {
-                                        #tarantool_crate::msgpack::rmp::decode::read_array_len(r)
+                                        #[allow(unused_variables)]
+                                        let len = #tarantool_crate::msgpack::rmp::decode::read_array_len(r)
                                             .map_err(|err| #tarantool_crate::msgpack::DecodeError::from_vre::<Self>(err))?;
                                         let as_map = false;
                                         #fields
@@ -46,8 +46,12 @@ pub enum PrivType {
 /// This function is a wrapper around similarly named one in tarantool.
 /// It allows to run access check for the current user against
 /// specified space and access type. Most relevant access types are read and write.
-pub fn box_access_check_space(space_id: SpaceId, user_access: PrivType) -> crate::Result<()> {
-    let ret = unsafe { ffi::box_access_check_space(space_id, user_access as u16) };
+pub fn box_access_check_space(
+    space_id: impl Into<SpaceId>,
+    user_access: PrivType,
+) -> crate::Result<()> {
+    let space_id = space_id.into();
+    let ret = unsafe { ffi::box_access_check_space(space_id.0, user_access as u16) };
     if ret == -1 {
         Err(error::Error::Tarantool(error::TarantoolError::last()))
     } else {
@@ -7,17 +7,20 @@ use std::collections::VecDeque;
 use std::convert::TryFrom;
 use std::ffi::c_void;
 use std::io::{self, Read, Write};
-use std::mem::forget;
-use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::mem::{forget, ManuallyDrop};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
 use std::os::unix::io::{AsRawFd, IntoRawFd, RawFd};
+use std::pin::Pin;
 use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
 use std::time::Duration;
 
 use core::ptr::null_mut;
 
 use crate::error::{Error, TarantoolError};
 use crate::ffi::tarantool as ffi;
-use crate::fiber::{unpack_callback, Cond};
+use crate::fiber::{self, unpack_callback, Cond};
+use crate::time::Instant;
 
 const TIMEOUT_INFINITY: f64 = 365.0 * 86400.0 * 100.0;
 
@@ -160,6 +163,119 @@ impl TryFrom<TcpListener> for CoIOListener {
     }
 }
 
+/// Uses CoIO main loop to poll read/write events on a UDP socket.
+///
+/// ```no_run
+/// use tarantool::coio::CoIOUdpSocket;
+///
+/// let socket = CoIOUdpSocket::bind("127.0.0.1:0").unwrap();
+/// socket.send_to(b"requests:1|c", "127.0.0.1:8125").unwrap();
+/// ```
+pub struct CoIOUdpSocket {
+    // The socket is closed via `coio_close` in `Drop`.
+    inner: ManuallyDrop<UdpSocket>,
+}
+
+impl CoIOUdpSocket {
+    /// Creates a UDP socket bound to the given address.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self, io::Error> {
+        Self::try_from(UdpSocket::bind(addr)?)
+    }
+
+    /// Returns the local address this socket is bound to.
+    pub fn local_addr(&self) -> Result<SocketAddr, io::Error> {
+        self.inner.local_addr()
+    }
+
+    /// Sends a datagram to the given address. Yields if the socket isn't
+    /// ready for writing. Returns the number of bytes sent.
+    pub fn send_to<A: ToSocketAddrs>(&self, buf: &[u8], addr: A) -> Result<usize, io::Error> {
+        self.send_to_with_timeout(buf, addr, None)
+    }
+
+    /// Same as [`Self::send_to`], but returns an error of kind
+    /// [`io::ErrorKind::TimedOut`] if the socket didn't become ready for
+    /// writing within `timeout`.
+    pub fn send_to_with_timeout<A: ToSocketAddrs>(
+        &self,
+        buf: &[u8],
+        addr: A,
+        timeout: Option<Duration>,
+    ) -> Result<usize, io::Error> {
+        let addr = addr.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "no addresses to send data to")
+        })?;
+        self.with_wait(ffi::CoIOFlags::WRITE, timeout, |s| s.send_to(buf, addr))
+    }
+
+    /// Receives a single datagram. Yields until one is available. Returns the
+    /// number of bytes read and the address of the sender.
+    ///
+    /// If `buf` is too small to hold the datagram, the excess bytes are
+    /// discarded.
+    pub fn recv_from(&self, buf: &mut [u8]) -> Result<(usize, SocketAddr), io::Error> {
+        self.recv_from_with_timeout(buf, None)
+    }
+
+    /// Same as [`Self::recv_from`], but returns an error of kind
+    /// [`io::ErrorKind::TimedOut`] if no datagram arrived within `timeout`.
+    pub fn recv_from_with_timeout(
+        &self,
+        buf: &mut [u8],
+        timeout: Option<Duration>,
+    ) -> Result<(usize, SocketAddr), io::Error> {
+        self.with_wait(ffi::CoIOFlags::READ, timeout, |s| s.recv_from(buf))
+    }
+
+    pub fn inner_socket(&self) -> &UdpSocket {
+        &self.inner
+    }
+
+    fn with_wait<T>(
+        &self,
+        flags: ffi::CoIOFlags,
+        timeout: Option<Duration>,
+        mut f: impl FnMut(&UdpSocket) -> Result<T, io::Error>,
+    ) -> Result<T, io::Error> {
+        let deadline = timeout.map(|t| fiber::clock().saturating_add(t));
+        loop {
+            match f(&self.inner) {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    let timeout = match deadline {
+                        None => TIMEOUT_INFINITY,
+                        Some(deadline) => deadline.duration_since(fiber::clock()).as_secs_f64(),
+                    };
+                    coio_wait(self.inner.as_raw_fd(), flags, timeout)?;
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+impl TryFrom<UdpSocket> for CoIOUdpSocket {
+    type Error = io::Error;
+
+    fn try_from(value: UdpSocket) -> Result<Self, Self::Error> {
+        value.set_nonblocking(true)?;
+        Ok(Self {
+            inner: ManuallyDrop::new(value),
+        })
+    }
+}
+
+impl AsRawFd for CoIOUdpSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl Drop for CoIOUdpSocket {
+    fn drop(&mut self) {
+        unsafe { ffi::coio_close(self.inner.as_raw_fd()) };
+    }
+}
+
 /// Wait until `READ` or `WRITE` event on socket (`fd`). Yields.
 ///
 /// - `fd` - non-blocking socket file description
@@ -232,6 +348,61 @@ pub unsafe fn getaddrinfo(
     }
 }
 
+/// Pull some bytes from the non-blocking file descriptor `fd` into the
+/// specified buffer, waiting (yielding the current fiber) for the data to
+/// become available until the `deadline`.
+///
+/// Returns how many bytes were read, or an error of kind
+/// [`io::ErrorKind::TimedOut`] if no data was available before the `deadline`.
+///
+/// The `deadline` is measured by [`fiber::clock`](crate::fiber::clock).
+pub fn read_with_deadline(fd: RawFd, buf: &mut [u8], deadline: Instant) -> io::Result<usize> {
+    loop {
+        let result = unsafe { libc::read(fd, buf.as_mut_ptr() as *mut c_void, buf.len()) };
+        if result >= 0 {
+            return Ok(result as usize);
+        }
+
+        let err = io::Error::last_os_error();
+        match err.kind() {
+            io::ErrorKind::Interrupted => continue,
+            io::ErrorKind::WouldBlock => {}
+            _ => return Err(err),
+        }
+
+        let timeout = deadline.duration_since(fiber::clock());
+        coio_wait(fd, ffi::CoIOFlags::READ, timeout.as_secs_f64())?;
+    }
+}
+
+/// Write a buffer into the non-blocking file descriptor `fd`, waiting
+/// (yielding the current fiber) for it to become writable until the
+/// `deadline`.
+///
+/// Returns how many bytes were written, or an error of kind
+/// [`io::ErrorKind::TimedOut`] if `fd` didn't become writable before the
+/// `deadline`.
+///
+/// The `deadline` is measured by [`fiber::clock`](crate::fiber::clock).
+pub fn write_with_deadline(fd: RawFd, buf: &[u8], deadline: Instant) -> io::Result<usize> {
+    loop {
+        let result = unsafe { libc::write(fd, buf.as_ptr() as *const c_void, buf.len()) };
+        if result >= 0 {
+            return Ok(result as usize);
+        }
+
+        let err = io::Error::last_os_error();
+        match err.kind() {
+            io::ErrorKind::Interrupted => continue,
+            io::ErrorKind::WouldBlock => {}
+            _ => return Err(err),
+        }
+
+        let timeout = deadline.duration_since(fiber::clock());
+        coio_wait(fd, ffi::CoIOFlags::WRITE, timeout.as_secs_f64())?;
+    }
+}
+
 #[inline(always)]
 pub(crate) fn read(
     fd: RawFd,
@@ -376,3 +547,238 @@ struct Chan<T> {
     tx_count: Cell<usize>,
     rx_is_active: Cell<bool>,
 }
+
+/// Default capacity of the buffer of a [`pipe`].
+pub const PIPE_DEFAULT_CAPACITY: usize = 64 * 1024;
+
+/// Creates an in-process pipe for streaming bytes from one fiber to another,
+/// returning the reader/writer halves.
+///
+/// The data is passed through a buffer of [`PIPE_DEFAULT_CAPACITY`] bytes,
+/// see [`pipe_with_capacity`] for specifying a different capacity.
+///
+/// Both halves implement the blocking [`Read`]/[`Write`] traits, which yield
+/// the calling fiber until the operation can proceed, and the
+/// [`futures::AsyncRead`]/[`futures::AsyncWrite`] traits, which can be used
+/// with the [`fiber::block_on`] executor.
+///
+/// Reading returns `0` bytes (end of file) once the writer is dropped and the
+/// buffered data is consumed. Writing returns
+/// [`io::ErrorKind::BrokenPipe`] once the reader is dropped. If the fiber is
+/// cancelled while blocked in a [`Read`]/[`Write`] method, the method returns
+/// an [`io::ErrorKind::Other`] error.
+#[inline(always)]
+pub fn pipe() -> (PipeReader, PipeWriter) {
+    pipe_with_capacity(PIPE_DEFAULT_CAPACITY)
+}
+
+/// Same as [`pipe`], but the buffer holds at most `capacity` bytes.
+///
+/// # Panics
+/// Panics if `capacity` is 0.
+pub fn pipe_with_capacity(capacity: usize) -> (PipeReader, PipeWriter) {
+    assert!(capacity > 0, "pipe capacity must be positive");
+    let pipe = Rc::new(Pipe {
+        buffer: RefCell::new(VecDeque::with_capacity(capacity)),
+        capacity,
+        readable: Cond::new(),
+        writable: Cond::new(),
+        reader_waker: Cell::new(None),
+        writer_waker: Cell::new(None),
+        reader_closed: Cell::new(false),
+        writer_closed: Cell::new(false),
+    });
+
+    (PipeReader(pipe.clone()), PipeWriter(pipe))
+}
+
+/// The error returned by the blocking pipe operations if the fiber is
+/// cancelled. It must not be [`io::ErrorKind::Interrupted`], because
+/// [`Read::read_to_end`], [`Write::write_all`] and the like retry the
+/// operation on such errors.
+#[inline(always)]
+fn fiber_cancelled() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "fiber is cancelled")
+}
+
+/// The reading half of a [`pipe`].
+#[derive(Debug)]
+pub struct PipeReader(Rc<Pipe>);
+
+impl PipeReader {
+    /// Reads the buffered data into `buf` or returns `None` if there's no
+    /// data and the writer is still alive.
+    fn try_read(&self, buf: &mut [u8]) -> Option<usize> {
+        let pipe = &self.0;
+        let n = {
+            let mut buffer = pipe.buffer.borrow_mut();
+            if buffer.is_empty() {
+                if pipe.writer_closed.get() || buf.is_empty() {
+                    return Some(0);
+                }
+                return None;
+            }
+            let n = buf.len().min(buffer.len());
+            for (dst, src) in buf.iter_mut().zip(buffer.drain(..n)) {
+                *dst = src;
+            }
+            n
+        };
+        pipe.notify_writer();
+        Some(n)
+    }
+}
+
+impl Read for PipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if let Some(n) = self.try_read(buf) {
+                return Ok(n);
+            }
+            if !self.0.readable.wait() {
+                return Err(fiber_cancelled());
+            }
+        }
+    }
+}
+
+impl futures::AsyncRead for PipeReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if let Some(n) = self.try_read(buf) {
+            return Poll::Ready(Ok(n));
+        }
+        self.0.reader_waker.set(Some(cx.waker().clone()));
+        Poll::Pending
+    }
+}
+
+impl Drop for PipeReader {
+    fn drop(&mut self) {
+        self.0.reader_closed.set(true);
+        self.0.notify_writer();
+    }
+}
+
+/// The writing half of a [`pipe`].
+#[derive(Debug)]
+pub struct PipeWriter(Rc<Pipe>);
+
+impl PipeWriter {
+    /// Writes as much of `buf` as fits into the buffer or returns `None` if
+    /// the buffer is full and the reader is still alive.
+    fn try_write(&self, buf: &[u8]) -> Option<io::Result<usize>> {
+        let pipe = &self.0;
+        if pipe.reader_closed.get() || pipe.writer_closed.get() {
+            return Some(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        if buf.is_empty() {
+            return Some(Ok(0));
+        }
+        let n = {
+            let mut buffer = pipe.buffer.borrow_mut();
+            let n = buf.len().min(pipe.capacity - buffer.len());
+            if n == 0 {
+                return None;
+            }
+            buffer.extend(&buf[..n]);
+            n
+        };
+        pipe.notify_reader();
+        Some(Ok(n))
+    }
+}
+
+impl Write for PipeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        loop {
+            if let Some(res) = self.try_write(buf) {
+                return res;
+            }
+            if !self.0.writable.wait() {
+                return Err(fiber_cancelled());
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn flush(&mut self) -> io::Result<()> {
+        // The data becomes available to the reader as soon as it's written
+        Ok(())
+    }
+}
+
+impl futures::AsyncWrite for PipeWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if let Some(res) = self.try_write(buf) {
+            return Poll::Ready(res);
+        }
+        self.0.writer_waker.set(Some(cx.waker().clone()));
+        Poll::Pending
+    }
+
+    #[inline(always)]
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.0.writer_closed.set(true);
+        self.0.notify_reader();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Drop for PipeWriter {
+    fn drop(&mut self) {
+        self.0.writer_closed.set(true);
+        self.0.notify_reader();
+    }
+}
+
+struct Pipe {
+    buffer: RefCell<VecDeque<u8>>,
+    capacity: usize,
+    /// Signalled when data is written or the writer is closed.
+    readable: Cond,
+    /// Signalled when data is read or the reader is closed.
+    writable: Cond,
+    reader_waker: Cell<Option<Waker>>,
+    writer_waker: Cell<Option<Waker>>,
+    reader_closed: Cell<bool>,
+    writer_closed: Cell<bool>,
+}
+
+impl Pipe {
+    fn notify_reader(&self) {
+        self.readable.signal();
+        if let Some(waker) = self.reader_waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn notify_writer(&self) {
+        self.writable.signal();
+        if let Some(waker) = self.writer_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl std::fmt::Debug for Pipe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pipe")
+            .field("len", &self.buffer.borrow().len())
+            .field("capacity", &self.capacity)
+            .field("reader_closed", &self.reader_closed.get())
+            .field("writer_closed", &self.writer_closed.get())
+            .finish_non_exhaustive()
+    }
+}
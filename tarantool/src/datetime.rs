@@ -41,7 +41,7 @@ impl Datetime {
     /// Convert an array of bytes (internal tarantool msgpack ext)
     /// in the little endian order into a `DateTime`.
     #[inline(always)]
-    fn from_bytes_tt(bytes: &[u8]) -> Result<Self, Error> {
+    pub(crate) fn from_bytes_tt(bytes: &[u8]) -> Result<Self, Error> {
         let mut sec_bytes: [u8; 8] = [0; 8];
         sec_bytes.copy_from_slice(&bytes[0..8]);
 
@@ -285,14 +285,16 @@ mod tarantool_decimal {
         }
     }
 
-    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-    pub struct DecimalFromStrError;
+    use super::DecimalFromStrError;
 
     impl std::str::FromStr for Decimal {
         type Err = DecimalFromStrError;
 
         #[inline(always)]
         fn from_str(s: &str) -> Result<Self, Self::Err> {
+            if s.contains('\0') {
+                return Err(DecimalFromStrError::new(s));
+            }
             // The underlying `decNumberFromString` api only supports null
             // terminated strings so there is no way to avoid a copy here
             // Therefore you should use `std::ffi::CStr` whenever possible
@@ -321,7 +323,7 @@ mod tarantool_decimal {
                 let res = ffi::decimal_from_string(dec.as_mut_ptr(), s.as_ptr());
 
                 if res.is_null() {
-                    Err(DecimalFromStrError)
+                    Err(DecimalFromStrError::new(&s.to_string_lossy()))
                 } else {
                     Ok(Self::from_raw(dec.assume_init()))
                 }
@@ -998,8 +1000,7 @@ mod standalone_decimal {
         }
     }
 
-    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-    pub struct DecimalFromStrError;
+    use super::DecimalFromStrError;
 
     impl std::str::FromStr for Decimal {
         type Err = DecimalFromStrError;
@@ -1009,7 +1010,7 @@ mod standalone_decimal {
             with_context(|ctx| ctx.parse(s).ok())
                 .flatten()
                 .and_then(|d| Self::try_from(d).ok())
-                .ok_or(DecimalFromStrError)
+                .ok_or_else(|| DecimalFromStrError::new(s))
         }
     }
 
@@ -1028,7 +1029,7 @@ mod standalone_decimal {
         #[inline(always)]
         fn try_from(s: &std::ffi::CStr) -> Result<Self, Self::Error> {
             s.to_str()
-                .map_err(|_| DecimalFromStrError)
+                .map_err(|_| DecimalFromStrError::new(&s.to_string_lossy()))
                 .and_then(str::parse)
         }
     }
@@ -1221,8 +1222,120 @@ impl Decimal {
     pub fn to_u64(self) -> Option<u64> {
         std::convert::TryInto::try_into(self).ok()
     }
+
+    /// Format `self` as a plain decimal string, which never uses the exponent
+    /// notation, e.g. `"0.00000012"` instead of `"1.2E-7"`. This is useful
+    /// for example when the value is sent to a system which doesn't support
+    /// the exponent notation (like some JSON parsers).
+    ///
+    /// Unlike [`Display`](std::fmt::Display) the notation doesn't depend on
+    /// the magnitude of the number. The result doesn't depend on the locale.
+    pub fn to_plain_string(&self) -> String {
+        to_plain_string(&self.to_string())
+    }
+}
+
+/// Converts the string produced by `Display` for `Decimal` into a plain
+/// decimal string without the exponent.
+fn to_plain_string(s: &str) -> String {
+    let Some(e) = s.find(['E', 'e']) else {
+        return s.to_owned();
+    };
+    let exponent: i64 = s[e + 1..]
+        .parse()
+        .expect("decimal exponent is always a valid integer");
+    let mantissa = &s[..e];
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = [int_part, frac_part].concat();
+
+    // Position of the decimal point relative to the first digit
+    let point = int_part.len() as i64 + exponent;
+    let mut res = String::with_capacity(digits.len() + point.unsigned_abs() as usize + 3);
+    res.push_str(sign);
+    if point <= 0 {
+        res.push_str("0.");
+        res.extend(std::iter::repeat('0').take(point.unsigned_abs() as _));
+        res.push_str(&digits);
+    } else if point as usize >= digits.len() {
+        res.push_str(&digits);
+        res.extend(std::iter::repeat('0').take(point as usize - digits.len()));
+    } else {
+        let (int_digits, frac_digits) = digits.split_at(point as _);
+        res.push_str(int_digits);
+        res.push('.');
+        res.push_str(frac_digits);
+    }
+    res
+}
+
+/// Error returned when parsing a [`Decimal`] from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecimalFromStrError {
+    input: String,
+    reason: &'static str,
+}
+
+impl DecimalFromStrError {
+    /// Maximum number of characters of the input kept in the error.
+    const MAX_INPUT_CHARS: usize = 64;
+
+    fn new(input: &str) -> Self {
+        let reason = if input.is_empty() {
+            "the string is empty"
+        } else if input.contains('\0') {
+            "the string contains a nul byte"
+        } else if matches!(
+            input
+                .trim_start_matches(['+', '-'])
+                .to_ascii_lowercase()
+                .as_str(),
+            "inf" | "infinity" | "nan" | "snan"
+        ) {
+            "infinity and NaN are not supported"
+        } else if input.parse::<f64>().is_ok() {
+            "the number is out of range"
+        } else {
+            "expected a number like \"-12.34\" or \"1.5e10\""
+        };
+
+        let mut chars = input.chars();
+        let mut input: String = chars.by_ref().take(Self::MAX_INPUT_CHARS).collect();
+        if chars.next().is_some() {
+            input.push_str("...");
+        }
+        Self { input, reason }
+    }
+
+    /// The string which failed to parse. Long strings are truncated and end
+    /// with `"..."`.
+    #[inline(always)]
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// The reason why the string isn't a valid decimal.
+    #[inline(always)]
+    pub fn reason(&self) -> &str {
+        self.reason
+    }
 }
 
+impl std::fmt::Display for DecimalFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid decimal string {:?}: {}",
+            self.input, self.reason
+        )
+    }
+}
+
+impl std::error::Error for DecimalFromStrError {}
+
 impl std::cmp::Ord for Decimal {
     #[inline(always)]
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
@@ -1424,6 +1537,62 @@ mod tests {
         assert_eq!("inf".parse::<Decimal>().ok(), None::<Decimal>);
         assert_eq!("infinity".parse::<Decimal>().ok(), None::<Decimal>);
         assert_eq!("NaN".parse::<Decimal>().ok(), None::<Decimal>);
+
+        // A nul byte doesn't terminate the string
+        assert_eq!("1\x002".parse::<Decimal>().ok(), None::<Decimal>);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn from_string_error() {
+        let err = |s: &str| s.parse::<Decimal>().unwrap_err().to_string();
+        assert_eq!(
+            err("foobar"),
+            r#"invalid decimal string "foobar": expected a number like "-12.34" or "1.5e10""#
+        );
+        assert_eq!(err(""), r#"invalid decimal string "": the string is empty"#);
+        assert_eq!(
+            err("-inf"),
+            r#"invalid decimal string "-inf": infinity and NaN are not supported"#
+        );
+        assert_eq!(
+            err("1e9999999999"),
+            r#"invalid decimal string "1e9999999999": the number is out of range"#
+        );
+
+        // Long inputs are truncated
+        let long = "1".repeat(100) + "x";
+        let e = long.parse::<Decimal>().unwrap_err();
+        assert_eq!(e.input(), format!("{}...", "1".repeat(64)));
+        assert_eq!(e.reason(), r#"expected a number like "-12.34" or "1.5e10""#);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn to_plain_string() {
+        let cases = [
+            ("-8.11", "-8.11"),
+            ("1e-20", "0.00000000000000000001"),
+            ("-1.2e-7", "-0.00000012"),
+            ("1.5e30", "1500000000000000000000000000000"),
+            ("1e37", "10000000000000000000000000000000000000"),
+            (
+                "-123456789012345678901234567890",
+                "-123456789012345678901234567890",
+            ),
+            ("0.000001", "0.000001"),
+        ];
+        for (input, expected) in cases {
+            let d = Decimal::try_from(input).unwrap();
+            let plain = d.to_plain_string();
+            assert_eq!(plain, expected);
+            assert!(!plain.contains(['e', 'E']));
+            assert_eq!(Decimal::try_from(plain.as_str()).unwrap(), d);
+        }
+
+        let e = Decimal::try_from("1.2.3").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            r#"invalid decimal string "1.2.3": expected a number like "-12.34" or "1.5e10""#
+        );
     }
 
     #[crate::test(tarantool = "crate")]
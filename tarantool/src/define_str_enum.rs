@@ -83,6 +83,10 @@ impl<E: Debug> std::error::Error for UnknownEnumVariant<E> {}
 ///         }
 ///     }
 ///
+///     pub const fn values() -> &'static [&'static str] {
+///         &["#FF0000", "#00FF00", "#0000FF"]
+///     }
+///
 ///     // ... for full list see the implementation.
 /// }
 /// ```
@@ -115,11 +119,98 @@ impl<E: Debug> std::error::Error for UnknownEnumVariant<E> {}
 /// assert_eq!(Season::from_str("  SUMMER  "), Ok(Season::Summer));
 /// ```
 ///
+/// # Numeric codes
+///
+/// Each variant can optionally be assigned a numeric code, which becomes the
+/// variant's discriminant. This is useful for enums which correspond to
+/// tarantool's internal numeric constants.
+///
+/// If at least one variant has an explicit numeric code, the generated
+/// [`msgpack::Decode`](crate::msgpack::Decode) implementation accepts msgpack
+/// integers as well as strings. If every variant has an explicit numeric code,
+/// `From<Enum> for u32` and `From<Enum> for i64` are also generated.
+///
+/// ```
+/// # use tarantool::define_str_enum;
+/// # use std::convert::TryFrom;
+/// define_str_enum! {
+///     pub enum Code {
+///         Foo = "foo" = 1,
+///         Bar = "bar" = 2,
+///     }
+/// }
+///
+/// assert_eq!(u32::from(Code::Bar), 2);
+/// assert_eq!(Code::try_from(1_u32), Ok(Code::Foo));
+/// assert_eq!(Code::try_from(3_u32), Err(3));
+/// ```
+///
 /// [`serde::Deserialize<'de>`]: https://docs.rs/serde/latest/serde/trait.Deserialize.html
 /// [`serde::Serialize`]: https://docs.rs/serde/latest/serde/trait.Serialize.html
 // TODO: make this into a derive macro
 macro_rules! define_str_enum {
     (
+        $(#![$macro_attr:ident])?
+        $(#[$emeta:meta])*
+        $vis:vis enum $enum:ident {
+            $(
+                $(#[$varmeta:meta])*
+                $variant:ident = $display:literal = $num:literal,
+            )+
+        }
+    ) => {
+        $crate::define_str_enum! {
+            @define
+            $(#![$macro_attr])?
+            $(#[$emeta])*
+            $vis enum $enum {
+                $(
+                    $(#[$varmeta])*
+                    $variant = $display = $num,
+                )+
+            }
+        }
+
+        impl ::std::convert::From<$enum> for u32 {
+            #[inline(always)]
+            fn from(e: $enum) -> Self {
+                e as _
+            }
+        }
+
+        impl ::std::convert::From<$enum> for i64 {
+            #[inline(always)]
+            fn from(e: $enum) -> Self {
+                e as _
+            }
+        }
+    };
+
+    (
+        $(#![$macro_attr:ident])?
+        $(#[$emeta:meta])*
+        $vis:vis enum $enum:ident {
+            $(
+                $(#[$varmeta:meta])*
+                $variant:ident = $display:literal $(= $num:literal)?,
+            )+
+        }
+    ) => {
+        $crate::define_str_enum! {
+            @define
+            $(#![$macro_attr])?
+            $(#[$emeta])*
+            $vis enum $enum {
+                $(
+                    $(#[$varmeta])*
+                    $variant = $display $(= $num)?,
+                )+
+            }
+        }
+    };
+
+    (
+        @define
         $(#![$macro_attr:ident])?
         $(#[$emeta:meta])*
         $vis:vis enum $enum:ident {
@@ -161,11 +252,22 @@ macro_rules! define_str_enum {
                 }
             }
 
-            /// Returns a slice of string values of all the variants of `Self`.
+            /// Returns a slice of string values of all the variants of `Self`
+            /// in the order of declaration, i.e. in the same order as
+            /// [`Self::VARIANTS`].
+            ///
+            /// Can be used for listing the allowed values, e.g. in the help
+            /// text of a command line option.
             #[inline(always)]
             $vis const fn values() -> &'static [&'static str] {
                 &[ $( $display, )+ ]
             }
+
+            /// If this is `true` then at least one of the variants has an
+            /// explicit numeric code and the enum can be decoded from a
+            /// msgpack integer as well as from a string.
+            $vis const HAS_NUMERIC_CODES: bool =
+                false $( || $crate::define_str_enum!(@has_num $($num)?) )+;
         }
 
         impl ::std::convert::AsRef<str> for $enum {
@@ -308,6 +410,24 @@ macro_rules! define_str_enum {
             fn decode(r: &mut &'de [u8], _context: &$crate::msgpack::Context) -> std::result::Result<Self, $crate::msgpack::DecodeError> {
                 use $crate::msgpack::rmp;
 
+                if Self::HAS_NUMERIC_CODES {
+                    let is_int = match r.first().map(|&b| rmp::Marker::from_u8(b)) {
+                        Some(rmp::Marker::FixPos(_) | rmp::Marker::FixNeg(_)) => true,
+                        Some(rmp::Marker::U8 | rmp::Marker::U16 | rmp::Marker::U32 | rmp::Marker::U64) => true,
+                        Some(rmp::Marker::I8 | rmp::Marker::I16 | rmp::Marker::I32 | rmp::Marker::I64) => true,
+                        _ => false,
+                    };
+                    if is_int {
+                        let n: i64 = rmp::decode::read_int(r)
+                            .map_err(|err| $crate::msgpack::DecodeError::new::<Self>(err))?;
+                        return Self::from_i64(n).ok_or_else(|| {
+                            $crate::msgpack::DecodeError::new::<Self>(
+                                format!("unknown enum variant code `{}`", n)
+                            )
+                        });
+                    }
+                }
+
                 let len = rmp::decode::read_str_len(r)
                     .map_err(|err| $crate::msgpack::DecodeError::new::<Self>(err))?;
                 let decoded_variant = r.get(0..(len as usize))
@@ -331,6 +451,9 @@ macro_rules! define_str_enum {
         }
     };
 
+    (@has_num $num:literal) => { true };
+    (@has_num) => { false };
+
     (@attr coerce_from_str $($then:tt)*) => {
         $($then)*
     };
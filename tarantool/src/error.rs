@@ -101,6 +101,18 @@ pub enum Error {
     #[error("space metadata not found")]
     MetaNotFound,
 
+    /// A space with the given name doesn't exist.
+    ///
+    /// See [`Space::find_or_err`](crate::space::Space::find_or_err).
+    #[error("space `{name}` not found")]
+    SpaceNotFound { name: String },
+
+    /// An index with the given name doesn't exist in the space.
+    ///
+    /// See [`Space::index_or_err`](crate::space::Space::index_or_err).
+    #[error("index `{name}` not found in space {space_id}")]
+    IndexNotFound { space_id: u32, name: String },
+
     #[error("msgpack encode error: {0}")]
     MsgpackEncode(#[from] crate::msgpack::EncodeError),
 
@@ -111,6 +123,12 @@ pub enum Error {
     #[error("{0}")]
     ConnectionClosed(Arc<Error>),
 
+    /// A transaction was aborted, because the fiber yielded inside it.
+    ///
+    /// See [`TransactionError::Yielded`].
+    #[error("transaction aborted, because the fiber yielded inside it (e.g. in fiber::sleep, a network request or an `.await`), memtx transactions can only yield if `memtx_use_mvcc_engine` is enabled: {0}")]
+    TransactionYielded(BoxError),
+
     /// This should only be used if the error doesn't fall into one of the above
     /// categories.
     #[error("{0}")]
@@ -141,6 +159,58 @@ impl Error {
         }
     }
 
+    /// Returns the name of the space or index which wasn't found, if this is
+    /// a [`Error::SpaceNotFound`] or [`Error::IndexNotFound`] error.
+    #[inline]
+    pub fn missing_name(&self) -> Option<&str> {
+        match self {
+            Self::SpaceNotFound { name } | Self::IndexNotFound { name, .. } => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the operation which failed with this error may
+    /// succeed if it is retried later, for example after a timeout, a
+    /// connection failure or a leader switch during failover.
+    ///
+    /// For errors returned by tarantool the decision is based on the error
+    /// code, see [`TarantoolErrorCode::is_retriable`] for the list of codes
+    /// which are considered transient. [`Error::ConnectionClosed`] is
+    /// retriable unless the connection was closed because of an
+    /// authentication or access error (e.g. a wrong password). Encoding,
+    /// decoding and other logic errors are never retriable.
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Self::Tarantool(e) | Self::Remote(e) => TarantoolErrorCode::from_u32(e.error_code())
+                .is_some_and(TarantoolErrorCode::is_retriable),
+            Self::IO(e) => matches!(
+                e.kind(),
+                io::ErrorKind::TimedOut
+                    | io::ErrorKind::WouldBlock
+                    | io::ErrorKind::Interrupted
+                    | io::ErrorKind::ConnectionRefused
+                    | io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::NotConnected
+                    | io::ErrorKind::BrokenPipe
+            ),
+            #[cfg(feature = "network_client")]
+            Self::Tcp(e) => matches!(
+                **e,
+                crate::network::client::tcp::Error::Connect { .. }
+                    | crate::network::client::tcp::Error::Timeout
+            ),
+            Self::ConnectionClosed(e) => match &**e {
+                Self::Tarantool(e) | Self::Remote(e) => {
+                    !TarantoolErrorCode::from_u32(e.error_code())
+                        .is_some_and(TarantoolErrorCode::is_auth_error)
+                }
+                _ => true,
+            },
+            _ => false,
+        }
+    }
+
     /// Returns the name of the variant as it is spelled in the source code.
     pub const fn variant_name(&self) -> &'static str {
         match self {
@@ -159,9 +229,12 @@ impl Error {
             Self::Tcp(_) => "Tcp",
             Self::LuaError(_) => "LuaError",
             Self::MetaNotFound => "MetaNotFound",
+            Self::SpaceNotFound { .. } => "SpaceNotFound",
+            Self::IndexNotFound { .. } => "IndexNotFound",
             Self::MsgpackEncode(_) => "MsgpackEncode",
             Self::MsgpackDecode(_) => "MsgpackDecode",
             Self::ConnectionClosed(_) => "ConnectionClosed",
+            Self::TransactionYielded(_) => "TransactionYielded",
             Self::Other(_) => "Other",
         }
     }
@@ -197,6 +270,7 @@ where
             TransactionError::FailedToCommit(e) => e.into(),
             TransactionError::FailedToRollback(e) => e.into(),
             TransactionError::RolledBack(e) => e.into(),
+            TransactionError::Yielded(e) => Error::TransactionYielded(e),
             TransactionError::AlreadyStarted => BoxError::new(
                 TarantoolErrorCode::ActiveTransaction,
                 "transaction has already been started",
@@ -347,6 +421,27 @@ impl BoxError {
         Self::maybe_last().err().unwrap()
     }
 
+    /// Get the information about the last API call error and clear it, so
+    /// that the subsequent calls to [`Self::maybe_last`] return `Ok(())`
+    /// until a new error is set. Returns `None` if error was not set.
+    ///
+    /// This is useful for checking whether a specific tarantool call has set
+    /// an error.
+    #[inline]
+    pub fn take() -> Option<Self> {
+        let res = Self::maybe_last().err();
+        if res.is_some() {
+            Self::clear();
+        }
+        res
+    }
+
+    /// Clear the last API call error. Same as [`clear_error`].
+    #[inline(always)]
+    pub fn clear() {
+        clear_error()
+    }
+
     /// Set `self` as the last API call error.
     /// Useful when returning errors from stored prcoedures.
     #[inline(always)]
@@ -950,6 +1045,23 @@ const _: () = {
 };
 
 impl TarantoolErrorCode {
+    /// Returns the variant corresponding to the raw error `code` or `None` if
+    /// it's not a known tarantool error code.
+    ///
+    /// Unknown codes are either custom ones (see [`BoxError::new`]) or ones
+    /// added in a newer tarantool version, so they should be handled as raw
+    /// `u32`, e.g. the value of [`BoxError::error_code`].
+    #[inline(always)]
+    pub const fn from_u32(code: u32) -> Option<Self> {
+        Self::from_i64(code as _)
+    }
+
+    /// Returns the raw value of the error code.
+    #[inline(always)]
+    pub const fn as_u32(self) -> u32 {
+        self as _
+    }
+
     pub fn try_last() -> Option<Self> {
         unsafe {
             let e_ptr = ffi::box_error_last();
@@ -957,13 +1069,93 @@ impl TarantoolErrorCode {
                 return None;
             }
             let u32_code = ffi::box_error_code(e_ptr);
-            TarantoolErrorCode::from_i64(u32_code as _)
+            TarantoolErrorCode::from_u32(u32_code)
         }
     }
 
     pub fn last() -> Self {
         Self::try_last().unwrap()
     }
+
+    /// Returns `true` if the error with this code is transient, i.e. the
+    /// request which caused it may succeed if retried later.
+    ///
+    /// The following codes are considered transient:
+    /// - timeouts: [`Timeout`], [`VyQuotaTimeout`], [`TransactionTimeout`],
+    ///   [`SyncQuorumTimeout`];
+    /// - connection problems: [`NoConnection`];
+    /// - instance is not ready or is temporarily read-only (e.g. during
+    ///   failover): [`Readonly`], [`Loading`], [`BootstrapReadonly`],
+    ///   [`Unconfigured`], [`NotLeader`], [`TooEarlySubscribe`];
+    /// - conflicts with concurrent activity: [`TransactionConflict`],
+    ///   [`SyncRollback`], [`SchemaUpdateInProgress`],
+    ///   [`CheckpointInProgress`], [`InterferingPromote`],
+    ///   [`InterferingElections`], [`NoElectionQuorum`].
+    ///
+    /// All other codes are considered permanent. Note that [`WalIo`] is not
+    /// considered transient either, because it usually means a disk fault,
+    /// which requires an intervention.
+    ///
+    /// [`Timeout`]: Self::Timeout
+    /// [`VyQuotaTimeout`]: Self::VyQuotaTimeout
+    /// [`TransactionTimeout`]: Self::TransactionTimeout
+    /// [`SyncQuorumTimeout`]: Self::SyncQuorumTimeout
+    /// [`NoConnection`]: Self::NoConnection
+    /// [`Readonly`]: Self::Readonly
+    /// [`Loading`]: Self::Loading
+    /// [`BootstrapReadonly`]: Self::BootstrapReadonly
+    /// [`Unconfigured`]: Self::Unconfigured
+    /// [`NotLeader`]: Self::NotLeader
+    /// [`TooEarlySubscribe`]: Self::TooEarlySubscribe
+    /// [`TransactionConflict`]: Self::TransactionConflict
+    /// [`SyncRollback`]: Self::SyncRollback
+    /// [`SchemaUpdateInProgress`]: Self::SchemaUpdateInProgress
+    /// [`CheckpointInProgress`]: Self::CheckpointInProgress
+    /// [`InterferingPromote`]: Self::InterferingPromote
+    /// [`InterferingElections`]: Self::InterferingElections
+    /// [`NoElectionQuorum`]: Self::NoElectionQuorum
+    /// [`WalIo`]: Self::WalIo
+    pub const fn is_retriable(self) -> bool {
+        matches!(
+            self,
+            Self::Timeout
+                | Self::VyQuotaTimeout
+                | Self::TransactionTimeout
+                | Self::SyncQuorumTimeout
+                | Self::NoConnection
+                | Self::Readonly
+                | Self::Loading
+                | Self::BootstrapReadonly
+                | Self::Unconfigured
+                | Self::NotLeader
+                | Self::TooEarlySubscribe
+                | Self::TransactionConflict
+                | Self::SyncRollback
+                | Self::SchemaUpdateInProgress
+                | Self::CheckpointInProgress
+                | Self::InterferingPromote
+                | Self::InterferingElections
+                | Self::NoElectionQuorum
+        )
+    }
+
+    /// Returns `true` if the error with this code means that the user failed
+    /// to authenticate or isn't allowed to perform the request. Such errors
+    /// are permanent, retrying the request won't help.
+    const fn is_auth_error(self) -> bool {
+        matches!(
+            self,
+            Self::AccessDenied
+                | Self::NoSuchUser
+                | Self::PasswordMismatch
+                | Self::UnknownAuthMethod
+                | Self::InvalidAuthData
+                | Self::InvalidAuthRequest
+                | Self::PasswordExpired
+                | Self::AuthDelay
+                | Self::AuthRequired
+        )
+    }
 }
 
 impl From<TarantoolErrorCode> for u32 {
@@ -973,6 +1165,56 @@ impl From<TarantoolErrorCode> for u32 {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// ResultExt
+////////////////////////////////////////////////////////////////////////////////
+
+/// Extension methods for [`Result`].
+pub trait ResultExt<T> {
+    /// Returns the success value or sets the error as the current fiber's last
+    /// error with the given `code` and returns `None`.
+    ///
+    /// This is useful in raw stored procedures (i.e. ones not defined via
+    /// [`tarantool::proc`]), which must set the last error and return `-1` in
+    /// case of failure.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::os::raw::c_int;
+    /// use tarantool::error::{ResultExt, TarantoolErrorCode};
+    /// use tarantool::tuple::{FunctionArgs, FunctionCtx};
+    ///
+    /// #[no_mangle]
+    /// unsafe extern "C" fn raw_proc(ctx: FunctionCtx, args: FunctionArgs) -> c_int {
+    ///     let f = || {
+    ///         let (x,): (i32,) = args.decode().or_set_error(TarantoolErrorCode::ProcC)?;
+    ///         ctx.return_mp(&(x + 1)).or_set_error(TarantoolErrorCode::ProcC)
+    ///     };
+    ///     f().unwrap_or(-1)
+    /// }
+    /// ```
+    ///
+    /// [`tarantool::proc`]: macro@crate::proc
+    fn or_set_error(self, code: impl Into<u32>) -> Option<T>;
+}
+
+impl<T, E> ResultExt<T> for std::result::Result<T, E>
+where
+    E: Display,
+{
+    #[inline]
+    #[track_caller]
+    fn or_set_error(self, code: impl Into<u32>) -> Option<T> {
+        match self {
+            Ok(v) => Some(v),
+            Err(e) => {
+                BoxError::new(code, e.to_string()).set_last();
+                None
+            }
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // ...
 ////////////////////////////////////////////////////////////////////////////////
@@ -1053,10 +1295,71 @@ fn tarantool_error_doesnt_depend_on_link_error() {
     assert!(!format!("{}", err).is_empty());
 }
 
+#[test]
+fn tarantool_error_code_u32_conversions() {
+    let known = [
+        (0, TarantoolErrorCode::Unknown),
+        (3, TarantoolErrorCode::TupleFound),
+        (33, TarantoolErrorCode::NoSuchProc),
+        (78, TarantoolErrorCode::Timeout),
+        (102, TarantoolErrorCode::ProcC),
+        (274, TarantoolErrorCode::Unconfigured),
+    ];
+    for (raw, code) in known {
+        assert_eq!(TarantoolErrorCode::from_u32(raw), Some(code));
+        assert_eq!(code.as_u32(), raw);
+        assert_eq!(u32::from(code), raw);
+    }
+
+    assert_eq!(
+        TarantoolErrorCode::from_u32(TarantoolErrorCode::MAX as u32 + 1),
+        None
+    );
+    assert_eq!(TarantoolErrorCode::from_u32(666), None);
+    assert_eq!(TarantoolErrorCode::from_u32(u32::MAX), None);
+}
+
 #[cfg(feature = "internal_test")]
 mod tests {
     use super::*;
 
+    #[crate::test(tarantool = "crate")]
+    fn is_retriable() {
+        let e: Error = crate::fiber::r#async::timeout::Error::<Error>::Expired.into();
+        assert!(e.is_retriable(), "{}", e);
+
+        let e = Error::Remote(BoxError::new(TarantoolErrorCode::Timeout, "timeout"));
+        assert!(e.is_retriable());
+        let e = Error::Tarantool(BoxError::new(TarantoolErrorCode::Readonly, "read only"));
+        assert!(e.is_retriable());
+        let e = Error::IO(io::ErrorKind::TimedOut.into());
+        assert!(e.is_retriable());
+
+        let e = Error::Remote(BoxError::new(TarantoolErrorCode::NoSuchProc, "no proc"));
+        assert!(!e.is_retriable());
+        let e = Error::Tarantool(BoxError::new(666_u32, "custom error"));
+        assert!(!e.is_retriable());
+        let e = Error::MetaNotFound;
+        assert!(!e.is_retriable());
+        // Usually a disk fault
+        let e = Error::Tarantool(BoxError::new(TarantoolErrorCode::WalIo, "wal io"));
+        assert!(!e.is_retriable());
+
+        let e = Error::ConnectionClosed(Arc::new(Error::IO(io::ErrorKind::ConnectionReset.into())));
+        assert!(e.is_retriable());
+        // Authentication and access errors won't go away after a reconnect
+        let e = Error::ConnectionClosed(Arc::new(Error::Remote(BoxError::new(
+            TarantoolErrorCode::PasswordMismatch,
+            "Incorrect password supplied for user 'guest'",
+        ))));
+        assert!(!e.is_retriable());
+        let e = Error::ConnectionClosed(Arc::new(Error::Remote(BoxError::new(
+            TarantoolErrorCode::AccessDenied,
+            "Execute access to function 'f' is denied for user 'guest'",
+        ))));
+        assert!(!e.is_retriable());
+    }
+
     #[crate::test(tarantool = "crate")]
     fn set_error_expands_format() {
         let msg = "my message";
@@ -1187,4 +1490,19 @@ mod tests {
         // This used to crash before the fix
         assert_eq!(e.error_type(), "ClientError");
     }
+
+    #[crate::test(tarantool = "crate")]
+    fn take_and_clear() {
+        set_error!(TarantoolErrorCode::Unknown, "foo");
+        let e = BoxError::take().unwrap();
+        assert_eq!(e.to_string(), "Unknown: foo");
+        assert!(BoxError::maybe_last().is_ok());
+        assert!(BoxError::take().is_none());
+
+        set_error!(TarantoolErrorCode::Unknown, "bar");
+        assert!(BoxError::maybe_last().is_err());
+        BoxError::clear();
+        assert!(BoxError::maybe_last().is_ok());
+        assert!(BoxError::take().is_none());
+    }
 }
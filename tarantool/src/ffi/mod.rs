@@ -57,6 +57,20 @@ pub fn has_tuple_field_by_path() -> bool {
     }
 }
 
+/// Check whether the current tarantool executable supports calculating tuple
+/// hashes.
+/// If this function returns `false` then [`KeyDef::hash`] and [`Tuple::hash`]
+/// will **panic**.
+///
+/// [`KeyDef::hash`]: crate::tuple::KeyDef::hash
+/// [`Tuple::hash`]: crate::tuple::Tuple::hash
+pub fn has_tuple_hash() -> bool {
+    unsafe {
+        let name = crate::c_str!("box_tuple_hash");
+        helper::tnt_internal_symbol::<*const ()>(name).is_some() || helper::has_dyn_symbol(name)
+    }
+}
+
 /// Check whether the current tarantool executable supports datetime api.
 /// If this function returns `false` using functions in
 /// [`tarantool::datetime`] may result in a **panic**.
@@ -7,6 +7,8 @@ use std::mem::MaybeUninit;
 use std::os::raw::{c_char, c_int, c_void};
 
 pub const IPROTO_DATA: u8 = 0x30;
+pub const SQL_INFO_ROW_COUNT: u8 = 0x00;
+pub const SQL_INFO_AUTOINCREMENT_IDS: u8 = 0x01;
 
 // Note that all of the functions defined here are either `pub` or `pub(crate)`
 // even if they're only used in this file. This is because the `define_dlsym_reloc`
@@ -540,6 +540,13 @@ pub unsafe fn fiber_channel_is_empty(ch: *mut fiber_channel) -> bool {
     (*ch).count == 0
 }
 
+/// # Safety
+/// `ch` must point to a valid instance of [`fiber_channel`]
+#[inline(always)]
+pub unsafe fn fiber_channel_is_full(ch: *mut fiber_channel) -> bool {
+    (*ch).count >= (*ch).size
+}
+
 #[repr(C)]
 #[derive(Copy, Clone)]
 pub union ipc_data {
@@ -1017,7 +1024,13 @@ extern "C" {
         key_b: *const c_char,
         key_def: *mut BoxKeyDef,
     ) -> c_int;
-    #[cfg(feature = "picodata")]
+}
+
+crate::define_dlsym_reloc! {
+    /// Calculate a tuple hash for a given key definition.
+    ///
+    /// This symbol is not available in all tarantool versions, use
+    /// [`crate::ffi::has_tuple_hash`] to check if it is supported.
     pub fn box_tuple_hash(tuple: *mut BoxTuple, key_def: *mut BoxKeyDef) -> c_uint;
 }
 
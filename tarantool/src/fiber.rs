@@ -17,6 +17,7 @@ use crate::ffi::{lua, tarantool as ffi};
 use crate::static_assert;
 use crate::time::Instant;
 use crate::tlua::{self as tlua, AsLua};
+use crate::transaction::TransactionError;
 use crate::unwrap_ok_or;
 use crate::{c_ptr, set_error};
 use ::va_list::VaList;
@@ -30,13 +31,15 @@ pub use channel::TrySendError;
 pub use csw::check_yield;
 pub use csw::YieldResult;
 pub use mutex::Mutex;
-pub use r#async::block_on;
+pub use notify::Notify;
+pub use r#async::{block_on, block_on_timeout};
 use std::cell::UnsafeCell;
 use std::ffi::CString;
 use std::future::Future;
 use std::marker::PhantomData;
 use std::mem::{align_of, size_of};
 use std::os::raw::c_void;
+use std::panic::UnwindSafe;
 use std::ptr::NonNull;
 use std::rc::Rc;
 use std::time::Duration;
@@ -46,7 +49,11 @@ pub mod safety;
 pub use safety::*;
 pub mod channel;
 mod csw;
+pub mod debug;
+pub mod local;
+pub use crate::fiber_local as local;
 pub mod mutex;
+pub mod notify;
 
 /// Type alias for a fiber id.
 pub type FiberId = u64;
@@ -356,6 +363,32 @@ impl Builder<NoFunc> {
         self.func(|| block_on(f))
     }
 
+    /// Sets the callee async function for the new fiber, which is bounded by
+    /// the time `budget`.
+    ///
+    /// The future is raced against a timer inside the fiber. If it completes
+    /// in time, the fiber returns `Some(result)`. Otherwise the future is
+    /// dropped (i.e. cancelled) once the `budget` expires and the fiber
+    /// returns `None`.
+    ///
+    /// This is useful for bounding background fibers, which otherwise could
+    /// be stuck forever.
+    #[inline(always)]
+    pub fn func_async_with_timeout<'f, F, T>(
+        self,
+        f: F,
+        budget: Duration,
+    ) -> Builder<impl FnOnce() -> Option<T> + 'f>
+    where
+        F: Future<Output = T> + 'f,
+        T: 'f,
+    {
+        self.func_async(async move {
+            let f = async move { Ok::<_, std::convert::Infallible>(f.await) };
+            r#async::timeout::timeout(budget, f).await.ok()
+        })
+    }
+
     /// Sets the callee procedure for the new fiber.
     #[deprecated = "Use `Builder::func` instead"]
     #[inline(always)]
@@ -411,6 +444,110 @@ impl<F> Builder<F> {
     }
 }
 
+impl<'f, F> Builder<F>
+where
+    F: FnOnce() + UnwindSafe + 'f,
+{
+    /// Makes the new fiber catch panics in its function.
+    ///
+    /// Unwinding out of the fiber function would cross the FFI boundary into
+    /// tarantool, which aborts the process. With this option the panic is
+    /// caught instead, its message is logged via [`say_error`] and the fiber
+    /// exits normally.
+    ///
+    /// The fiber function must be [`UnwindSafe`]. If it captures something
+    /// which isn't, wrap it into [`AssertUnwindSafe`] before passing it to
+    /// [`Builder::func`]:
+    /// ```no_run
+    /// use std::panic::AssertUnwindSafe;
+    /// use tarantool::fiber;
+    ///
+    /// let state = std::cell::Cell::new(0);
+    /// fiber::Builder::new()
+    ///     .func(AssertUnwindSafe(|| {
+    ///         state.set(1);
+    ///         panic!("oops");
+    ///     }))
+    ///     .catch_unwind()
+    ///     .start()
+    ///     .unwrap()
+    ///     .join();
+    /// assert_eq!(state.get(), 1);
+    /// ```
+    ///
+    /// Only fiber functions returning `()` are supported, because there's no
+    /// value to return from a fiber which panicked.
+    ///
+    /// [`say_error`]: crate::say_error
+    /// [`AssertUnwindSafe`]: std::panic::AssertUnwindSafe
+    #[inline(always)]
+    pub fn catch_unwind(self) -> Builder<impl FnOnce() + 'f> {
+        #[rustfmt::skip]
+        let Self { name, attr, f } = self;
+        Builder {
+            name,
+            attr,
+            f: move || {
+                if let Err(e) = std::panic::catch_unwind(f) {
+                    let msg = if let Some(s) = e.downcast_ref::<&str>() {
+                        s
+                    } else if let Some(s) = e.downcast_ref::<String>() {
+                        s.as_str()
+                    } else {
+                        "<non-string panic payload>"
+                    };
+                    crate::say_error!("fiber panicked: {msg}");
+                }
+            },
+        }
+    }
+}
+
+impl<'f, F, T, E> Builder<F>
+where
+    F: FnOnce() -> Result<T, E> + 'f,
+{
+    /// Makes the new fiber execute its whole function inside a transaction.
+    ///
+    /// The transaction is started when the fiber starts and is committed if
+    /// the function returns `Ok`, or rolled back if it returns `Err` or
+    /// panics. The fiber returns the result of [`transaction::transaction`],
+    /// so for example a failed commit is reported via the [`JoinHandle`].
+    ///
+    /// ```no_run
+    /// use tarantool::fiber;
+    /// use tarantool::space::Space;
+    ///
+    /// let jh = fiber::Builder::new()
+    ///     .func(|| {
+    ///         let space = Space::find("accounts").unwrap();
+    ///         space.update(&[1], [("-", 1, 100)])?;
+    ///         space.update(&[2], [("+", 1, 100)])?;
+    ///         Ok::<_, tarantool::error::Error>(())
+    ///     })
+    ///     .in_transaction()
+    ///     .start()
+    ///     .unwrap();
+    /// jh.join().unwrap();
+    /// ```
+    ///
+    /// Note that **memtx** transactions are aborted if the fiber yields, see
+    /// [`transaction::transaction_async`] for details.
+    ///
+    /// [`transaction::transaction`]: crate::transaction::transaction
+    /// [`transaction::transaction_async`]: crate::transaction::transaction_async
+    #[inline(always)]
+    pub fn in_transaction(self) -> Builder<impl FnOnce() -> Result<T, TransactionError<E>> + 'f> {
+        #[rustfmt::skip]
+        let Self { name, attr, f } = self;
+        Builder {
+            name,
+            attr,
+            f: move || crate::transaction::transaction(f),
+        }
+    }
+}
+
 impl<'f, F, T> Builder<F>
 where
     F: FnOnce() -> T + 'f,
@@ -824,8 +961,13 @@ where
         let f = std::mem::replace(&mut ctx.fiber_rust_closure, std::ptr::null_mut());
         let f = Box::from_raw(f.cast::<F>());
 
-        // Call `f` and drop the closure.
+        // Call `f` and drop the closure. The fiber-local values of this
+        // fiber are dropped afterwards, even if `f` panics.
+        let guard = local::CleanupGuard(ctx.fiber_id);
+        #[cfg(debug_assertions)]
+        let _tracked = debug::TrackGuard::new();
         let t = (f)();
+        drop(guard);
 
         // Write results into the join handle if needed.
         if needs_returning::<T>() {
@@ -909,8 +1051,13 @@ where
                 // userdata originally contained None
                 tlua::error!(l, "rust FnOnce callback was called more than once"));
 
-        // call f and drop it afterwards
+        // call f and drop it afterwards, the fiber-local values of this fiber
+        // are dropped even if f panics
+        let guard = local::CleanupGuard(id());
+        #[cfg(debug_assertions)]
+        let _tracked = debug::TrackGuard::new();
         let res = f();
+        drop(guard);
 
         // return results to lua
         if needs_returning::<T>() {
@@ -1738,8 +1885,17 @@ pub unsafe fn name_raw(id: Option<FiberId>) -> Option<&'static [u8]> {
     }
 }
 
+/// Maximum length of a fiber name in bytes.
+///
+/// Same as `FIBER_NAME_MAX` in tarantool sources.
+pub const FIBER_NAME_MAX: usize = 255;
+
 /// Sets the name of the current fiber.
 ///
+/// Names longer than [`FIBER_NAME_MAX`] bytes are silently truncated by
+/// tarantool (or rejected with a panic if [`has_fiber_id`] returns `false`).
+/// Use [`try_set_name`] to get an error instead.
+///
 /// NOTE: if [`has_fiber_id`] returns `false` this function uses an
 /// inefficient implementation based on the lua api.
 #[inline]
@@ -1755,6 +1911,21 @@ pub fn set_name(name: &str) {
     }
 }
 
+/// Sets the name of the current fiber.
+///
+/// Returns an error if `name` is longer than [`FIBER_NAME_MAX`] bytes instead
+/// of truncating it like [`set_name`] does.
+#[inline]
+pub fn try_set_name(name: &str) -> crate::Result<()> {
+    if name.len() > FIBER_NAME_MAX {
+        #[rustfmt::skip]
+        set_error!(TarantoolErrorCode::IllegalParams, "fiber name is too long: {} bytes, the maximum is {FIBER_NAME_MAX}", name.len());
+        return Err(TarantoolError::last().into());
+    }
+    set_name(name);
+    Ok(())
+}
+
 /// Sets the name of the fiber with the given id.
 /// Returns `false` if the fiber wasn't found, `true` otherwise.
 ///
@@ -1973,6 +2144,8 @@ impl Cond {
     /// [`fiber::is_cancelled`]: crate::fiber::is_cancelled
     #[inline(always)]
     pub fn wait(&self) -> bool {
+        #[cfg(debug_assertions)]
+        let _guard = debug::WaitGuard::new();
         unsafe { ffi::fiber_cond_wait(self.inner) >= 0 }
     }
 }
@@ -2260,6 +2433,41 @@ mod tests {
         assert_eq!(res, 69);
     }
 
+    #[crate::test(tarantool = "crate")]
+    fn builder_async_func_with_timeout() {
+        let jh = Builder::new()
+            .func_async_with_timeout(async { 69 }, Duration::from_secs(10))
+            .start()
+            .unwrap();
+        assert_eq!(jh.join(), Some(69));
+
+        let dropped = Rc::new(Cell::new(false));
+        struct SetOnDrop(Rc<Cell<bool>>);
+        impl Drop for SetOnDrop {
+            fn drop(&mut self) {
+                self.0.set(true);
+            }
+        }
+        let guard = SetOnDrop(dropped.clone());
+
+        let t0 = fiber::clock();
+        let jh = Builder::new()
+            .func_async_with_timeout(
+                async move {
+                    let _guard = guard;
+                    // Sleep "forever"
+                    r#async::sleep(Duration::from_secs(100500)).await;
+                    69
+                },
+                Duration::from_millis(10),
+            )
+            .start()
+            .unwrap();
+        assert_eq!(jh.join(), None);
+        assert!(fiber::clock().duration_since(t0) < Duration::from_secs(10));
+        assert!(dropped.get());
+    }
+
     #[crate::test(tarantool = "crate")]
     #[allow(deprecated)]
     fn builder_async_proc() {
@@ -2366,6 +2574,29 @@ mod tests {
         }
     }
 
+    #[crate::test(tarantool = "crate")]
+    fn fiber_name_too_long() {
+        let name = "x".repeat(FIBER_NAME_MAX + 1);
+        let jh = fiber::start(|| {
+            let e = fiber::try_set_name(&name).unwrap_err();
+            assert_eq!(
+                e.to_string(),
+                "box error: IllegalParams: fiber name is too long: 256 bytes, the maximum is 255"
+            );
+
+            let max_name = &name[..FIBER_NAME_MAX];
+            fiber::try_set_name(max_name).unwrap();
+            assert_eq!(fiber::name(), max_name);
+
+            if unsafe { has_fiber_id() } {
+                // The name is truncated
+                fiber::set_name(&name);
+                assert_eq!(fiber::name(), max_name);
+            }
+        });
+        jh.join();
+    }
+
     #[allow(clippy::unusual_byte_groupings)]
     #[crate::test(tarantool = "crate")]
     fn fiber_csw() {
@@ -2661,4 +2892,61 @@ mod tests {
 
         jh.join();
     }
+
+    #[crate::test(tarantool = "crate")]
+    fn builder_in_transaction() {
+        use crate::space::Space;
+        use std::panic::AssertUnwindSafe;
+
+        let space = Space::builder(&crate::temp_space_name!()).create().unwrap();
+        space.index_builder("pk").create().unwrap();
+
+        // Commit on success
+        let jh = Builder::new()
+            .func(|| {
+                assert!(crate::transaction::is_in_transaction());
+                space.insert(&(1,))?;
+                space.insert(&(2,))?;
+                Ok::<_, crate::error::Error>(())
+            })
+            .in_transaction()
+            .start()
+            .unwrap();
+        jh.join().unwrap();
+        assert_eq!(space.len().unwrap(), 2);
+
+        // Rollback on error
+        let jh = Builder::new()
+            .func(|| {
+                space.insert(&(3,))?;
+                space.insert(&(1,))?;
+                Ok::<_, crate::error::Error>(())
+            })
+            .in_transaction()
+            .start()
+            .unwrap();
+        let e = jh.join().unwrap_err();
+        assert!(matches!(e, TransactionError::RolledBack(_)));
+        assert_eq!(space.len().unwrap(), 2);
+
+        // Rollback on panic. Unwinding out of a fiber aborts the process, so
+        // the wrapped function is called on the current fiber instead.
+        let f = Builder::new()
+            .func(AssertUnwindSafe(|| {
+                space.insert(&(3,)).unwrap();
+                space.insert(&(4,)).unwrap();
+                if true {
+                    panic!("oops");
+                }
+                Ok::<_, crate::error::Error>(())
+            }))
+            .in_transaction()
+            .f;
+        let res = std::panic::catch_unwind(AssertUnwindSafe(f));
+        assert!(res.is_err());
+        assert!(!crate::transaction::is_in_transaction());
+        assert_eq!(space.len().unwrap(), 2);
+
+        space.drop().unwrap();
+    }
 }
@@ -21,20 +21,26 @@
 //! - Channels
 //!   - [`oneshot`]
 //!   - [`watch`]
+//! - Combinators:
+//!   - [`Unordered`]
 //! - Extension Traits:
 //!   - [`timeout::IntoTimeout`]
 //!   - [`IntoOnDrop`]
 
-use std::{future::Future, pin::Pin, rc::Rc, task::Poll, time::Duration};
+use std::{convert::Infallible, future::Future, pin::Pin, rc::Rc, task::Poll, time::Duration};
+
+use crate::time::Instant;
 
 use futures::pin_mut;
 
 pub mod mutex;
 pub mod oneshot;
 pub mod timeout;
+pub mod unordered;
 pub mod watch;
 
 pub use mutex::Mutex;
+pub use unordered::Unordered;
 
 #[cfg(feature = "async-std")]
 pub use async_std;
@@ -47,6 +53,7 @@ pub struct RecvError;
 
 mod waker {
     use crate::fiber;
+    use std::cell::Cell;
     use std::rc::Rc;
     use std::task::RawWaker;
     use std::task::RawWakerVTable;
@@ -55,6 +62,7 @@ mod waker {
     #[derive(Default)]
     pub struct FiberWaker {
         cond: fiber::Cond,
+        woken: Cell<bool>,
     }
 
     impl FiberWaker {
@@ -63,8 +71,15 @@ mod waker {
         }
 
         pub fn wake(&self) {
+            self.woken.set(true);
             self.cond.broadcast()
         }
+
+        /// Returns `true` if [`Self::wake`] was called since the last call to
+        /// this function.
+        pub fn take_woken(&self) -> bool {
+            self.woken.replace(false)
+        }
     }
 
     unsafe impl Send for FiberWaker {}
@@ -243,6 +258,29 @@ impl<T> IntoOnDrop for T where T: Future + Sized {}
 ///
 /// For examples see module level documentation in [`super::async`].
 pub fn block_on<F: Future>(f: F) -> F::Output {
+    block_on_until(f, None).expect("there's no deadline")
+}
+
+/// Like [`block_on`], but gives up if the future doesn't complete within
+/// `timeout`, in which case [`timeout::Error::Expired`] is returned and the
+/// future is dropped unfinished.
+///
+/// Unlike [`timeout::timeout`] this works with futures of any output type.
+///
+/// The future is polled at least once, so a `timeout` equal to
+/// [`Duration::ZERO`] can still succeed if the future is immediately ready.
+pub fn block_on_timeout<F: Future>(
+    f: F,
+    timeout: Duration,
+) -> timeout::Result<F::Output, Infallible> {
+    let deadline = super::clock().checked_add(timeout);
+    block_on_until(f, deadline).ok_or(timeout::Error::Expired)
+}
+
+/// Polls the future on the current fiber until it completes or the
+/// `deadline` is reached, in which case `None` is returned. The future is
+/// polled at least once.
+fn block_on_until<F: Future>(f: F, deadline: Option<Instant>) -> Option<F::Output> {
     let rcw: Rc<waker::FiberWaker> = Default::default();
     let waker = waker::with_rcw(rcw.clone());
 
@@ -251,11 +289,20 @@ pub fn block_on<F: Future>(f: F) -> F::Output {
         let mut cx = context::ContextExt::from_waker(&waker);
 
         if let Poll::Ready(t) = f.as_mut().poll(cx.cx()) {
-            return t;
+            return Some(t);
+        }
+
+        let now = super::clock();
+        if matches!(deadline, Some(deadline) if deadline <= now) {
+            return None;
         }
 
-        let timeout = match cx.deadline {
-            Some(deadline) => deadline.duration_since(super::clock()),
+        let deadline = match (cx.deadline, deadline) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        let timeout = match deadline {
+            Some(deadline) => deadline.duration_since(now),
             None => Duration::MAX,
         };
 
@@ -269,15 +316,141 @@ pub fn block_on<F: Future>(f: F) -> F::Output {
     }
 }
 
+/// Polls the future until it either completes or stalls, i.e. returns
+/// [`Poll::Pending`] without being woken up and without a deadline which has
+/// already expired. Never blocks the current fiber and doesn't wait for I/O.
+///
+/// This is useful for deterministic testing of async code: the future makes
+/// all the progress it can, after which the test can trigger some event and
+/// call this function again. Futures like [`yield_now`] are polled until
+/// they're ready.
+///
+/// Note that a future which keeps waking itself up will be polled forever.
+///
+/// **NOTE**: this function is a part of the fiber based executor and must
+/// only be used with futures supported by it (see [`block_on`]).
+pub fn run_until_stalled<F: Future + ?Sized>(mut f: Pin<&mut F>) -> Poll<F::Output> {
+    let rcw: Rc<waker::FiberWaker> = Default::default();
+    let waker = waker::with_rcw(rcw.clone());
+
+    loop {
+        let mut cx = context::ContextExt::from_waker(&waker);
+
+        if let Poll::Ready(t) = f.as_mut().poll(cx.cx()) {
+            return Poll::Ready(t);
+        }
+
+        let deadline_expired = matches!(cx.deadline, Some(deadline) if deadline <= super::clock());
+        if !rcw.take_woken() && !deadline_expired {
+            return Poll::Pending;
+        }
+    }
+}
+
 /// An async friendly version of [fiber::sleep](crate::fiber::sleep). Prefer this version when working in async
 /// contexts.
-pub async fn sleep(time: Duration) {
-    use timeout::IntoTimeout as _;
+///
+/// The returned [`Sleep`] future can be [reset](Sleep::reset) to wait until a
+/// different deadline, which is useful for implementing idle timeouts.
+///
+/// **NOTE**: this future must only be used with the fiber based executor
+/// (i.e. [`block_on`]) otherwise the behaviour is undefined.
+#[inline]
+pub fn sleep(time: Duration) -> Sleep {
+    Sleep {
+        // If the deadline cannot be represented, sleep forever
+        deadline: super::clock().checked_add(time),
+    }
+}
+
+/// Like [`sleep`], but with an explicit deadline.
+#[inline]
+pub fn sleep_until(deadline: Instant) -> Sleep {
+    Sleep {
+        deadline: Some(deadline),
+    }
+}
 
-    // We can't just do a `fiber::sleep` as we need this to work well with other futures
-    let (tx, rx) = oneshot::channel::<()>();
-    rx.timeout(time).await.unwrap_err();
-    drop(tx);
+/// Future returned by [`sleep`] and [`sleep_until`], which is ready once the
+/// deadline is reached.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Sleep {
+    /// `None` means the deadline is infinite.
+    deadline: Option<Instant>,
+}
+
+impl Sleep {
+    /// Returns the deadline at which the future will be ready. Returns `None`
+    /// if the future is never going to be ready.
+    #[inline(always)]
+    pub fn expires_at(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Changes the deadline of the future. The future will be ready once
+    /// `deadline` is reached, even if it's pending already or the previous
+    /// deadline has already been reached.
+    ///
+    /// Usually used for extending the wait, e.g. for resetting an idle
+    /// timeout each time there's some activity.
+    #[inline(always)]
+    pub fn reset(&mut self, deadline: Instant) {
+        self.deadline = Some(deadline);
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<()> {
+        match self.deadline {
+            Some(deadline) if super::clock() >= deadline => Poll::Ready(()),
+            Some(deadline) => {
+                // SAFETY: This is safe as long as the `Context` really
+                // is the `ContextExt`. It's always true within provided
+                // `block_on` async runtime.
+                unsafe { context::ContextExt::set_deadline(cx, deadline) };
+                Poll::Pending
+            }
+            // No deadline, wait forever
+            None => Poll::Pending,
+        }
+    }
+}
+
+/// Yields the current fiber, letting other fibers (and other futures polled
+/// by the same executor) make progress. This is the async analog of
+/// [fiber::reschedule](crate::fiber::reschedule).
+///
+/// The returned future is pending on the first poll and ready on the next
+/// one, which happens on the next event loop iteration.
+///
+/// **NOTE**: this future must only be used with the fiber based executor
+/// (i.e. [`block_on`]) otherwise the behaviour is undefined.
+pub fn yield_now() -> impl Future<Output = ()> {
+    struct YieldNow {
+        yielded: bool,
+    }
+
+    impl Future for YieldNow {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<()> {
+            if self.yielded {
+                return Poll::Ready(());
+            }
+            self.yielded = true;
+
+            // SAFETY: This is safe as long as the `Context` really
+            // is the `ContextExt`. It's always true within provided
+            // `block_on` async runtime.
+            unsafe { context::ContextExt::set_deadline(cx, super::clock()) };
+            Poll::Pending
+        }
+    }
+
+    YieldNow { yielded: false }
 }
 
 #[cfg(feature = "internal_test")]
@@ -300,6 +473,128 @@ mod tests {
         assert!(before_sleep.elapsed() >= sleep_for);
     }
 
+    #[crate::test(tarantool = "crate")]
+    fn block_on_timeout_expires() {
+        let timeout = Duration::from_millis(50);
+        let start = fiber::clock();
+        let res = block_on_timeout(always_pending(), timeout);
+        assert_eq!(res, Err(timeout::Error::Expired));
+        assert!(start.elapsed() >= timeout);
+
+        assert_eq!(block_on_timeout(async { 69 }, Duration::ZERO), Ok(69));
+
+        let res = block_on_timeout(
+            async {
+                sleep(Duration::from_millis(10)).await;
+                420
+            },
+            Duration::from_secs(10),
+        );
+        assert_eq!(res, Ok(420));
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn run_until_stalled_makes_progress() {
+        let (tx, rx) = oneshot::channel();
+        let progress = Cell::new(0);
+        let f = async {
+            yield_now().await;
+            progress.set(1);
+            yield_now().await;
+            progress.set(2);
+            rx.await.unwrap()
+        };
+        pin_mut!(f);
+
+        let res = fiber::check_yield(|| run_until_stalled(f.as_mut()));
+        assert_eq!(res, fiber::YieldResult::DidntYield(Poll::Pending));
+        assert_eq!(progress.get(), 2);
+
+        // Nothing changed, still pending
+        assert_eq!(run_until_stalled(f.as_mut()), Poll::Pending);
+
+        tx.send(69).unwrap();
+        assert_eq!(run_until_stalled(f.as_mut()), Poll::Ready(69));
+
+        // Doesn't wait for timers
+        let s = sleep(Duration::from_secs(100));
+        pin_mut!(s);
+        let res = fiber::check_yield(|| run_until_stalled(s.as_mut()));
+        assert_eq!(res, fiber::YieldResult::DidntYield(Poll::Pending));
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn sleep_reset() {
+        let start = fiber::clock();
+        let first_deadline = start + Duration::from_millis(50);
+        let new_deadline = start + Duration::from_millis(150);
+
+        block_on(async {
+            let mut s = sleep_until(first_deadline);
+            assert_eq!(futures::poll!(&mut s), Poll::Pending);
+
+            // Extend the wait before the original deadline expires
+            sleep(Duration::from_millis(10)).await;
+            s.reset(new_deadline);
+            assert_eq!(s.expires_at(), Some(new_deadline));
+
+            (&mut s).await;
+            assert!(fiber::clock() >= new_deadline);
+
+            // Reset after the future fired makes it wait again
+            let another_deadline = fiber::clock() + Duration::from_millis(50);
+            s.reset(another_deadline);
+            assert_eq!(futures::poll!(&mut s), Poll::Pending);
+            s.await;
+            assert!(fiber::clock() >= another_deadline);
+        });
+
+        // Overflowing duration means sleep forever
+        let mut s = sleep(Duration::MAX);
+        assert_eq!(s.expires_at(), None);
+        block_on(async {
+            assert_eq!(futures::poll!(&mut s), Poll::Pending);
+        });
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn yield_now_interleaves() {
+        let log = std::cell::RefCell::new(vec![]);
+        let task = |name: &'static str| {
+            let log = &log;
+            async move {
+                for i in 0..3 {
+                    log.borrow_mut().push((name, i));
+                    yield_now().await;
+                }
+            }
+        };
+
+        let should_yield = fiber::check_yield(|| {
+            block_on(async {
+                futures::join!(task("a"), task("b"));
+            })
+        });
+        assert_eq!(should_yield, fiber::YieldResult::Yielded(()));
+        assert_eq!(
+            log.into_inner(),
+            [("a", 0), ("b", 0), ("a", 1), ("b", 1), ("a", 2), ("b", 2)]
+        );
+
+        // Other fibers make progress
+        let flag = Rc::new(Cell::new(false));
+        let jh = fiber::defer({
+            let flag = flag.clone();
+            move || flag.set(true)
+        });
+        block_on(async {
+            assert!(!flag.get());
+            yield_now().await;
+            assert!(flag.get());
+        });
+        jh.join();
+    }
+
     #[crate::test(tarantool = "crate")]
     fn on_drop_is_executed() {
         block_on(async {
@@ -74,6 +74,25 @@ impl<T> Receiver<T> {
     pub fn is_closed(&self) -> bool {
         Rc::weak_count(&self.0) == 0
     }
+
+    /// Attempts to receive the value without waiting.
+    ///
+    /// Returns `None` if the value hasn't been sent yet (or if the sender
+    /// was dropped without sending, which can be checked with
+    /// [`Self::is_closed`]).
+    ///
+    /// If the value is returned, it is taken out of the channel, so
+    /// awaiting the receiver afterwards will result in [`super::RecvError`].
+    #[inline]
+    pub fn try_recv(&mut self) -> Option<T> {
+        match self.0.take() {
+            State::Ready(t) => Some(t),
+            pending @ State::Pending(_) => {
+                self.0.set(pending);
+                None
+            }
+        }
+    }
 }
 
 impl<T> Future for Receiver<T> {
@@ -196,6 +215,24 @@ mod tests {
         assert_eq!(tx.send(0).unwrap_err(), 0);
     }
 
+    #[crate::test(tarantool = "crate")]
+    fn try_recv() {
+        let (tx, mut rx) = channel::<i32>();
+        assert_eq!(rx.try_recv(), None);
+        assert_eq!(rx.try_recv(), None);
+        tx.send(42).unwrap();
+        assert_eq!(rx.try_recv(), Some(42));
+        // The value has been taken
+        assert_eq!(rx.try_recv(), None);
+        assert_eq!(fiber::block_on(rx), Err(RecvError));
+
+        // Sender dropped without sending
+        let (tx, mut rx) = channel::<i32>();
+        drop(tx);
+        assert_eq!(rx.try_recv(), None);
+        assert!(rx.is_closed());
+    }
+
     #[crate::test(tarantool = "crate")]
     fn drop_sender() {
         let (tx, rx) = channel::<i32>();
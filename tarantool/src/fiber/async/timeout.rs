@@ -41,6 +41,10 @@ pub struct Timeout<F> {
 /// A `timeout` equal to [`Duration::ZERO`] guarantees that awaiting this future
 /// will **not** result in a fiber yield.
 ///
+/// If `timeout` is so large that the deadline cannot be represented by
+/// [`Instant`], the deadline is considered infinite and the future will never
+/// expire.
+///
 /// ```no_run
 /// use tarantool::fiber::r#async::*;
 /// use tarantool::fiber;
@@ -192,6 +196,18 @@ mod tests {
         fiber::block_on(timeout(Duration::MAX, async { ok(1) })).unwrap();
     }
 
+    #[crate::test(tarantool = "crate")]
+    fn timeout_overflowing_deadline_stays_pending() {
+        let (_tx, rx) = oneshot::channel::<i32>();
+        // must not panic
+        let f = timeout(Duration::from_secs(u64::MAX), rx);
+        assert_eq!(f.deadline, None);
+
+        // the inner future never expires, so the outer one does
+        let res = fiber::block_on(timeout(Duration::from_millis(10), f));
+        assert_eq!(res, Err(Error::Expired));
+    }
+
     #[crate::test(tarantool = "crate")]
     fn await_actually_yields() {
         // ready future, no timeout -> no yield
@@ -0,0 +1,279 @@
+//! A collection of futures which yields their results in the order of
+//! completion.
+//!
+//! See [`Unordered`] for details.
+//!
+//! # Example
+//! ```no_run
+//! use tarantool::fiber::r#async::{sleep, Unordered};
+//! use tarantool::fiber::r#async::futures::StreamExt as _;
+//! use tarantool::fiber;
+//! use std::time::Duration;
+//!
+//! let mut futures = Unordered::new();
+//! for ms in [30, 10, 20] {
+//!     futures.push(async move {
+//!         sleep(Duration::from_millis(ms)).await;
+//!         ms
+//!     });
+//! }
+//! let order = fiber::block_on(async move {
+//!     let mut order = vec![];
+//!     while let Some(ms) = futures.next().await {
+//!         order.push(ms);
+//!     }
+//!     order
+//! });
+//! assert_eq!(order, [10, 20, 30]);
+//! ```
+
+use futures::stream::{FusedStream, Stream};
+use std::future::Future;
+use std::iter::FromIterator;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A set of futures which are polled concurrently on the current fiber and
+/// whose results are yielded as soon as they're ready, i.e. in the order of
+/// completion rather than in the order they were pushed.
+///
+/// Unlike spawning a fiber for each future, this doesn't require the futures
+/// to be `'static` and all of them are executed by the fiber which polls the
+/// `Unordered`.
+///
+/// The results are retrieved via the [`Stream`] implementation, e.g. using
+/// [`StreamExt::next`].
+///
+/// The futures are polled in round-robin order: each time the `Unordered` is
+/// polled, polling resumes from the future following the one which completed
+/// last. So a stream of quickly completing futures can't indefinitely delay the
+/// ones pushed after them, and every pending future is polled at least once per
+/// `len()` polls of the `Unordered`. Because of this, if several futures become
+/// ready at the same time, their results aren't necessarily yielded in the
+/// order the futures were pushed.
+///
+/// In the worst case every pending future is polled each time the `Unordered`
+/// is polled, so this is intended for a moderate number of futures.
+///
+/// **NOTE**: this must only be used with the fiber based executor (i.e.
+/// [`block_on`]), and so must the futures pushed into it.
+///
+/// **NOTE**: the executor can only wait for a single file descriptor at a
+/// time, and while it does, it isn't woken up by wakers. So if several of the
+/// futures are waiting on a file descriptor (e.g. reading from a
+/// [`TcpStream`]) only the one polled last is waited for, and the rest of the
+/// futures are only polled again once that one becomes ready or the closest
+/// deadline expires. Consider pushing such futures into separate fibers
+/// instead, or using them with a [`timeout`] to bound the delay.
+///
+/// [`block_on`]: super::block_on
+/// [`TcpStream`]: crate::network::client::tcp::TcpStream
+/// [`timeout`]: super::timeout::timeout
+/// [`StreamExt::next`]: futures::StreamExt::next
+#[must_use = "streams do nothing unless polled"]
+pub struct Unordered<F> {
+    futures: Vec<Pin<Box<F>>>,
+    /// Index of the future to be polled first next time.
+    next: usize,
+}
+
+impl<F> Unordered<F> {
+    /// Creates an empty set of futures.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self {
+            futures: vec![],
+            next: 0,
+        }
+    }
+
+    /// Adds a future to the set. The future is not polled until the
+    /// `Unordered` is polled.
+    #[inline(always)]
+    pub fn push(&mut self, future: F) {
+        self.futures.push(Box::pin(future));
+    }
+
+    /// Returns the number of futures which haven't completed yet.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.futures.len()
+    }
+
+    /// Returns `true` if there are no futures which haven't completed yet.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.futures.is_empty()
+    }
+}
+
+impl<F: Future> Stream for Unordered<F> {
+    type Item = F::Output;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<F::Output>> {
+        let len = self.futures.len();
+        if len == 0 {
+            return Poll::Ready(None);
+        }
+        // The same `cx` is passed to all the futures, so that the waker and
+        // the closest deadline of all of them are registered with the
+        // executor. Only the last file descriptor wait is kept though, see
+        // the note in the type docs.
+        for n in 0..len {
+            let i = (self.next + n) % len;
+            if let Poll::Ready(res) = self.futures[i].as_mut().poll(cx) {
+                drop(self.futures.remove(i));
+                // The future which followed the completed one is now at index
+                // `i`, it wasn't polled this time so it goes first next time.
+                self.next = i;
+                return Poll::Ready(Some(res));
+            }
+        }
+        Poll::Pending
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.futures.len(), Some(self.futures.len()))
+    }
+}
+
+impl<F: Future> FusedStream for Unordered<F> {
+    #[inline(always)]
+    fn is_terminated(&self) -> bool {
+        self.futures.is_empty()
+    }
+}
+
+impl<F> Default for Unordered<F> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F> FromIterator<F> for Unordered<F> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        Self {
+            futures: iter.into_iter().map(Box::pin).collect(),
+            next: 0,
+        }
+    }
+}
+
+impl<F> Extend<F> for Unordered<F> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = F>>(&mut self, iter: I) {
+        self.futures.extend(iter.into_iter().map(Box::pin));
+    }
+}
+
+impl<F> std::fmt::Debug for Unordered<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Unordered")
+            .field("len", &self.futures.len())
+            .finish()
+    }
+}
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use super::*;
+    use crate::fiber;
+    use crate::fiber::r#async::{oneshot, sleep, yield_now};
+    use futures::StreamExt as _;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    #[crate::test(tarantool = "crate")]
+    fn completion_order() {
+        let mut futures: Unordered<_> = vec![30, 10, 40, 20]
+            .into_iter()
+            .map(|ms| async move {
+                sleep(Duration::from_millis(ms)).await;
+                ms
+            })
+            .collect();
+        assert_eq!(futures.len(), 4);
+
+        let order = fiber::block_on(async {
+            let mut order = vec![];
+            while let Some(ms) = futures.next().await {
+                order.push(ms);
+            }
+            order
+        });
+        assert_eq!(order, [10, 20, 30, 40]);
+        assert!(futures.is_empty());
+        assert_eq!(fiber::block_on(futures.next()), None);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn wakes_up_on_channel() {
+        let (tx_1, rx_1) = oneshot::channel::<i32>();
+        let (tx_2, rx_2) = oneshot::channel::<i32>();
+        let mut futures = Unordered::new();
+        futures.push(rx_1);
+        futures.push(rx_2);
+
+        let jh = fiber::start(move || {
+            tx_2.send(2).unwrap();
+            fiber::sleep(Duration::from_millis(10));
+            tx_1.send(1).unwrap();
+        });
+        let res: Vec<_> = fiber::block_on(futures.map(Result::unwrap).collect());
+        assert_eq!(res, [2, 1]);
+        jh.join();
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn round_robin() {
+        const N: usize = 16;
+        let loops = Rc::new(Cell::new(0_usize));
+        let last_polled: Rc<Vec<Cell<usize>>> = Rc::new((0..N).map(|_| Cell::new(0)).collect());
+
+        // Tasks of different lengths, so that they keep completing while the
+        // others are still running.
+        let mut futures: Unordered<_> = (0..N)
+            .map(|i| {
+                let loops = loops.clone();
+                let last_polled = last_polled.clone();
+                async move {
+                    for _ in 0..10 + i * 3 {
+                        last_polled[i].set(loops.get());
+                        yield_now().await;
+                    }
+                    i
+                }
+            })
+            .collect();
+
+        let mut done = vec![];
+        fiber::block_on(futures::future::poll_fn(|cx| loop {
+            let res = Pin::new(&mut futures).poll_next(cx);
+            loops.set(loops.get() + 1);
+            for (i, last) in last_polled.iter().enumerate() {
+                if done.contains(&i) {
+                    continue;
+                }
+                // Every pending future is polled at least once per `len()`
+                // polls of the `Unordered`.
+                assert!(
+                    loops.get() - last.get() <= N,
+                    "task {} hasn't been polled since loop {}, now {}",
+                    i,
+                    last.get(),
+                    loops.get()
+                );
+            }
+            match res {
+                Poll::Ready(Some(i)) => done.push(i),
+                Poll::Ready(None) => return Poll::Ready(()),
+                Poll::Pending => return Poll::Pending,
+            }
+        }));
+        assert_eq!(done.len(), N);
+    }
+}
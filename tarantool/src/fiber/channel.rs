@@ -1,6 +1,8 @@
-use std::{marker::PhantomData, mem::MaybeUninit, ptr::NonNull, rc::Rc, time::Duration};
+use std::{
+    cell::RefCell, marker::PhantomData, mem::MaybeUninit, ptr::NonNull, rc::Rc, time::Duration,
+};
 
-use crate::{error::TarantoolErrorCode, ffi::tarantool as ffi};
+use crate::{error::TarantoolErrorCode, ffi::tarantool as ffi, fiber::Cond};
 
 ////////////////////////////////////////////////////////////////////////////////
 // Channel
@@ -43,6 +45,7 @@ impl<T> Channel<T> {
             .expect("Memory allocation failure when creating fiber::Channel");
         Self(Rc::new(ChannelBox {
             inner,
+            select_waiters: Default::default(),
             marker: PhantomData,
         }))
     }
@@ -55,6 +58,7 @@ impl<T> Channel<T> {
     #[inline(always)]
     pub fn close(self) {
         unsafe { ffi::fiber_channel_close(self.as_ptr()) }
+        self.0.wakeup_select_waiters();
     }
 
     #[inline(always)]
@@ -62,16 +66,31 @@ impl<T> Channel<T> {
         unsafe { ffi::fiber_channel_is_closed(self.as_ptr()) }
     }
 
+    /// Returns `true` if there are no buffered messages in the channel, i.e.
+    /// a `recv` would block.
     #[inline(always)]
     pub fn is_empty(&self) -> bool {
         unsafe { ffi::fiber_channel_is_empty(self.as_ptr()) }
     }
 
+    /// Returns `true` if the channel's buffer is full, i.e. a `send` would
+    /// block unless there's a fiber waiting in `recv`.
+    ///
+    /// Note that an unbuffered channel (i.e. one with [`Self::size`] of 0) is
+    /// always full.
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        unsafe { ffi::fiber_channel_is_full(self.as_ptr()) }
+    }
+
+    /// Returns the capacity of the channel's buffer, i.e. the `size` it was
+    /// created with.
     #[inline(always)]
     pub fn size(&self) -> u32 {
         unsafe { ffi::fiber_channel_size(self.as_ptr()) }
     }
 
+    /// Returns the number of buffered messages in the channel.
     #[inline(always)]
     pub fn count(&self) -> u32 {
         unsafe { ffi::fiber_channel_count(self.as_ptr()) }
@@ -100,6 +119,10 @@ impl<T> SendTimeout<T> for Channel<T> {
             ipc_value.data_union.data = t_box_ptr.cast();
             ipc_value.base.destroy = Some(Self::destroy_msg);
 
+            // The waiters will only wake up after this fiber yields, at which
+            // point the message will be available in the channel.
+            self.0.wakeup_select_waiters();
+
             let ret_code = ffi::fiber_channel_put_msg_timeout(
                 self.as_ptr(),
                 ipc_value_ptr.cast(),
@@ -400,9 +423,20 @@ impl<T> IntoIterator for Channel<T> {
 
 struct ChannelBox<T> {
     inner: NonNull<ffi::fiber_channel>,
+    /// Conds of the fibers blocked in [`select`] on this channel.
+    select_waiters: RefCell<Vec<Rc<Cond>>>,
     marker: PhantomData<T>,
 }
 
+impl<T> ChannelBox<T> {
+    #[inline]
+    fn wakeup_select_waiters(&self) {
+        for cond in self.select_waiters.borrow().iter() {
+            cond.signal();
+        }
+    }
+}
+
 impl<T> Drop for ChannelBox<T> {
     #[inline(always)]
     fn drop(&mut self) {
@@ -410,6 +444,75 @@ impl<T> Drop for ChannelBox<T> {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// select
+////////////////////////////////////////////////////////////////////////////////
+
+/// Receives a message from any of the `channels`, blocking until one of them
+/// has a message.
+///
+/// Returns the index of the channel in `channels` and the received message.
+/// Returns `None` if all of the channels are disconnected (see
+/// [`Channel::recv`]), if the current fiber is cancelled or if `channels` is
+/// empty.
+///
+/// # Fairness
+///
+/// If several channels are ready at the same time, the one with the lowest
+/// index is chosen. This means that a channel which always has messages will
+/// starve the ones after it, so make sure to order the channels by priority.
+///
+/// # Example
+/// ```no_run
+/// use tarantool::fiber::channel::{self, Channel};
+///
+/// let high = Channel::new(8);
+/// let low = Channel::new(8);
+/// low.send("hello").unwrap();
+/// assert_eq!(channel::select(&[&high, &low]), Some((1, "hello")));
+/// ```
+pub fn select<T>(channels: &[&Channel<T>]) -> Option<(usize, T)> {
+    struct Unregister<'a, T> {
+        channels: &'a [&'a Channel<T>],
+        cond: Rc<Cond>,
+    }
+
+    impl<T> Drop for Unregister<'_, T> {
+        fn drop(&mut self) {
+            for ch in self.channels {
+                let mut waiters = ch.0.select_waiters.borrow_mut();
+                waiters.retain(|c| !Rc::ptr_eq(c, &self.cond));
+            }
+        }
+    }
+
+    let cond = Rc::new(Cond::new());
+    for ch in channels {
+        ch.0.select_waiters.borrow_mut().push(cond.clone());
+    }
+    let _guard = Unregister {
+        channels,
+        cond: cond.clone(),
+    };
+
+    loop {
+        let mut n_disconnected = 0;
+        for (i, ch) in channels.iter().enumerate() {
+            match ch.try_recv() {
+                Ok(v) => return Some((i, v)),
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => n_disconnected += 1,
+            }
+        }
+        if n_disconnected == channels.len() {
+            return None;
+        }
+        if !cond.wait() && crate::fiber::is_cancelled() {
+            return None;
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum RecvError {
     /// Timeout exceeded while waiting for a message from a channel.
@@ -491,4 +594,44 @@ mod tests {
 
         jh.join();
     }
+
+    #[crate::test(tarantool = "crate")]
+    fn select_second_ready_first() {
+        if !crate::ffi::has_fiber_channel() {
+            return;
+        }
+
+        let first = Channel::<&str>::new(1);
+        let second = Channel::<&str>::new(1);
+
+        let jh = fiber::start({
+            let first = first.clone();
+            let second = second.clone();
+            move || {
+                second.send("second").unwrap();
+                fiber::reschedule();
+                first.send("first").unwrap();
+            }
+        });
+
+        assert_eq!(select(&[&first, &second]), Some((1, "second")));
+        assert_eq!(select(&[&first, &second]), Some((0, "first")));
+        jh.join();
+
+        // Both are ready, the first one is chosen
+        first.send("a").unwrap();
+        second.send("b").unwrap();
+        assert_eq!(select(&[&first, &second]), Some((0, "a")));
+        assert_eq!(select(&[&first, &second]), Some((1, "b")));
+
+        // The waiters are cleaned up
+        assert!(first.0.select_waiters.borrow().is_empty());
+        assert!(second.0.select_waiters.borrow().is_empty());
+
+        // All disconnected
+        first.clone().close();
+        second.clone().close();
+        assert_eq!(select(&[&first, &second]), None);
+        assert_eq!(select::<()>(&[]), None);
+    }
 }
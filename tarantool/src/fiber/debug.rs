@@ -0,0 +1,407 @@
+//! Debugging utilities for fibers.
+//!
+//! Currently this only contains a cooperative deadlock detector, see
+//! [`enable_deadlock_detection`].
+
+use super::FiberId;
+use crate::time::Instant;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+/// Information about a fiber blocked in [`Cond::wait`].
+///
+/// [`Cond::wait`]: super::Cond::wait
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockedFiber {
+    pub id: FiberId,
+    pub name: String,
+    /// The moment the fiber started waiting (see [`Instant::now_fiber`]).
+    pub since: Instant,
+}
+
+/// Parameters of the deadlock detector.
+///
+/// See [`enable_deadlock_detection`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeadlockDetection {
+    /// How often the detector checks the state of the blocked fibers.
+    pub check_interval: Duration,
+    /// Number of consecutive checks during which all of the tracked fibers
+    /// were blocked, none of them have woken up and no new fibers have
+    /// blocked or started, after which a deadlock is reported.
+    pub iterations: u32,
+}
+
+impl Default for DeadlockDetection {
+    #[inline(always)]
+    fn default() -> Self {
+        Self {
+            check_interval: Duration::from_secs(1),
+            iterations: 5,
+        }
+    }
+}
+
+impl DeadlockDetection {
+    /// Enable the deadlock detector with these parameters. If the detector is
+    /// already enabled, its parameters are updated.
+    ///
+    /// See [`enable_deadlock_detection`].
+    pub fn enable(self) -> crate::Result<()> {
+        let detector = STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            state.params = Some(self);
+            state.detector
+        });
+        if detector.is_none() {
+            // NOTE: the state must not be borrowed here, because the detector
+            // fiber starts executing immediately. Also the detection is only
+            // enabled after the detector fiber is started, so that it doesn't
+            // track itself: it never blocks on a `Cond`, so no deadlock would
+            // ever be reported.
+            let id = super::Builder::new()
+                .name("deadlock_detector")
+                .func(detector_loop)
+                .start_non_joinable()?;
+            STATE.with(|state| state.borrow_mut().detector = Some(id));
+        }
+        ENABLED.with(|enabled| enabled.set(true));
+        Ok(())
+    }
+}
+
+/// Enable the cooperative deadlock detector with the default parameters.
+///
+/// When enabled, the fibers started via [`fiber::Builder`] (or
+/// [`fiber::start`] etc.) are tracked and a background fiber named
+/// `"deadlock_detector"` periodically checks on them. If all of the tracked
+/// fibers are blocked in [`Cond::wait`] and none of them have woken up for
+/// [`DeadlockDetection::iterations`] consecutive checks, a warning listing the
+/// names and ids of the blocked fibers is logged. The same list can be
+/// retrieved with [`last_deadlock_report`].
+///
+/// As long as at least one of the tracked fibers isn't blocked, nothing is
+/// reported, so idle fibers waiting for work don't trigger the detector. Only
+/// the fibers started after the detector was enabled are tracked.
+///
+/// This is a diagnostic tool and it's off by default. The tracking is only
+/// compiled in debug builds (i.e. with `debug_assertions`), in release builds
+/// no fibers are ever reported.
+///
+/// **NOTE**: a fiber is only considered blocked while it's in [`Cond::wait`]
+/// without a timeout. A tracked fiber which is waiting in some other way
+/// (e.g. in [`sleep`], on a [`Channel`], in [`JoinHandle::join`] or on a
+/// socket) is considered runnable, so while such a fiber exists no deadlock
+/// is reported, even if the rest of the tracked fibers are stuck. In
+/// particular a periodic background fiber started via [`fiber::Builder`]
+/// effectively disables the detection. On the other hand, fibers not started
+/// via [`fiber::Builder`] (e.g. the ones processing iproto requests) are not
+/// known to the detector, so if some of those would eventually wake the
+/// tracked fibers up, the report is a false positive.
+///
+/// Use [`DeadlockDetection::enable`] to specify custom parameters.
+///
+/// [`fiber::Builder`]: super::Builder
+/// [`fiber::start`]: super::start
+/// [`Cond::wait`]: super::Cond::wait
+/// [`Channel`]: super::Channel
+/// [`sleep`]: super::sleep
+/// [`JoinHandle::join`]: super::JoinHandle::join
+#[inline(always)]
+pub fn enable_deadlock_detection() -> crate::Result<()> {
+    DeadlockDetection::default().enable()
+}
+
+/// Disable the deadlock detector. The detector fiber exits after the next
+/// check.
+pub fn disable_deadlock_detection() {
+    ENABLED.with(|enabled| enabled.set(false));
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.params = None;
+        state.blocked.clear();
+        state.tracked.clear();
+        state.last_report = None;
+    });
+}
+
+/// Returns `true` if the deadlock detector is enabled.
+#[inline(always)]
+pub fn is_deadlock_detection_enabled() -> bool {
+    ENABLED.with(Cell::get)
+}
+
+/// Returns the fibers which were reported by the deadlock detector the last
+/// time a deadlock was detected, or `None` if no deadlocks were detected since
+/// the detector was enabled.
+pub fn last_deadlock_report() -> Option<Vec<BlockedFiber>> {
+    STATE.with(|state| state.borrow().last_report.clone())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// internals
+////////////////////////////////////////////////////////////////////////////////
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+#[derive(Default)]
+struct State {
+    params: Option<DeadlockDetection>,
+    detector: Option<FiberId>,
+    /// Fibers started via [`super::Builder`] which haven't finished yet.
+    tracked: BTreeSet<FiberId>,
+    blocked: BTreeMap<FiberId, BlockedFiber>,
+    /// Incremented every time a fiber blocks, wakes up, starts or finishes.
+    generation: u64,
+    last_report: Option<Vec<BlockedFiber>>,
+}
+
+/// Marks the current fiber as blocked until dropped.
+#[cfg_attr(not(debug_assertions), allow(dead_code))]
+pub(crate) struct WaitGuard(FiberId);
+
+#[cfg_attr(not(debug_assertions), allow(dead_code))]
+impl WaitGuard {
+    #[inline]
+    pub(crate) fn new() -> Option<Self> {
+        if !is_deadlock_detection_enabled() {
+            return None;
+        }
+        let id = super::id();
+        let fiber = BlockedFiber {
+            id,
+            name: super::name(),
+            since: Instant::now_fiber(),
+        };
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            state.blocked.insert(id, fiber);
+            state.generation += 1;
+        });
+        Some(Self(id))
+    }
+}
+
+impl Drop for WaitGuard {
+    fn drop(&mut self) {
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            state.blocked.remove(&self.0);
+            state.generation += 1;
+        });
+    }
+}
+
+/// Marks the current fiber as tracked by the deadlock detector until dropped.
+#[cfg_attr(not(debug_assertions), allow(dead_code))]
+pub(crate) struct TrackGuard(FiberId);
+
+#[cfg_attr(not(debug_assertions), allow(dead_code))]
+impl TrackGuard {
+    #[inline]
+    pub(crate) fn new() -> Option<Self> {
+        if !is_deadlock_detection_enabled() {
+            return None;
+        }
+        let id = super::id();
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            state.tracked.insert(id);
+            state.generation += 1;
+        });
+        Some(Self(id))
+    }
+}
+
+impl Drop for TrackGuard {
+    fn drop(&mut self) {
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            state.tracked.remove(&self.0);
+            state.generation += 1;
+        });
+    }
+}
+
+impl State {
+    /// Returns `true` if there are some tracked fibers and all of them are
+    /// blocked.
+    fn all_tracked_blocked(&self) -> bool {
+        !self.tracked.is_empty() && self.tracked.iter().all(|id| self.blocked.contains_key(id))
+    }
+}
+
+fn detector_loop() {
+    let mut last_generation = None;
+    let mut stale_checks = 0;
+    let params = || STATE.with(|state| state.borrow().params);
+    while let Some(DeadlockDetection { check_interval, .. }) = params() {
+        super::sleep(check_interval);
+        // Parameters could've changed while we were sleeping
+        let Some(params) = params() else {
+            break;
+        };
+
+        let report = STATE.with(|state| {
+            let state = state.borrow();
+            if !state.all_tracked_blocked() || last_generation != Some(state.generation) {
+                last_generation = Some(state.generation);
+                stale_checks = 0;
+                return None;
+            }
+            stale_checks += 1;
+            if stale_checks != params.iterations {
+                return None;
+            }
+            let report = state
+                .tracked
+                .iter()
+                .filter_map(|id| state.blocked.get(id).cloned())
+                .collect::<Vec<_>>();
+            Some(report)
+        });
+
+        let Some(report) = report else {
+            continue;
+        };
+        let fibers: Vec<_> = report
+            .iter()
+            .map(|f| format!("'{}' (id {})", f.name, f.id))
+            .collect();
+        crate::say_warn!(
+            "possible fiber deadlock: no progress during {} checks, blocked fibers: {}",
+            params.iterations,
+            fibers.join(", "),
+        );
+        STATE.with(|state| state.borrow_mut().last_report = Some(report));
+    }
+    STATE.with(|state| state.borrow_mut().detector = None);
+}
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use super::*;
+    use crate::fiber::{self, Cond};
+    use std::rc::Rc;
+
+    // Waits are only tracked in debug builds
+    #[cfg(debug_assertions)]
+    #[crate::test(tarantool = "crate")]
+    fn two_fiber_deadlock() {
+        DeadlockDetection {
+            check_interval: Duration::from_millis(10),
+            iterations: 3,
+        }
+        .enable()
+        .unwrap();
+
+        let cond_a = Rc::new(Cond::new());
+        let cond_b = Rc::new(Cond::new());
+        // Each fiber waits for the other one to signal first
+        let jh_a = fiber::Builder::new()
+            .name("deadlock_a")
+            .func({
+                let (cond_a, cond_b) = (cond_a.clone(), cond_b.clone());
+                move || {
+                    cond_a.wait();
+                    cond_b.signal();
+                }
+            })
+            .start()
+            .unwrap();
+        let jh_b = fiber::Builder::new()
+            .name("deadlock_b")
+            .func({
+                let (cond_a, cond_b) = (cond_a.clone(), cond_b.clone());
+                move || {
+                    cond_b.wait();
+                    cond_a.signal();
+                }
+            })
+            .start()
+            .unwrap();
+        let ids = [jh_a.id_checked().unwrap(), jh_b.id_checked().unwrap()];
+        // The detector fiber itself isn't tracked
+        STATE.with(|state| {
+            let state = state.borrow();
+            let detector = state.detector.unwrap();
+            assert!(!state.tracked.contains(&detector));
+            assert!(ids.iter().all(|id| state.tracked.contains(id)));
+        });
+
+        let deadline = fiber::clock().saturating_add(Duration::from_secs(3));
+        let report = loop {
+            if let Some(report) = last_deadlock_report() {
+                break report;
+            }
+            assert!(fiber::clock() < deadline, "deadlock wasn't detected");
+            fiber::sleep(Duration::from_millis(10));
+        };
+        let mut reported: Vec<_> = report.iter().map(|f| (f.id, f.name.as_str())).collect();
+        reported.sort();
+        assert_eq!(reported, [(ids[0], "deadlock_a"), (ids[1], "deadlock_b")]);
+
+        disable_deadlock_detection();
+        assert!(last_deadlock_report().is_none());
+
+        // Resolve the deadlock
+        cond_a.signal();
+        jh_a.join();
+        jh_b.join();
+    }
+
+    #[cfg(debug_assertions)]
+    #[crate::test(tarantool = "crate")]
+    fn idle_waiter_not_reported() {
+        DeadlockDetection {
+            check_interval: Duration::from_millis(10),
+            iterations: 3,
+        }
+        .enable()
+        .unwrap();
+
+        // A worker waiting for work which doesn't come for a while
+        let cond = Rc::new(Cond::new());
+        let jh_idle = fiber::Builder::new()
+            .name("idle_worker")
+            .func({
+                let cond = cond.clone();
+                move || {
+                    cond.wait();
+                }
+            })
+            .start()
+            .unwrap();
+        // Another fiber is busy meanwhile
+        let jh_busy = fiber::Builder::new()
+            .name("busy_worker")
+            .func(|| fiber::sleep(Duration::from_millis(200)))
+            .start()
+            .unwrap();
+
+        fiber::sleep(Duration::from_millis(100));
+        assert!(last_deadlock_report().is_none());
+        jh_busy.join();
+
+        // Once the busy fiber is done, the idle one is the only tracked fiber
+        // and it's blocked with no one to wake it up
+        let deadline = fiber::clock().saturating_add(Duration::from_secs(3));
+        let report = loop {
+            if let Some(report) = last_deadlock_report() {
+                break report;
+            }
+            assert!(fiber::clock() < deadline, "deadlock wasn't detected");
+            fiber::sleep(Duration::from_millis(10));
+        };
+        let reported: Vec<_> = report.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(reported, ["idle_worker"]);
+
+        disable_deadlock_detection();
+        cond.signal();
+        jh_idle.join();
+    }
+}
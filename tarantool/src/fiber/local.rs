@@ -0,0 +1,334 @@
+//! Fiber-local storage.
+//!
+//! See [`fiber::local!`](crate::fiber::local!) for details.
+
+use super::FiberId;
+use crate::ffi::has_fiber_id;
+use crate::ffi::tarantool as ffi;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Declares a new fiber-local storage key of type [`LocalKey`].
+///
+/// Works like [`std::thread_local!`], but each fiber gets its own copy of the
+/// value, which is lazily initialized on the first access from that fiber and
+/// dropped when the fiber ends.
+///
+/// Values are accessed via [`LocalKey::with`].
+///
+/// # Cleanup
+///
+/// Values of fibers started via [`fiber::Builder`] (or [`fiber::start`] etc.)
+/// are dropped right after the fiber function returns or panics. Values of
+/// other fibers are dropped lazily, once tarantool reports the fiber doesn't
+/// exist anymore.
+///
+/// **NOTE**: tarantool processes iproto requests (including calls to stored
+/// procedures) in fibers taken from a pool, each of which handles many
+/// requests one after another. The values are bound to the fiber, not to the
+/// request, so a value set while handling one request will be seen by the
+/// following requests handled by the same fiber. Don't use fiber-local
+/// storage for per-request state in such fibers, or reset the value
+/// explicitly at the start of each request.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::cell::Cell;
+/// use tarantool::fiber;
+///
+/// fiber::local! {
+///     static COUNTER: Cell<u32> = Cell::new(0);
+/// }
+///
+/// COUNTER.with(|c| c.set(c.get() + 1));
+/// assert_eq!(COUNTER.with(Cell::get), 1);
+///
+/// fiber::start(|| {
+///     // Other fibers don't see the changes
+///     assert_eq!(COUNTER.with(Cell::get), 0);
+/// })
+/// .join();
+/// ```
+///
+/// [`fiber::Builder`]: crate::fiber::Builder
+/// [`fiber::start`]: crate::fiber::start
+#[macro_export]
+macro_rules! fiber_local {
+    () => {};
+
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty = $init:expr; $($rest:tt)*) => {
+        $crate::fiber_local!($(#[$attr])* $vis static $name: $t = $init);
+        $crate::fiber_local!($($rest)*);
+    };
+
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty = $init:expr) => {
+        $(#[$attr])*
+        $vis static $name: $crate::fiber::local::LocalKey<$t> = {
+            fn __init() -> $t {
+                $init
+            }
+            ::std::thread_local! {
+                static __STORAGE: $crate::fiber::local::Storage<$t> = $crate::fiber::local::Storage::new();
+            }
+            $crate::fiber::local::LocalKey::new(&__STORAGE, __init)
+        };
+    };
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// LocalKey
+////////////////////////////////////////////////////////////////////////////////
+
+/// A fiber-local storage key, which owns a separate value for each fiber.
+///
+/// Must be declared via the [`fiber::local!`](crate::fiber::local!) macro.
+pub struct LocalKey<T: 'static> {
+    storage: &'static std::thread::LocalKey<Storage<T>>,
+    init: fn() -> T,
+}
+
+impl<T: 'static> LocalKey<T> {
+    #[doc(hidden)]
+    #[inline(always)]
+    pub const fn new(storage: &'static std::thread::LocalKey<Storage<T>>, init: fn() -> T) -> Self {
+        Self { storage, init }
+    }
+
+    /// Acquires a reference to the value of the current fiber, initializing
+    /// it if this is the first access from this fiber.
+    ///
+    /// It is safe to yield inside `f`: other fibers accessing the same key
+    /// will get their own values.
+    #[inline]
+    pub fn with<F, R>(&'static self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        let id = super::id();
+        let value = self.storage.with(|storage| storage.get(id));
+        let value = match value {
+            Some(value) => value,
+            None => {
+                // The initializer may access other fiber-local keys,
+                // so it must be called while no borrows are active.
+                let value = Rc::new((self.init)());
+                let first = self
+                    .storage
+                    .with(|storage| storage.insert(id, value.clone()));
+                if first {
+                    register(self);
+                }
+                value
+            }
+        };
+        f(&value)
+    }
+}
+
+/// Type erased interface for cleaning up values of finished fibers.
+trait Cleanup {
+    fn remove(&self, id: FiberId);
+}
+
+impl<T: 'static> Cleanup for LocalKey<T> {
+    #[inline]
+    fn remove(&self, id: FiberId) {
+        let value = self
+            .storage
+            .with(|storage| storage.values.borrow_mut().remove(&id));
+        // The value is dropped after the borrow is released, because drop
+        // may access this key.
+        drop(value);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Storage
+////////////////////////////////////////////////////////////////////////////////
+
+/// The thread-local part of a [`LocalKey`].
+#[doc(hidden)]
+pub struct Storage<T> {
+    values: RefCell<HashMap<FiberId, Rc<T>>>,
+    /// Number of values at which the values of the finished fibers will be
+    /// purged.
+    purge_at: Cell<usize>,
+    registered: Cell<bool>,
+}
+
+const MIN_PURGE_AT: usize = 16;
+
+impl<T> Storage<T> {
+    #[inline(always)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            values: RefCell::new(HashMap::new()),
+            purge_at: Cell::new(MIN_PURGE_AT),
+            registered: Cell::new(false),
+        }
+    }
+
+    #[inline(always)]
+    fn get(&self, id: FiberId) -> Option<Rc<T>> {
+        self.values.borrow().get(&id).cloned()
+    }
+
+    /// Returns `true` if this is the first value ever stored.
+    fn insert(&self, id: FiberId, value: Rc<T>) -> bool {
+        let len = {
+            let mut values = self.values.borrow_mut();
+            values.insert(id, value);
+            values.len()
+        };
+
+        if len >= self.purge_at.get() {
+            // Values of fibers not started via `fiber::Builder` are not
+            // removed when the fiber ends, so we do it here.
+            let mut finished = vec![];
+            self.values.borrow_mut().retain(|&id, value| {
+                if fiber_exists(id) {
+                    return true;
+                }
+                finished.push(value.clone());
+                false
+            });
+            let len = self.values.borrow().len();
+            self.purge_at.set(MIN_PURGE_AT.max(len * 2));
+            // Dropped after the borrow is released.
+            drop(finished);
+        }
+
+        !self.registered.replace(true)
+    }
+}
+
+#[inline]
+fn fiber_exists(id: FiberId) -> bool {
+    // SAFETY: safe as long as we only call this from the tx thread.
+    if unsafe { has_fiber_id() } {
+        // SAFETY: always safe.
+        !unsafe { ffi::fiber_find(id) }.is_null()
+    } else {
+        crate::global_lua()
+            .eval_with("return require'fiber'.find(...) ~= nil", id)
+            .expect("lua error")
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// cleanup
+////////////////////////////////////////////////////////////////////////////////
+
+thread_local! {
+    static KEYS: RefCell<Vec<&'static dyn Cleanup>> = RefCell::new(vec![]);
+}
+
+#[inline]
+fn register<T: 'static>(key: &'static LocalKey<T>) {
+    KEYS.with(|keys| keys.borrow_mut().push(key));
+}
+
+/// Drops all the fiber-local values of the fiber with the given `id`.
+///
+/// Is called when a fiber started via [`fiber::Builder`] finishes.
+///
+/// [`fiber::Builder`]: crate::fiber::Builder
+pub(crate) fn cleanup(id: FiberId) {
+    // Copy the list, because dropping the values may register new keys.
+    let keys = KEYS.with(|keys| keys.borrow().clone());
+    for key in keys {
+        key.remove(id);
+    }
+}
+
+/// Calls [`cleanup`] for the fiber with the given id when dropped, so that
+/// the values are dropped even if the fiber function panics.
+pub(crate) struct CleanupGuard(pub(crate) FiberId);
+
+impl Drop for CleanupGuard {
+    #[inline(always)]
+    fn drop(&mut self) {
+        cleanup(self.0)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use crate::fiber;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[crate::test(tarantool = "crate")]
+    fn independent_values() {
+        fiber::local! {
+            static COUNTER: Cell<u32> = Cell::new(0);
+            static NAME: std::cell::RefCell<String> = Default::default();
+        }
+
+        let cond = Rc::new(fiber::Cond::new());
+        let mut fibers = vec![];
+        for i in 1..=2 {
+            let cond = cond.clone();
+            let jh = fiber::start(move || {
+                NAME.with(|name| *name.borrow_mut() = format!("fiber #{i}"));
+                for _ in 0..i * 10 {
+                    COUNTER.with(|c| c.set(c.get() + 1));
+                    // Let the other fiber run in between
+                    cond.wait();
+                }
+                (COUNTER.with(Cell::get), NAME.with(|n| n.borrow().clone()))
+            });
+            fibers.push(jh);
+        }
+
+        for _ in 0..100 {
+            cond.broadcast();
+            fiber::reschedule();
+        }
+
+        let results: Vec<_> = fibers.into_iter().map(|jh| jh.join()).collect();
+        assert_eq!(
+            results,
+            [(10, "fiber #1".to_string()), (20, "fiber #2".to_string())]
+        );
+
+        // The current fiber's values are unaffected
+        assert_eq!(COUNTER.with(Cell::get), 0);
+        assert_eq!(NAME.with(|n| n.borrow().clone()), "");
+
+        // The values of finished fibers are dropped
+        let n_values = COUNTER.storage.with(|s| s.values.borrow().len());
+        assert_eq!(n_values, 1);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn values_are_dropped() {
+        struct SetOnDrop(Rc<Cell<bool>>);
+        impl Drop for SetOnDrop {
+            fn drop(&mut self) {
+                self.0.set(true);
+            }
+        }
+
+        fiber::local! {
+            static GUARD: Cell<Option<SetOnDrop>> = Cell::new(None);
+        }
+
+        let dropped = Rc::new(Cell::new(false));
+        let dropped_clone = dropped.clone();
+        let jh = fiber::start(move || {
+            GUARD.with(|g| g.set(Some(SetOnDrop(dropped_clone))));
+            fiber::reschedule();
+        });
+        assert!(!dropped.get());
+        jh.join();
+        assert!(dropped.get());
+    }
+}
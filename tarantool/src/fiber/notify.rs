@@ -0,0 +1,161 @@
+//! A broadcast notification carrying a value.
+//!
+//! See [`Notify`] for details.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::time::Duration;
+
+use crate::fiber::{self, Cond};
+use crate::time::Instant;
+
+////////////////////////////////////////////////////////////////////////////////
+// Notify
+////////////////////////////////////////////////////////////////////////////////
+
+/// A [`Cond`]-like primitive which delivers a value to the waiting fibers.
+///
+/// [`Notify::notify`] wakes up all the fibers blocked in [`Notify::wait`] (or
+/// [`Notify::wait_timeout`]) and each of them receives a clone of the value.
+/// Fibers which start waiting after the notification was sent wait for the
+/// next one, i.e. the values aren't buffered.
+///
+/// If several notifications are sent before a waiting fiber gets to run, the
+/// fiber receives the latest value.
+///
+/// # Example
+/// ```no_run
+/// use tarantool::fiber::{self, Notify};
+/// use std::rc::Rc;
+///
+/// let notify = Rc::new(Notify::new());
+/// let jh = fiber::start({
+///     let notify = notify.clone();
+///     move || notify.wait()
+/// });
+/// notify.notify("hello".to_string());
+/// assert_eq!(jh.join(), Some("hello".to_string()));
+/// ```
+pub struct Notify<T> {
+    cond: Cond,
+    value: RefCell<Option<T>>,
+    /// Number of notifications sent so far. Used to distinguish the
+    /// notifications from spurious wakeups.
+    generation: Cell<u64>,
+}
+
+impl<T> Notify<T> {
+    /// Creates a new `Notify` with no waiters.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self {
+            cond: Cond::new(),
+            value: RefCell::new(None),
+            generation: Cell::new(0),
+        }
+    }
+
+    /// Wakes up all the fibers waiting for a notification and delivers
+    /// `value` to them. Does nothing but store the value if no one is waiting.
+    /// Does not yield.
+    #[inline]
+    pub fn notify(&self, value: T) {
+        *self.value.borrow_mut() = Some(value);
+        self.generation.set(self.generation.get() + 1);
+        self.cond.broadcast();
+    }
+}
+
+impl<T: Clone> Notify<T> {
+    /// Suspends the current fiber until the next [`Self::notify`] and returns
+    /// the notification value.
+    ///
+    /// Returns `None` if the fiber was cancelled while waiting (check
+    /// [`fiber::is_cancelled`]).
+    #[inline]
+    pub fn wait(&self) -> Option<T> {
+        self.wait_timeout(crate::clock::INFINITY)
+    }
+
+    /// Suspends the current fiber until the next [`Self::notify`] or until
+    /// `timeout` is exceeded and returns the notification value.
+    ///
+    /// Returns `None` on timeout or if the fiber was cancelled while waiting
+    /// (check [`fiber::is_cancelled`]).
+    #[inline]
+    pub fn wait_timeout(&self, timeout: Duration) -> Option<T> {
+        let deadline = fiber::clock().saturating_add(timeout);
+        self.wait_deadline(deadline)
+    }
+
+    /// Suspends the current fiber until the next [`Self::notify`] or until
+    /// `deadline` is reached and returns the notification value.
+    ///
+    /// Returns `None` on deadline or if the fiber was cancelled while waiting
+    /// (check [`fiber::is_cancelled`]).
+    pub fn wait_deadline(&self, deadline: Instant) -> Option<T> {
+        let generation = self.generation.get();
+        while self.generation.get() == generation {
+            // Spurious wakeups (e.g. an explicit `fiber::wakeup`) are
+            // distinguished from notifications by the generation.
+            if !self.cond.wait_deadline(deadline) {
+                return None;
+            }
+        }
+        self.value.borrow().clone()
+    }
+}
+
+impl<T> Default for Notify<T> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for Notify<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Notify")
+            .field("generation", &self.generation.get())
+            .finish()
+    }
+}
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[crate::test(tarantool = "crate")]
+    fn notify_all_waiters() {
+        let notify = Rc::new(Notify::new());
+        let waiters: Vec<_> = (0..3)
+            .map(|_| {
+                let notify = notify.clone();
+                fiber::start(move || notify.wait())
+            })
+            .collect();
+
+        notify.notify(vec![1, 2, 3]);
+        for jh in waiters {
+            assert_eq!(jh.join(), Some(vec![1, 2, 3]));
+        }
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn spurious_wakeup() {
+        let notify = Rc::new(Notify::new());
+        let jh = fiber::start({
+            let notify = notify.clone();
+            move || notify.wait_timeout(Duration::from_secs(3))
+        });
+        // The waiter keeps waiting after an explicit wakeup
+        fiber::wakeup(jh.id_checked().unwrap());
+        fiber::reschedule();
+        notify.notify("value");
+        assert_eq!(jh.join(), Some("value"));
+
+        // Notifications sent before waiting aren't received
+        assert_eq!(notify.wait_timeout(Duration::ZERO), None);
+    }
+}
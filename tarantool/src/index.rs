@@ -26,13 +26,19 @@ use crate::unwrap_or;
 use crate::util::NumOrStr;
 use crate::util::Value;
 
-pub type IndexId = u32;
+crate::space::define_id_type! {
+    /// Id of a tarantool index within its space.
+    ///
+    /// Use this instead of a bare `u32` to avoid mixing up index ids with
+    /// space ids or other numbers.
+    IndexId
+}
 
 /// An index is a group of key values and pointers.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Index {
-    space_id: SpaceId,
-    index_id: IndexId,
+    space_id: u32,
+    index_id: u32,
 }
 
 /// Controls how to iterate over tuples in an index.
@@ -98,7 +104,7 @@ pub enum IteratorType {
 
 #[allow(dead_code)]
 pub struct Builder<'a> {
-    space_id: SpaceId,
+    space_id: u32,
     name: &'a str,
     opts: IndexOptions,
 }
@@ -118,9 +124,9 @@ macro_rules! define_setters {
 impl<'a> Builder<'a> {
     /// Creates a new index builder with default options.
     #[inline(always)]
-    pub fn new(space_id: SpaceId, name: &'a str) -> Self {
+    pub fn new(space_id: impl Into<SpaceId>, name: &'a str) -> Self {
         Self {
-            space_id,
+            space_id: space_id.into().0,
             name,
             opts: IndexOptions::default(),
         }
@@ -128,7 +134,6 @@ impl<'a> Builder<'a> {
 
     define_setters! {
         index_type(r#type: IndexType)
-        id(id: SpaceId)
         unique(unique: bool)
         if_not_exists(if_not_exists: bool)
         dimension(dimension: u32)
@@ -142,6 +147,13 @@ impl<'a> Builder<'a> {
         func(func: String)
     }
 
+    /// Set the id of the index, either an [`IndexId`] or a plain `u32`.
+    #[inline(always)]
+    pub fn id(mut self, id: impl Into<IndexId>) -> Self {
+        self.opts.id = Some(id.into().0);
+        self
+    }
+
     /// Add a part to the index's parts list.
     ///
     /// Use this method to set each part individually or use [`parts`] to set
@@ -488,7 +500,7 @@ crate::define_str_enum! {
 
 impl Index {
     #[inline(always)]
-    pub(crate) fn new(space_id: SpaceId, index_id: IndexId) -> Self {
+    pub(crate) fn new(space_id: u32, index_id: u32) -> Self {
         Index { space_id, index_id }
     }
 
@@ -498,22 +510,50 @@ impl Index {
     /// `id`s must be valid tarantool space/index id. Only use this function with
     /// ids acquired from tarantool in some way, e.g. from lua code.
     #[inline(always)]
-    pub const unsafe fn from_ids_unchecked(space_id: SpaceId, index_id: IndexId) -> Self {
-        Self { space_id, index_id }
+    pub const unsafe fn new_unchecked(space_id: SpaceId, index_id: IndexId) -> Self {
+        Self {
+            space_id: space_id.0,
+            index_id: index_id.0,
+        }
+    }
+
+    /// Create an `Index` with corresponding space and index `id`s.
+    ///
+    /// # Safety
+    /// `id`s must be valid tarantool space/index id. Only use this function with
+    /// ids acquired from tarantool in some way, e.g. from lua code.
+    #[deprecated = "use `Index::new_unchecked` which accepts `SpaceId` and `IndexId` instead"]
+    #[inline(always)]
+    pub const unsafe fn from_ids_unchecked(space_id: u32, index_id: u32) -> Self {
+        Self::new_unchecked(SpaceId(space_id), IndexId(index_id))
     }
 
     /// Return id of this index.
+    #[deprecated = "use `Index::index_id` which returns an `IndexId` instead"]
     #[inline(always)]
     pub fn id(&self) -> u32 {
         self.index_id
     }
 
+    /// Return id of this index as an [`IndexId`].
+    #[inline(always)]
+    pub fn index_id(&self) -> IndexId {
+        IndexId(self.index_id)
+    }
+
     /// Return the space id of this index.
+    #[deprecated = "use `Index::parent_space_id` which returns a `SpaceId` instead"]
     #[inline(always)]
     pub fn space_id(&self) -> u32 {
         self.space_id
     }
 
+    /// Return id of the space this index belongs to as a [`SpaceId`].
+    #[inline(always)]
+    pub fn parent_space_id(&self) -> SpaceId {
+        SpaceId(self.space_id)
+    }
+
     // Return index metadata from system `_index` space.
     #[inline]
     pub fn meta(&self) -> Result<Metadata, Error> {
@@ -575,6 +615,14 @@ impl Index {
     ///
     /// - `type` - iterator type
     /// - `key` - encoded key in MsgPack Array format (`[part1, part2, ...]`).
+    ///
+    /// For TREE indexes `key` may contain fewer parts than the index, in which
+    /// case only the first parts are compared, i.e. the key is matched as a
+    /// prefix. For example with an index on `(id, a, b)` selecting with
+    /// [`IteratorType::Eq`] and key `(id,)` returns all tuples with the given
+    /// `id` regardless of `a` and `b`. The key may be a tuple, a slice or a
+    /// struct (which is encoded as an array) with the leading parts of the
+    /// index. An empty key (`&()`) matches all tuples.
     #[inline]
     pub fn select<K>(&self, iterator_type: IteratorType, key: &K) -> Result<IndexIterator, Error>
     where
@@ -603,6 +651,62 @@ impl Index {
         })
     }
 
+    /// Same as [`Self::select`], but the returned iterator yields the current
+    /// fiber after every `yield_every` tuples, see [`YieldingIndexIterator`].
+    #[inline(always)]
+    pub fn select_yielding<K>(
+        &self,
+        iterator_type: IteratorType,
+        key: &K,
+        yield_every: usize,
+    ) -> Result<YieldingIndexIterator, Error>
+    where
+        K: ToTupleBuffer + ?Sized,
+    {
+        Ok(self.select(iterator_type, key)?.yielding(yield_every))
+    }
+
+    /// Iterate over the tuples with keys between `start` and `end` in
+    /// ascending order.
+    ///
+    /// The bounds are included or excluded depending on `start_inclusive` and
+    /// `end_inclusive`. For example selecting with `start_inclusive = true`
+    /// and `end_inclusive = false` yields the tuples in the half-open range
+    /// `[start, end)`.
+    ///
+    /// The keys are compared using the index's key definition, so partial
+    /// keys are supported, in which case only the specified parts are compared.
+    ///
+    /// - `start` - encoded key in MsgPack Array format (`[part1, part2, ...]`).
+    /// - `end` - encoded key in MsgPack Array format (`[part1, part2, ...]`).
+    pub fn select_between<K1, K2>(
+        &self,
+        start: &K1,
+        start_inclusive: bool,
+        end: &K2,
+        end_inclusive: bool,
+    ) -> Result<impl Iterator<Item = Tuple>, Error>
+    where
+        K1: ToTupleBuffer + ?Sized,
+        K2: ToTupleBuffer + ?Sized,
+    {
+        let key_def = self.meta()?.try_to_key_def().map_err(Error::other)?;
+        let end = end.to_tuple_buffer()?;
+        let iterator_type = if start_inclusive {
+            IteratorType::GE
+        } else {
+            IteratorType::GT
+        };
+        let iter = self.select(iterator_type, start)?;
+        Ok(
+            iter.take_while(move |tuple| match key_def.compare_with_key(tuple, &end) {
+                std::cmp::Ordering::Less => true,
+                std::cmp::Ordering::Equal => end_inclusive,
+                std::cmp::Ordering::Greater => false,
+            }),
+        )
+    }
+
     /// Delete a tuple identified by a key.
     ///
     /// Same as [space.delete()](../space/struct.Space.html#method.delete), but a key is searched in this index instead
@@ -900,6 +1004,36 @@ impl Index {
         }
     }
 
+    /// Extracts the key of this index from `tuple` using the key definition
+    /// tarantool keeps for the index. Unlike [`KeyDef::extract_key`] this
+    /// doesn't require building a [`KeyDef`] from the index [`Metadata`].
+    ///
+    /// # Safety
+    /// The index must exist and `tuple` must conform to the space format
+    /// (e.g. it must have passed the format check of an insert).
+    pub(crate) unsafe fn extract_key_from_conforming(
+        &self,
+        tuple: &Tuple,
+    ) -> Result<TupleBuffer, Error> {
+        let used_before = ffi::box_region_used();
+        let mut key_size = MaybeUninit::uninit();
+        let key = ffi::box_tuple_extract_key(
+            tuple.as_ptr(),
+            self.space_id,
+            self.index_id,
+            key_size.as_mut_ptr(),
+        );
+        if key.is_null() {
+            return Err(TarantoolError::last().into());
+        }
+        let key = std::slice::from_raw_parts(key as *const u8, key_size.assume_init() as _);
+        let res = TupleBuffer::from_vec_unchecked(key.to_vec());
+        // SAFETY: safe, because we only truncate the region to where it was
+        // before the call to this function.
+        ffi::box_region_truncate(used_before);
+        Ok(res)
+    }
+
     /// Extract key from `tuple` according to key definition of given
     /// index.
     ///
@@ -931,8 +1065,8 @@ impl Index {
 /// Representation of a tuple holding index metadata in system `_index` space.
 #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
 pub struct Metadata<'a> {
-    pub space_id: SpaceId,
-    pub index_id: IndexId,
+    pub space_id: u32,
+    pub index_id: u32,
     pub name: Cow<'a, str>,
     pub r#type: IndexType,
     pub opts: BTreeMap<Cow<'a, str>, Value<'a>>,
@@ -1035,6 +1169,61 @@ impl Drop for IndexIterator {
     }
 }
 
+impl IndexIterator {
+    /// Convert the iterator into one which yields the current fiber after
+    /// every `yield_every` tuples, see [`YieldingIndexIterator`].
+    #[inline(always)]
+    pub fn yielding(self, yield_every: usize) -> YieldingIndexIterator {
+        YieldingIndexIterator {
+            inner: self,
+            yield_every,
+            count: 0,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// YieldingIndexIterator
+////////////////////////////////////////////////////////////////////////////////
+
+/// Index iterator which yields the current fiber after every `yield_every`
+/// tuples, so that iterating over a large number of tuples doesn't starve
+/// other fibers.
+///
+/// The fiber is yielded via [`fiber::reschedule`], i.e. it is resumed as soon
+/// as the other ready fibers get a chance to run. If `yield_every` is 0 the
+/// fiber is never yielded.
+///
+/// Note that yielding inside a memtx transaction aborts it, so this iterator
+/// shouldn't be used inside transactions. Also note that the data can be
+/// modified by other fibers while the current one is yielded, so the iteration
+/// doesn't represent a consistent snapshot of the index.
+///
+/// Can be obtained via [`Index::select_yielding`], [`Space::select_yielding`]
+/// or [`IndexIterator::yielding`].
+///
+/// [`fiber::reschedule`]: crate::fiber::reschedule
+pub struct YieldingIndexIterator {
+    inner: IndexIterator,
+    yield_every: usize,
+    count: usize,
+}
+
+impl Iterator for YieldingIndexIterator {
+    type Item = Tuple;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.yield_every != 0 && self.count == self.yield_every {
+            self.count = 0;
+            crate::fiber::reschedule();
+        }
+        let tuple = self.inner.next()?;
+        self.count += 1;
+        Some(tuple)
+    }
+}
+
 #[cfg(feature = "internal_test")]
 mod tests {
     use super::*;
@@ -1058,7 +1247,7 @@ mod tests {
         assert_eq!(
             meta,
             Metadata {
-                space_id: space.id(),
+                space_id: space.space_id().0,
                 index_id: 0,
                 name: "pk".into(),
                 r#type: IndexType::Hash,
@@ -1089,7 +1278,7 @@ mod tests {
         assert_eq!(
             meta,
             Metadata {
-                space_id: space.id(),
+                space_id: space.space_id().0,
                 index_id: 1,
                 name: "i".into(),
                 r#type: IndexType::Tree,
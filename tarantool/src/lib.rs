@@ -120,8 +120,8 @@ pub mod vclock;
 /// #[tarantool::proc]
 /// fn my_proc() -> i32 { 69 }
 ///
-/// let procs = all_procs();
-/// assert_eq!(procs[0].name(), "my_proc");
+/// let mut procs = all_procs();
+/// assert_eq!(procs.next().unwrap().name(), "my_proc");
 /// # }
 /// ```
 ///
@@ -5,6 +5,9 @@ use std::io::{Cursor, Read, Seek, SeekFrom};
 
 pub mod encode;
 pub use encode::*;
+mod pretty;
+pub(crate) use pretty::ext_to_string;
+pub use pretty::to_string_pretty;
 pub use rmp::{self, Marker};
 
 /// Msgpack encoding of `null`.
@@ -42,6 +42,25 @@ pub fn decode<'de, T: Decode<'de>>(mut bytes: &'de [u8]) -> Result<T, DecodeErro
     T::decode(&mut bytes, &Context::DEFAULT)
 }
 
+/// Decodes `T` from a slice of bytes in msgpack and checks that the whole
+/// slice was consumed.
+///
+/// Unlike [`decode`] this returns an error if there are any bytes left after
+/// the top-level value, which usually indicates a framing bug.
+///
+/// See [`Decode`].
+#[inline]
+pub fn decode_strict<'de, T: Decode<'de>>(mut bytes: &'de [u8]) -> Result<T, DecodeError> {
+    let res = T::decode(&mut bytes, &Context::DEFAULT)?;
+    if !bytes.is_empty() {
+        return Err(DecodeError::new::<T>(format!(
+            "{} trailing byte(s) after the decoded value",
+            bytes.len()
+        )));
+    }
+    Ok(res)
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Context
 ////////////////////////////////////////////////////////////////////////////////
@@ -283,6 +302,37 @@ where
     }
 }
 
+impl<'de, T> Decode<'de> for std::sync::Arc<T>
+where
+    T: Decode<'de>,
+{
+    #[inline(always)]
+    fn decode(r: &mut &'de [u8], context: &Context) -> Result<Self, DecodeError> {
+        T::decode(r, context).map(std::sync::Arc::new)
+    }
+}
+
+impl<'de> Decode<'de> for Box<str> {
+    #[inline(always)]
+    fn decode(r: &mut &'de [u8], context: &Context) -> Result<Self, DecodeError> {
+        String::decode(r, context).map(String::into_boxed_str)
+    }
+}
+
+impl<'de> Decode<'de> for std::rc::Rc<str> {
+    #[inline(always)]
+    fn decode(r: &mut &'de [u8], context: &Context) -> Result<Self, DecodeError> {
+        <&str>::decode(r, context).map(Into::into)
+    }
+}
+
+impl<'de> Decode<'de> for std::sync::Arc<str> {
+    #[inline(always)]
+    fn decode(r: &mut &'de [u8], context: &Context) -> Result<Self, DecodeError> {
+        <&str>::decode(r, context).map(Into::into)
+    }
+}
+
 impl<'de, T> Decode<'de> for Option<T>
 where
     T: Decode<'de>,
@@ -414,6 +464,14 @@ where
     }
 }
 
+/// Borrows the string from the input buffer without copying.
+impl<'de: 'a, 'a> Decode<'de> for Cow<'a, str> {
+    #[inline(always)]
+    fn decode(r: &mut &'de [u8], context: &Context) -> Result<Self, DecodeError> {
+        <&str>::decode(r, context).map(Cow::Borrowed)
+    }
+}
+
 impl<'de> Decode<'de> for String {
     #[inline]
     fn decode(r: &mut &'de [u8], _context: &Context) -> Result<Self, DecodeError> {
@@ -434,6 +492,29 @@ impl<'de> Decode<'de> for &'de str {
     }
 }
 
+/// Decodes a msgpack binary string (`MP_BIN`) without copying, i.e. the
+/// resulting slice points into the input buffer.
+///
+/// **NOTE**: `&[u8]` is *encoded* as a msgpack array of integers (see
+/// `impl Encode for [T]`), so it doesn't roundtrip via [`Encode`]. Use
+/// [`Bytes`], `rmp::encode::write_bin` or `serde_bytes` to produce `MP_BIN`
+/// values.
+impl<'de> Decode<'de> for &'de [u8] {
+    #[inline]
+    fn decode(r: &mut &'de [u8], _context: &Context) -> Result<Self, DecodeError> {
+        let n = rmp::decode::read_bin_len(r).map_err(DecodeError::from_vre::<Self>)? as usize;
+        if r.len() < n {
+            return Err(DecodeError::new::<Self>(format!(
+                "not enough data: expected {n} bytes, got {}",
+                r.len()
+            )));
+        }
+        let (res, bound) = r.split_at(n);
+        *r = bound;
+        Ok(res)
+    }
+}
+
 impl<'de, K, V> Decode<'de> for BTreeMap<K, V>
 where
     K: Decode<'de> + Ord,
@@ -661,7 +742,7 @@ where
 
 impl<T> Encode for Box<T>
 where
-    T: Encode,
+    T: Encode + ?Sized,
 {
     #[inline(always)]
     fn encode(&self, w: &mut impl Write, context: &Context) -> Result<(), EncodeError> {
@@ -671,7 +752,17 @@ where
 
 impl<T> Encode for std::rc::Rc<T>
 where
-    T: Encode,
+    T: Encode + ?Sized,
+{
+    #[inline(always)]
+    fn encode(&self, w: &mut impl Write, context: &Context) -> Result<(), EncodeError> {
+        (**self).encode(w, context)
+    }
+}
+
+impl<T> Encode for std::sync::Arc<T>
+where
+    T: Encode + ?Sized,
 {
     #[inline(always)]
     fn encode(&self, w: &mut impl Write, context: &Context) -> Result<(), EncodeError> {
@@ -902,6 +993,231 @@ impl Encode for serde_json::Map<String, serde_json::Value> {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// MsgpackExt
+////////////////////////////////////////////////////////////////////////////////
+
+/// A user defined msgpack extension type (`MP_EXT`).
+///
+/// The value is encoded as an `MP_EXT` with type [`Self::EXT_TYPE`] and the
+/// payload produced by [`Self::encode_ext`]. Use [`impl_msgpack_ext`] to
+/// implement [`Encode`] and [`Decode`] for the type based on this trait, after
+/// which it can be used as a field in structs deriving them.
+///
+/// Note that tarantool already uses some of the type ids for it's own types
+/// (e.g. 1 for decimal, 2 for uuid, 4 for datetime), so pick the ones which
+/// don't clash with those.
+///
+/// # Example
+/// ```
+/// use tarantool::msgpack::{self, DecodeError, MsgpackExt};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Rgb(u8, u8, u8);
+///
+/// impl MsgpackExt for Rgb {
+///     const EXT_TYPE: i8 = 42;
+///
+///     fn encode_ext(&self, buf: &mut Vec<u8>) {
+///         buf.extend_from_slice(&[self.0, self.1, self.2]);
+///     }
+///
+///     fn decode_ext(data: &[u8]) -> Result<Self, DecodeError> {
+///         match *data {
+///             [r, g, b] => Ok(Rgb(r, g, b)),
+///             _ => Err(DecodeError::new::<Self>("expected 3 bytes")),
+///         }
+///     }
+/// }
+///
+/// tarantool::impl_msgpack_ext!(Rgb);
+///
+/// let data = msgpack::encode(&Rgb(1, 2, 3));
+/// assert_eq!(data, [0xc7, 3, 42, 1, 2, 3]);
+/// assert_eq!(msgpack::decode::<Rgb>(&data).unwrap(), Rgb(1, 2, 3));
+/// ```
+///
+/// [`impl_msgpack_ext`]: crate::impl_msgpack_ext
+pub trait MsgpackExt: Sized {
+    /// Type id of the extension.
+    const EXT_TYPE: i8;
+
+    /// Write the payload of the extension into `buf`.
+    fn encode_ext(&self, buf: &mut Vec<u8>);
+
+    /// Decode the value from the payload of the extension.
+    fn decode_ext(data: &[u8]) -> Result<Self, DecodeError>;
+}
+
+/// Encodes `value` as a msgpack extension. See [`MsgpackExt`].
+pub fn encode_ext<T: MsgpackExt>(value: &T, w: &mut impl Write) -> Result<(), EncodeError> {
+    let mut buf = Vec::new();
+    value.encode_ext(&mut buf);
+    rmp::encode::write_ext_meta(w, buf.len() as _, T::EXT_TYPE)?;
+    w.write_all(&buf)?;
+    Ok(())
+}
+
+/// Decodes a msgpack extension of type `T`. See [`MsgpackExt`].
+pub fn decode_ext<T: MsgpackExt>(r: &mut &[u8]) -> Result<T, DecodeError> {
+    let meta = rmp::decode::read_ext_meta(r).map_err(DecodeError::from_vre::<T>)?;
+    if meta.typeid != T::EXT_TYPE {
+        return Err(DecodeError::new::<T>(format!(
+            "expected ext type {}, got {}",
+            T::EXT_TYPE,
+            meta.typeid
+        )));
+    }
+    let len = meta.size as usize;
+    if r.len() < len {
+        return Err(DecodeError::new::<T>(format!(
+            "not enough data for ext payload: expected {len} bytes, got {}",
+            r.len()
+        )));
+    }
+    let (data, tail) = r.split_at(len);
+    let res = T::decode_ext(data)?;
+    *r = tail;
+    Ok(res)
+}
+
+/// Implements [`Encode`] and [`Decode`] for a type implementing
+/// [`MsgpackExt`].
+///
+/// [`Encode`]: crate::msgpack::Encode
+/// [`Decode`]: crate::msgpack::Decode
+/// [`MsgpackExt`]: crate::msgpack::MsgpackExt
+#[macro_export]
+macro_rules! impl_msgpack_ext {
+    ($($t:ty),+ $(,)?) => {
+        $(
+            impl $crate::msgpack::Encode for $t {
+                #[inline(always)]
+                fn encode(
+                    &self,
+                    w: &mut impl ::std::io::Write,
+                    _context: &$crate::msgpack::Context,
+                ) -> ::std::result::Result<(), $crate::msgpack::EncodeError> {
+                    $crate::msgpack::encode_ext(self, w)
+                }
+            }
+
+            impl<'de> $crate::msgpack::Decode<'de> for $t {
+                #[inline(always)]
+                fn decode(
+                    r: &mut &'de [u8],
+                    _context: &$crate::msgpack::Context,
+                ) -> ::std::result::Result<Self, $crate::msgpack::DecodeError> {
+                    $crate::msgpack::decode_ext(r)
+                }
+            }
+        )+
+    };
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Bytes
+////////////////////////////////////////////////////////////////////////////////
+
+/// A byte buffer which is encoded as a msgpack binary string (`MP_BIN`), which
+/// is how tarantool stores `varbinary` values.
+///
+/// By contrast `Vec<u8>` is encoded as a msgpack array of integers.
+///
+/// The serde implementations also use the binary format.
+///
+/// # Example
+/// ```no_run
+/// use tarantool::msgpack::{self, Bytes};
+///
+/// let data = msgpack::encode(&Bytes(vec![1, 2, 3]));
+/// assert_eq!(data, b"\xc4\x03\x01\x02\x03");
+/// let bytes: Bytes = msgpack::decode(&data).unwrap();
+/// assert_eq!(bytes, [1, 2, 3]);
+/// ```
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct Bytes(#[serde(with = "serde_bytes")] pub Vec<u8>);
+
+impl Encode for Bytes {
+    #[inline(always)]
+    fn encode(&self, w: &mut impl Write, _context: &Context) -> Result<(), EncodeError> {
+        rmp::encode::write_bin(w, &self.0).map_err(Into::into)
+    }
+}
+
+impl<'de> Decode<'de> for Bytes {
+    #[inline]
+    fn decode(r: &mut &'de [u8], _context: &Context) -> Result<Self, DecodeError> {
+        // The length is checked before allocating, so a corrupt header
+        // can't make us allocate a huge buffer
+        let n = rmp::decode::read_bin_len(r).map_err(DecodeError::from_vre::<Self>)? as usize;
+        if r.len() < n {
+            return Err(DecodeError::new::<Self>(format!(
+                "not enough data: expected {n} bytes, got {}",
+                r.len()
+            )));
+        }
+        let (res, bound) = r.split_at(n);
+        *r = bound;
+        Ok(Self(res.to_vec()))
+    }
+}
+
+impl std::ops::Deref for Bytes {
+    type Target = Vec<u8>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Vec<u8> {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for Bytes {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.0
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    #[inline(always)]
+    fn from(v: Vec<u8>) -> Self {
+        Self(v)
+    }
+}
+
+impl From<Bytes> for Vec<u8> {
+    #[inline(always)]
+    fn from(b: Bytes) -> Self {
+        b.0
+    }
+}
+
+impl<const N: usize> PartialEq<[u8; N]> for Bytes {
+    #[inline(always)]
+    fn eq(&self, other: &[u8; N]) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<[u8]> for Bytes {
+    #[inline(always)]
+    fn eq(&self, other: &[u8]) -> bool {
+        self.0 == other
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // tests
 ////////////////////////////////////////////////////////////////////////////////
@@ -1249,9 +1565,9 @@ mod tests {
         let mut encoded = Vec::new();
         rmpv::encode::write_value(&mut encoded, &test_unnamed_forbidden_helper_arr).unwrap();
         let err_arr = TestUnnamedForbidden::decode(&mut encoded.as_slice(), ARR_CTX).unwrap_err();
-        assert_eq!(err_arr.to_string(), "failed decoding tarantool::msgpack::encode::tests::decode_optionals::TestUnnamedForbidden (0): failed decoding f32 (got FixPos(42)): the type decoded isn't match with the expected one");
+        assert_eq!(err_arr.to_string(), "failed decoding tarantool::msgpack::encode::tests::decode_optionals::TestUnnamedForbidden: not enough fields, expected 4, got 2 (note: optional fields must be explicitly null unless `allow_array_optionals` attribute is passed)");
         let err_map = TestUnnamedForbidden::decode(&mut encoded.as_slice(), MAP_CTX).unwrap_err();
-        assert_eq!(err_map.to_string(), "failed decoding tarantool::msgpack::encode::tests::decode_optionals::TestUnnamedForbidden (0): failed decoding f32 (got FixPos(42)): the type decoded isn't match with the expected one");
+        assert_eq!(err_map.to_string(), "failed decoding tarantool::msgpack::encode::tests::decode_optionals::TestUnnamedForbidden: not enough fields, expected 4, got 2 (note: optional fields must be explicitly null unless `allow_array_optionals` attribute is passed)");
 
         #[derive(Debug, Decode, PartialEq)]
         #[encode(tarantool = "crate", allow_array_optionals)]
@@ -1300,6 +1616,107 @@ mod tests {
         assert_eq!(decoded_arr, TestUnnamedAllowed(42, None, None));
     }
 
+    #[test]
+    fn decode_array_len_mismatch() {
+        #[derive(Debug, Decode, PartialEq)]
+        #[encode(tarantool = "crate")]
+        struct Named {
+            a: i32,
+            b: String,
+        }
+
+        let err = decode::<Named>(&encode(&(1, "x", 2))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed decoding tarantool::msgpack::encode::tests::decode_array_len_mismatch::Named: too many fields, expected 2, got 3"
+        );
+        let err = decode::<Named>(&encode(&(1,))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed decoding tarantool::msgpack::encode::tests::decode_array_len_mismatch::Named: not enough fields, expected 2, got 1"
+        );
+
+        #[derive(Debug, Decode, PartialEq)]
+        #[encode(tarantool = "crate", allow_array_optionals)]
+        struct Unnamed(i32, Option<i32>, Option<i32>);
+
+        assert_eq!(
+            decode::<Unnamed>(&encode(&(1,))).unwrap(),
+            Unnamed(1, None, None)
+        );
+        let err = decode::<Unnamed>(&encode(&(1, 2, 3, 4))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed decoding tarantool::msgpack::encode::tests::decode_array_len_mismatch::Unnamed: too many fields, expected at most 3, got 4"
+        );
+        let err = decode::<Unnamed>(&encode(&Vec::<i32>::new())).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed decoding tarantool::msgpack::encode::tests::decode_array_len_mismatch::Unnamed: not enough fields, expected at least 1, got 0"
+        );
+    }
+
+    #[test]
+    fn decode_nested_missing_optionals() {
+        #[derive(Debug, Decode, PartialEq)]
+        #[encode(tarantool = "crate", allow_array_optionals)]
+        struct Named {
+            a: i32,
+            b: Option<i32>,
+            c: Option<String>,
+        }
+
+        // The missing fields of one element must not be read from the next one
+        let value = Value::Array(vec![
+            Value::Array(vec![Value::from(1)]),
+            Value::Array(vec![Value::from(2), Value::from(3)]),
+            Value::Array(vec![Value::from(4), Value::Nil, Value::from("x")]),
+            Value::Array(vec![Value::from(5)]),
+        ]);
+        let mut encoded = Vec::new();
+        rmpv::encode::write_value(&mut encoded, &value).unwrap();
+        let decoded = decode::<Vec<Named>>(&encoded).unwrap();
+        assert_eq!(
+            decoded,
+            [
+                Named {
+                    a: 1,
+                    b: None,
+                    c: None
+                },
+                Named {
+                    a: 2,
+                    b: Some(3),
+                    c: None
+                },
+                Named {
+                    a: 4,
+                    b: None,
+                    c: Some("x".into())
+                },
+                Named {
+                    a: 5,
+                    b: None,
+                    c: None
+                },
+            ]
+        );
+
+        #[derive(Debug, Decode, PartialEq)]
+        #[encode(tarantool = "crate", allow_array_optionals)]
+        struct Unnamed(i32, Option<i32>, Option<i32>);
+
+        let decoded = decode::<Vec<Unnamed>>(&encode(&[vec![1], vec![2, 3], vec![4]])).unwrap();
+        assert_eq!(
+            decoded,
+            [
+                Unnamed(1, None, None),
+                Unnamed(2, Some(3), None),
+                Unnamed(4, None, None)
+            ]
+        );
+    }
+
     #[test]
     fn encode_raw() {
         use serde::Serialize;
@@ -1477,8 +1894,7 @@ mod tests {
         let err = decode::<Test6>(&bytes).unwrap_err();
         assert_eq!(
             err.to_string(),
-            "failed decoding tarantool::msgpack::encode::tests::encode_raw::Test6 (field 0): \
-            failed decoding alloc::vec::Vec<alloc::string::String> (got FixPos(42)): the type decoded isn't match with the expected one"
+            "failed decoding tarantool::msgpack::encode::tests::encode_raw::Test6: not enough fields, expected 3, got 1"
         );
 
         // Check for parsing scope of valid msgpack in multibyte scenario
@@ -2053,6 +2469,48 @@ mod tests {
         assert_eq!(original, decoded);
     }
 
+    #[test]
+    fn encode_map_in_struct() {
+        #[derive(Clone, Encode, Decode, PartialEq, Debug)]
+        #[encode(tarantool = "crate")]
+        struct Test {
+            id: u32,
+            labels: HashMap<String, String>,
+            counters: BTreeMap<String, u64>,
+        }
+
+        let original = Test {
+            id: 1,
+            labels: vec![("env".into(), "prod".into()), ("dc".into(), "msk".into())]
+                .into_iter()
+                .collect(),
+            counters: vec![("b".into(), 2), ("c".into(), 3), ("a".into(), 1)]
+                .into_iter()
+                .collect(),
+        };
+        let bytes = encode(&original);
+        let decoded: Test = decode(&bytes).unwrap();
+        assert_eq!(original, decoded);
+
+        // Maps are encoded as MP_MAP, BTreeMap keys are always sorted
+        let value = rmpv::decode::read_value(&mut &bytes[..]).unwrap();
+        let Value::Array(fields) = value else {
+            panic!("expected array, got {:?}", value);
+        };
+        let Value::Map(labels) = &fields[1] else {
+            panic!("expected map, got {:?}", fields[1]);
+        };
+        assert_eq!(labels.len(), 2);
+        assert_eq!(
+            fields[2],
+            Value::Map(vec![
+                ("a".into(), 1.into()),
+                ("b".into(), 2.into()),
+                ("c".into(), 3.into()),
+            ])
+        );
+    }
+
     #[test]
     fn encode_str() {
         let original = "hello";
@@ -2150,6 +2608,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decode_borrowed_is_zero_copy() {
+        #[derive(Debug, Decode, PartialEq)]
+        #[encode(tarantool = "crate")]
+        struct Borrowed<'a> {
+            name: &'a str,
+            data: &'a [u8],
+        }
+
+        let mut bytes = Vec::new();
+        rmp::encode::write_array_len(&mut bytes, 2).unwrap();
+        rmp::encode::write_str(&mut bytes, "hello").unwrap();
+        rmp::encode::write_bin(&mut bytes, b"\x01\x02\x03").unwrap();
+
+        let decoded: Borrowed = decode(&bytes).unwrap();
+        assert_eq!(
+            decoded,
+            Borrowed {
+                name: "hello",
+                data: &[1, 2, 3],
+            }
+        );
+
+        // Both fields point into the original buffer, i.e. no allocation happened
+        let buf = bytes.as_ptr_range();
+        assert!(buf.contains(&decoded.name.as_ptr()));
+        assert!(buf.contains(&decoded.data.as_ptr()));
+        assert_eq!(&bytes[2..7], decoded.name.as_bytes());
+        assert_eq!(&bytes[9..], decoded.data);
+
+        // Not enough data
+        let err = decode::<&[u8]>(b"\xc4\x03\x01").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed decoding &[u8]: not enough data: expected 3 bytes, got 1"
+        );
+    }
+
+    #[test]
+    fn encode_decode_smart_pointers() {
+        use std::rc::Rc;
+        use std::sync::Arc;
+
+        #[derive(Debug, Encode, Decode, PartialEq)]
+        #[encode(tarantool = "crate")]
+        struct Test<'a> {
+            boxed: Box<str>,
+            cow: Cow<'a, str>,
+            rc: Rc<str>,
+            arc: Arc<u32>,
+            boxed_opt: Box<Option<u32>>,
+        }
+
+        let original = Test {
+            boxed: "boxed".into(),
+            cow: Cow::Owned("cow".into()),
+            rc: "rc".into(),
+            arc: Arc::new(42),
+            boxed_opt: Box::new(Some(1)),
+        };
+        let bytes = encode(&original);
+        assert_eq!(
+            bytes,
+            encode(&("boxed", "cow", "rc", 42, 1)),
+            "smart pointers are encoded transparently"
+        );
+
+        let decoded: Test = decode(&bytes).unwrap();
+        assert_eq!(decoded, original);
+        // `Cow<str>` borrows from the input buffer
+        assert!(matches!(decoded.cow, Cow::Borrowed(_)));
+        assert!(bytes.as_ptr_range().contains(&decoded.cow.as_ptr()));
+
+        let err = decode::<Cow<str>>(b"\x01").unwrap_err();
+        assert!(
+            err.to_string().starts_with("failed decoding &str"),
+            "{}",
+            err
+        );
+    }
+
     #[test]
     fn encode_char() {
         let bytes = encode(&'a');
@@ -2198,6 +2737,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decode_strict_trailing_bytes() {
+        let value = vec!["one".to_string(), "two".to_string()];
+        let mut bytes = encode(&value);
+        assert_eq!(decode_strict::<Vec<String>>(&bytes).unwrap(), value);
+
+        bytes.push(0xc0);
+        assert_eq!(decode::<Vec<String>>(&bytes).unwrap(), value);
+        let err = decode_strict::<Vec<String>>(&bytes).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed decoding alloc::vec::Vec<alloc::string::String>: 1 trailing byte(s) after the decoded value"
+        );
+
+        // Errors of the value itself are still reported
+        let err = decode_strict::<Vec<String>>(b"\x91").unwrap_err();
+        assert!(!err.to_string().contains("trailing"));
+    }
+
     #[test]
     #[rustfmt::skip]
     fn encode_integer() {
@@ -2223,4 +2781,158 @@ mod tests {
         assert_eq!(decode::<u32>(b"\xce\xff\xff\xff\xff").unwrap(), u32::MAX);
         assert_eq!(decode::<u64>(b"\xcf\xff\xff\xff\xff\xff\xff\xff\xff").unwrap(), u64::MAX);
     }
+
+    #[test]
+    fn msgpack_ext_in_struct() {
+        #[derive(Debug, PartialEq)]
+        struct Point {
+            x: i16,
+            y: i16,
+        }
+
+        impl MsgpackExt for Point {
+            const EXT_TYPE: i8 = 100;
+
+            fn encode_ext(&self, buf: &mut Vec<u8>) {
+                buf.extend_from_slice(&self.x.to_be_bytes());
+                buf.extend_from_slice(&self.y.to_be_bytes());
+            }
+
+            fn decode_ext(data: &[u8]) -> Result<Self, DecodeError> {
+                match *data {
+                    [x0, x1, y0, y1] => Ok(Point {
+                        x: i16::from_be_bytes([x0, x1]),
+                        y: i16::from_be_bytes([y0, y1]),
+                    }),
+                    _ => Err(DecodeError::new::<Self>(format!(
+                        "expected 4 bytes, got {}",
+                        data.len()
+                    ))),
+                }
+            }
+        }
+
+        crate::impl_msgpack_ext!(Point);
+
+        #[derive(Debug, PartialEq, Encode, Decode)]
+        #[encode(tarantool = "crate")]
+        struct Shape {
+            name: String,
+            points: Vec<Point>,
+            center: Option<Point>,
+        }
+
+        assert_eq!(encode(&Point { x: 1, y: -1 }), b"\xd6\x64\x00\x01\xff\xff");
+
+        let shape = Shape {
+            name: "line".into(),
+            points: vec![Point { x: 0, y: 0 }, Point { x: 3, y: 4 }],
+            center: Some(Point { x: 1, y: 2 }),
+        };
+        let data = encode(&shape);
+        assert_eq!(decode_strict::<Shape>(&data).unwrap(), shape);
+
+        // Wrong ext type
+        let err = decode::<Point>(b"\xd6\x65\x00\x01\xff\xff").unwrap_err();
+        assert!(
+            err.to_string().contains("expected ext type 100, got 101"),
+            "{}",
+            err
+        );
+
+        // Wrong payload length
+        let err = decode::<Point>(b"\xd5\x64\x00\x01").unwrap_err();
+        assert!(
+            err.to_string().contains("expected 4 bytes, got 2"),
+            "{}",
+            err
+        );
+
+        // Not an ext at all
+        decode::<Point>(b"\x01").unwrap_err();
+    }
+
+    #[test]
+    fn bytes_roundtrip() {
+        let bytes = Bytes(vec![1, 2, 3]);
+        let data = encode(&bytes);
+        // MP_BIN, not an array of integers
+        assert_eq!(data, b"\xc4\x03\x01\x02\x03");
+        assert_eq!(decode::<Bytes>(&data).unwrap(), bytes);
+        assert_eq!(encode(&vec![1_u8, 2, 3]), b"\x93\x01\x02\x03");
+
+        // Same with serde
+        assert_eq!(rmp_serde::to_vec(&bytes).unwrap(), data);
+        assert_eq!(rmp_serde::from_slice::<Bytes>(&data).unwrap(), bytes);
+
+        let big = Bytes(vec![0xff; 300]);
+        let data = encode(&big);
+        assert_eq!(&data[..3], b"\xc5\x01\x2c");
+        assert_eq!(decode::<Bytes>(&data).unwrap(), big);
+
+        #[derive(Debug, Encode, Decode, PartialEq)]
+        #[encode(tarantool = "crate")]
+        struct WithBytes {
+            id: u32,
+            data: Bytes,
+        }
+        let v = WithBytes {
+            id: 1,
+            data: Bytes(b"foo".to_vec()),
+        };
+        let data = encode(&v);
+        assert_eq!(data, b"\x92\x01\xc4\x03foo");
+        assert_eq!(decode::<WithBytes>(&data).unwrap(), v);
+
+        let e = decode::<Bytes>(b"\x93\x01\x02\x03").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "failed decoding tarantool::msgpack::encode::Bytes (got FixArray(3)): \
+            the type decoded isn't match with the expected one"
+        );
+
+        let e = decode::<Bytes>(b"\xc4\x03\x01").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "failed decoding tarantool::msgpack::encode::Bytes: not enough data: expected 3 bytes, got 1"
+        );
+
+        // MP_BIN32 header claiming 4GiB of data
+        let e = decode::<Bytes>(b"\xc6\xff\xff\xff\xff\x01").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "failed decoding tarantool::msgpack::encode::Bytes: not enough data: expected 4294967295 bytes, got 1"
+        );
+    }
+
+    #[test]
+    fn char_roundtrip() {
+        for c in ['x', 'ж', '€', '🦀'] {
+            let data = encode(&c);
+            assert_eq!(data.len(), 1 + c.len_utf8());
+            assert_eq!(decode::<char>(&data).unwrap(), c);
+
+            // Same as serde
+            assert_eq!(rmp_serde::to_vec(&c).unwrap(), data);
+            assert_eq!(rmp_serde::from_slice::<char>(&data).unwrap(), c);
+        }
+
+        #[derive(Debug, Encode, Decode, PartialEq)]
+        #[encode(tarantool = "crate")]
+        struct WithChar {
+            id: u32,
+            sep: char,
+        }
+        let v = WithChar { id: 1, sep: ';' };
+        let data = encode(&v);
+        assert_eq!(data, b"\x92\x01\xa1;");
+        assert_eq!(decode::<WithChar>(&data).unwrap(), v);
+
+        let e = decode::<WithChar>(b"\x92\x01\xa0").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "failed decoding tarantool::msgpack::encode::tests::char_roundtrip::WithChar (field sep): \
+            failed decoding char: expected a msgpack non-empty string, got string length 0"
+        );
+    }
 }
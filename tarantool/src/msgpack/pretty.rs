@@ -0,0 +1,457 @@
+//! Human readable rendering of arbitrary msgpack data.
+//!
+//! See [`to_string_pretty`].
+
+use super::DecodeError;
+use crate::ffi::{datetime::MP_DATETIME, decimal::MP_DECIMAL, uuid::MP_UUID};
+use rmp::Marker;
+use std::convert::TryInto;
+use std::fmt::Write;
+
+const INDENT: usize = 2;
+
+/// Arrays and maps nested deeper than this are rendered as `...`, so that
+/// deeply nested data can't overflow the (small) fiber stack.
+const MAX_DEPTH: usize = 32;
+
+/// Renders msgpack `data` into a human readable JSON-like string. This is
+/// intended as a debugging aid, e.g. for figuring out why some data fails to
+/// decode.
+///
+/// Arrays and maps are rendered with each element on a separate line. Strings
+/// are quoted and escaped. Binary strings are rendered as `bin("<hex>")`.
+/// Arrays and maps nested more than 32 levels deep are rendered as `...`.
+///
+/// Tarantool's msgpack extension types are rendered as tagged values:
+/// - decimal: `decimal("3.14")`
+/// - uuid: `uuid("6f2ba4c4-0a4c-4d79-86ae-43d4f84b70e1")`
+/// - datetime: `datetime("2023-11-11 6:03:20.0 +00:00:00")`
+/// - any other extension: `ext(<type>, "<hex>")`
+///
+/// Returns an error if `data` is not a single valid msgpack value.
+///
+/// # Example
+/// ```
+/// use tarantool::msgpack;
+///
+/// let data = msgpack::encode(&(1, "two", [3.5]));
+/// assert_eq!(
+///     msgpack::to_string_pretty(&data).unwrap(),
+///     r#"[
+///   1,
+///   "two",
+///   [
+///     3.5
+///   ]
+/// ]"#,
+/// );
+/// ```
+pub fn to_string_pretty(data: &[u8]) -> Result<String, DecodeError> {
+    let mut r = data;
+    let mut out = String::new();
+    write_value(&mut r, &mut out, 0)?;
+    if !r.is_empty() {
+        let n = r.len();
+        return Err(error(format!("{n} trailing bytes after the value")));
+    }
+    Ok(out)
+}
+
+fn write_value(r: &mut &[u8], out: &mut String, indent: usize) -> Result<(), DecodeError> {
+    let marker = rmp::decode::read_marker(r).map_err(|_| error("failed to read marker"))?;
+    match marker {
+        Marker::Null => out.push_str("null"),
+        Marker::True => out.push_str("true"),
+        Marker::False => out.push_str("false"),
+        Marker::FixPos(v) => write!(out, "{v}").unwrap(),
+        Marker::FixNeg(v) => write!(out, "{v}").unwrap(),
+        Marker::U8 => write!(out, "{}", take_array::<1>(r)?[0]).unwrap(),
+        Marker::U16 => write!(out, "{}", u16::from_be_bytes(take_array(r)?)).unwrap(),
+        Marker::U32 => write!(out, "{}", u32::from_be_bytes(take_array(r)?)).unwrap(),
+        Marker::U64 => write!(out, "{}", u64::from_be_bytes(take_array(r)?)).unwrap(),
+        Marker::I8 => write!(out, "{}", i8::from_be_bytes(take_array(r)?)).unwrap(),
+        Marker::I16 => write!(out, "{}", i16::from_be_bytes(take_array(r)?)).unwrap(),
+        Marker::I32 => write!(out, "{}", i32::from_be_bytes(take_array(r)?)).unwrap(),
+        Marker::I64 => write!(out, "{}", i64::from_be_bytes(take_array(r)?)).unwrap(),
+        Marker::F32 => write!(out, "{:?}", f32::from_be_bytes(take_array(r)?)).unwrap(),
+        Marker::F64 => write!(out, "{:?}", f64::from_be_bytes(take_array(r)?)).unwrap(),
+        Marker::FixStr(_) | Marker::Str8 | Marker::Str16 | Marker::Str32 => {
+            let len = read_len(r, marker)?;
+            let bytes = take(r, len)?;
+            write!(out, "{:?}", String::from_utf8_lossy(bytes)).unwrap();
+        }
+        Marker::Bin8 | Marker::Bin16 | Marker::Bin32 => {
+            let len = read_len(r, marker)?;
+            let bytes = take(r, len)?;
+            write!(out, "bin(\"{}\")", Hex(bytes)).unwrap();
+        }
+        Marker::FixArray(_) | Marker::Array16 | Marker::Array32 if indent / INDENT >= MAX_DEPTH => {
+            let len = read_len(r, marker)?;
+            skip_values(r, len)?;
+            out.push_str("...");
+        }
+        Marker::FixMap(_) | Marker::Map16 | Marker::Map32 if indent / INDENT >= MAX_DEPTH => {
+            let len = read_len(r, marker)?;
+            skip_values(r, 2 * len)?;
+            out.push_str("...");
+        }
+        Marker::FixArray(_) | Marker::Array16 | Marker::Array32 => {
+            let len = read_len(r, marker)?;
+            write_container(r, out, indent, len, ['[', ']'], |r, out, indent| {
+                write_value(r, out, indent)
+            })?;
+        }
+        Marker::FixMap(_) | Marker::Map16 | Marker::Map32 => {
+            let len = read_len(r, marker)?;
+            write_container(r, out, indent, len, ['{', '}'], |r, out, indent| {
+                write_value(r, out, indent)?;
+                out.push_str(": ");
+                write_value(r, out, indent)
+            })?;
+        }
+        Marker::FixExt1
+        | Marker::FixExt2
+        | Marker::FixExt4
+        | Marker::FixExt8
+        | Marker::FixExt16
+        | Marker::Ext8
+        | Marker::Ext16
+        | Marker::Ext32 => {
+            let len = read_len(r, marker)?;
+            let [ty] = take_array::<1>(r)?;
+            let data = take(r, len)?;
+            write_ext(out, ty as i8, data);
+        }
+        Marker::Reserved => return Err(error("unexpected reserved marker 0xc1")),
+    }
+    Ok(())
+}
+
+fn write_container(
+    r: &mut &[u8],
+    out: &mut String,
+    indent: usize,
+    len: usize,
+    [open, close]: [char; 2],
+    mut write_item: impl FnMut(&mut &[u8], &mut String, usize) -> Result<(), DecodeError>,
+) -> Result<(), DecodeError> {
+    out.push(open);
+    if len == 0 {
+        out.push(close);
+        return Ok(());
+    }
+    for i in 0..len {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push('\n');
+        write_indent(out, indent + INDENT);
+        write_item(r, out, indent + INDENT)?;
+    }
+    out.push('\n');
+    write_indent(out, indent);
+    out.push(close);
+    Ok(())
+}
+
+/// Skips `n` msgpack values. Unlike [`write_value`] this doesn't recurse into
+/// nested arrays and maps, so it works for arbitrarily deep data.
+fn skip_values(r: &mut &[u8], mut n: usize) -> Result<(), DecodeError> {
+    while n > 0 {
+        n -= 1;
+        let marker = rmp::decode::read_marker(r).map_err(|_| error("failed to read marker"))?;
+        let len = match marker {
+            Marker::Null | Marker::True | Marker::False | Marker::FixPos(_) | Marker::FixNeg(_) => {
+                0
+            }
+            Marker::U8 | Marker::I8 => 1,
+            Marker::U16 | Marker::I16 => 2,
+            Marker::U32 | Marker::I32 | Marker::F32 => 4,
+            Marker::U64 | Marker::I64 | Marker::F64 => 8,
+            Marker::FixStr(_)
+            | Marker::Str8
+            | Marker::Str16
+            | Marker::Str32
+            | Marker::Bin8
+            | Marker::Bin16
+            | Marker::Bin32 => read_len(r, marker)?,
+            Marker::FixArray(_) | Marker::Array16 | Marker::Array32 => {
+                n += read_len(r, marker)?;
+                0
+            }
+            Marker::FixMap(_) | Marker::Map16 | Marker::Map32 => {
+                n += 2 * read_len(r, marker)?;
+                0
+            }
+            Marker::FixExt1
+            | Marker::FixExt2
+            | Marker::FixExt4
+            | Marker::FixExt8
+            | Marker::FixExt16
+            | Marker::Ext8
+            | Marker::Ext16
+            | Marker::Ext32 => {
+                // the extension type and the data
+                1 + read_len(r, marker)?
+            }
+            Marker::Reserved => return Err(error("unexpected reserved marker 0xc1")),
+        };
+        take(r, len)?;
+    }
+    Ok(())
+}
+
+fn write_ext(out: &mut String, ty: i8, data: &[u8]) {
+    let tag = match ty {
+        MP_DECIMAL => "decimal",
+        MP_UUID => "uuid",
+        MP_DATETIME => "datetime",
+        _ => "",
+    };
+    if let Some(value) = ext_to_string(ty, data) {
+        write!(out, "{tag}({value:?})").unwrap();
+        return;
+    }
+    write!(out, "ext({ty}, \"{}\")", Hex(data)).unwrap();
+}
+
+/// Converts the payload of one of tarantool's msgpack extension types
+/// (decimal, uuid or datetime) into a string. Returns `None` if the type is
+/// not one of those or if `data` is invalid.
+pub(crate) fn ext_to_string(ty: i8, data: &[u8]) -> Option<String> {
+    match ty {
+        MP_DECIMAL => decimal_to_string(data),
+        MP_UUID => {
+            let bytes = data.try_into().ok()?;
+            Some(crate::uuid::Uuid::from_bytes(bytes).to_string())
+        }
+        MP_DATETIME if data.len() == 8 || data.len() == 16 => {
+            let datetime = crate::datetime::Datetime::from_bytes_tt(data).ok()?;
+            Some(datetime.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Converts tarantool's msgpack representation of a decimal (scale followed
+/// by packed BCD) into a string. Returns `None` if `data` is invalid.
+fn decimal_to_string(mut data: &[u8]) -> Option<String> {
+    let scale: i32 = rmp::decode::read_int(&mut data).ok()?;
+    let (&last, digits) = data.split_last()?;
+    let negative = matches!(last & 0xf, 0xb | 0xd);
+
+    let mut nibbles = Vec::with_capacity(data.len() * 2);
+    for &byte in digits {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0xf);
+    }
+    nibbles.push(last >> 4);
+    if nibbles.iter().any(|&d| d > 9) {
+        return None;
+    }
+    let mut digits: String = nibbles
+        .iter()
+        .skip_while(|&&d| d == 0)
+        .map(|&d| char::from(b'0' + d))
+        .collect();
+    if digits.is_empty() {
+        digits.push('0');
+    }
+
+    let mut res = String::new();
+    if negative {
+        res.push('-');
+    }
+    let scale = scale as isize;
+    let n_digits = digits.len() as isize;
+    if scale <= 0 {
+        res.push_str(&digits);
+        if digits != "0" {
+            res.extend(std::iter::repeat('0').take(-scale as _));
+        }
+    } else if n_digits > scale {
+        let (int, frac) = digits.split_at((n_digits - scale) as _);
+        write!(res, "{int}.{frac}").unwrap();
+    } else {
+        res.push_str("0.");
+        res.extend(std::iter::repeat('0').take((scale - n_digits) as _));
+        res.push_str(&digits);
+    }
+    Some(res)
+}
+
+#[inline]
+fn read_len(r: &mut &[u8], marker: Marker) -> Result<usize, DecodeError> {
+    let len = match marker {
+        Marker::FixStr(n) | Marker::FixArray(n) | Marker::FixMap(n) => n as _,
+        Marker::FixExt1 => 1,
+        Marker::FixExt2 => 2,
+        Marker::FixExt4 => 4,
+        Marker::FixExt8 => 8,
+        Marker::FixExt16 => 16,
+        Marker::Str8 | Marker::Bin8 | Marker::Ext8 => take_array::<1>(r)?[0] as _,
+        Marker::Str16 | Marker::Bin16 | Marker::Array16 | Marker::Map16 | Marker::Ext16 => {
+            u16::from_be_bytes(take_array(r)?) as _
+        }
+        Marker::Str32 | Marker::Bin32 | Marker::Array32 | Marker::Map32 | Marker::Ext32 => {
+            u32::from_be_bytes(take_array(r)?) as _
+        }
+        _ => unreachable!("{:?} has no length", marker),
+    };
+    Ok(len)
+}
+
+#[inline]
+fn take<'a>(r: &mut &'a [u8], n: usize) -> Result<&'a [u8], DecodeError> {
+    if r.len() < n {
+        let got = r.len();
+        return Err(error(format!(
+            "not enough data: expected {n} bytes, got {got}"
+        )));
+    }
+    let (head, tail) = r.split_at(n);
+    *r = tail;
+    Ok(head)
+}
+
+#[inline]
+fn take_array<const N: usize>(r: &mut &[u8]) -> Result<[u8; N], DecodeError> {
+    Ok(take(r, N)?.try_into().expect("length is checked in take"))
+}
+
+#[inline]
+fn write_indent(out: &mut String, indent: usize) {
+    out.extend(std::iter::repeat(' ').take(indent));
+}
+
+#[inline]
+fn error(message: impl ToString) -> DecodeError {
+    DecodeError::new::<Msgpack>(message)
+}
+
+/// Only used for error messages.
+struct Msgpack;
+
+struct Hex<'a>(&'a [u8]);
+
+impl std::fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tuple_with_nested_array_and_uuid() {
+        let uuid = crate::uuid::Uuid::parse_str("6f2ba4c4-0a4c-4d79-86ae-43d4f84b70e1").unwrap();
+        let data = rmp_serde::to_vec(&(
+            1,
+            "foo",
+            (-2, 0.5, [(); 0], vec![Some(3), None]),
+            serde_bytes::Bytes::new(b"\x01\xff"),
+            std::collections::BTreeMap::from([("k", true)]),
+            uuid,
+        ))
+        .unwrap();
+        assert_eq!(
+            to_string_pretty(&data).unwrap(),
+            r#"[
+  1,
+  "foo",
+  [
+    -2,
+    0.5,
+    [],
+    [
+      3,
+      null
+    ]
+  ],
+  bin("01ff"),
+  {
+    "k": true
+  },
+  uuid("6f2ba4c4-0a4c-4d79-86ae-43d4f84b70e1")
+]"#
+        );
+    }
+
+    #[test]
+    fn ext_types() {
+        let ext = |ty: i8, data: &[u8]| {
+            let mut buf = vec![];
+            rmp::encode::write_ext_meta(&mut buf, data.len() as _, ty).unwrap();
+            buf.extend_from_slice(data);
+            to_string_pretty(&buf).unwrap()
+        };
+        // scale 2, digits 314, positive
+        assert_eq!(ext(MP_DECIMAL, &[0x02, 0x31, 0x4c]), r#"decimal("3.14")"#);
+        // scale 3, digits 5, negative
+        assert_eq!(ext(MP_DECIMAL, &[0x03, 0x5d]), r#"decimal("-0.005")"#);
+        // scale -2, digits 12, positive
+        assert_eq!(ext(MP_DECIMAL, &[0xfe, 0x01, 0x2c]), r#"decimal("1200")"#);
+        assert_eq!(
+            ext(MP_DATETIME, &1_700_000_000_i64.to_le_bytes()),
+            r#"datetime("2023-11-14 22:13:20.0 +00:00:00")"#
+        );
+        assert_eq!(ext(42, &[1, 2, 3]), r#"ext(42, "010203")"#);
+        // invalid uuid length is rendered as a generic ext
+        assert_eq!(ext(MP_UUID, &[1, 2]), r#"ext(2, "0102")"#);
+    }
+
+    #[test]
+    fn max_depth() {
+        // [[[...[1]...]], {"k": 2}] with 1000 nested arrays
+        let mut data = vec![0x92];
+        data.extend(std::iter::repeat(0x91).take(1000));
+        data.push(0x01);
+        data.extend([0x81, 0xa1, b'k', 0x02]);
+
+        let s = to_string_pretty(&data).unwrap();
+        assert_eq!(s.matches('[').count(), MAX_DEPTH);
+        assert_eq!(s.matches("...").count(), 1);
+        let elided_line = format!("\n{}...\n", " ".repeat(MAX_DEPTH * INDENT));
+        assert!(s.contains(&elided_line), "{}", s);
+        // The values after the elided one are rendered as usual
+        assert!(s.ends_with("],\n  {\n    \"k\": 2\n  }\n]"), "{}", s);
+
+        // Maps are elided the same way
+        let mut data = vec![];
+        data.extend(std::iter::repeat([0x81, 0xc0]).take(1000).flatten());
+        data.push(0xc0);
+        let s = to_string_pretty(&data).unwrap();
+        assert_eq!(s.matches('{').count(), MAX_DEPTH);
+        assert_eq!(s.matches("null: ...").count(), 1);
+
+        // Errors in the elided data are still reported
+        let e = to_string_pretty(&data[..data.len() - 1]).unwrap_err();
+        assert!(e.to_string().ends_with("failed to read marker"), "{}", e);
+    }
+
+    #[test]
+    fn errors() {
+        let e = to_string_pretty(&[0x92, 0x01]).unwrap_err();
+        assert!(e.to_string().ends_with("failed to read marker"), "{}", e);
+
+        let e = to_string_pretty(&[0xa3, b'a']).unwrap_err();
+        assert!(
+            e.to_string()
+                .ends_with("not enough data: expected 3 bytes, got 1"),
+            "{}",
+            e
+        );
+
+        let e = to_string_pretty(&[0x01, 0x02]).unwrap_err();
+        assert!(
+            e.to_string().ends_with("1 trailing bytes after the value"),
+            "{}",
+            e
+        );
+    }
+}
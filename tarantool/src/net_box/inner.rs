@@ -372,7 +372,7 @@ impl ConnInner {
             self.update_state(ConnState::Error);
             return Err(error.into());
         } else {
-            fiber::sleep(reconnect_after);
+            fiber::sleep(self.options.reconnect_delay());
             match self.connect() {
                 Ok(_) => {}
                 Err(err) => {
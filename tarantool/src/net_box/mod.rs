@@ -50,8 +50,10 @@ use promise::Promise;
 pub use space::RemoteSpace;
 
 use crate::error::Error;
+#[cfg(feature = "network_client")]
+use crate::network::client::CallBuilder;
 use crate::network::protocol;
-use crate::tuple::{Decode, ToTupleBuffer, Tuple};
+use crate::tuple::{Decode, DecodeOwned, ToTupleBuffer, Tuple};
 
 mod index;
 mod inner;
@@ -120,6 +122,16 @@ impl Conn {
         self.inner.close()
     }
 
+    /// Returns the schema version of the server from the header of the last
+    /// received response, or `None` if no responses were received yet.
+    ///
+    /// The schema version changes every time the server's schema is changed
+    /// (e.g. a space is created), so this can be used to detect that any
+    /// cached schema information is stale.
+    pub fn schema_version(&self) -> Option<u64> {
+        self.inner.schema_version.get()
+    }
+
     /// Execute a PING command.
     ///
     /// - `options` – the supported option is `timeout`
@@ -149,6 +161,16 @@ impl Conn {
         Ok(Some(res))
     }
 
+    /// Start building a remote stored procedure call. The same builder is
+    /// used by the async [`network::client`](crate::network::client).
+    ///
+    /// Call `send` on the returned builder to send the request.
+    #[cfg(feature = "network_client")]
+    #[inline(always)]
+    pub fn call_builder<'a>(&'a self, fn_name: &'a str) -> CallBuilder<'a, Conn> {
+        CallBuilder::new(self, fn_name)
+    }
+
     /// Call a remote stored procedure without yielding.
     ///
     /// If enqueuing a request succeeded a [`Promise`] is returned which will be
@@ -218,6 +240,44 @@ impl Conn {
     }
 }
 
+#[cfg(feature = "network_client")]
+impl<A> CallBuilder<'_, Conn, A>
+where
+    A: ToTupleBuffer + ?Sized,
+{
+    /// Send the request and wait for the response. This function yields.
+    ///
+    /// Same as [`Conn::call`] with [`Options::timeout`] set to the builder's
+    /// timeout. Returns an [`Error::IO`] with [`io::ErrorKind::TimedOut`] if
+    /// the response didn't arrive in time.
+    ///
+    /// [`io::ErrorKind::TimedOut`]: std::io::ErrorKind::TimedOut
+    pub fn send(self) -> Result<Tuple, Error> {
+        let options = Options {
+            timeout: self.timeout,
+            ..Default::default()
+        };
+        self.client.inner.request(
+            &protocol::Call {
+                fn_name: self.fn_name,
+                args: self.args,
+            },
+            &options,
+        )
+    }
+
+    /// Send the request, wait for the response and decode it into `R`. This
+    /// function yields.
+    ///
+    /// See also [`Self::send`].
+    pub fn send_and_decode<R>(self) -> Result<R, Error>
+    where
+        R: DecodeOwned,
+    {
+        self.send()?.decode()
+    }
+}
+
 impl Drop for Conn {
     fn drop(&mut self) {
         if self.is_master {
@@ -1,3 +1,5 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
 use std::time::Duration;
 
 use crate::error::Error;
@@ -66,6 +68,16 @@ pub struct ConnOptions {
     /// When a connection is explicitly closed, or when connection object is dropped, then reconnect attempts stop.
     pub reconnect_after: Duration,
 
+    /// Randomizes the [`reconnect_after`](#structfield.reconnect_after)
+    /// interval by up to `±reconnect_jitter` of its value, so that many
+    /// clients don't all reconnect at the same moment after a server restart.
+    ///
+    /// For example with `reconnect_after` of 1 second and `reconnect_jitter`
+    /// of `0.25` each reconnect attempt is made after a random delay between
+    /// 0.75 and 1.25 seconds. The value is clamped to `0.0..=1.0`.
+    /// Default: `0.0` (no jitter)
+    pub reconnect_jitter: f64,
+
     /// Duration to wait before returning “error: Connection timed out”.
     pub connect_timeout: Duration,
 
@@ -100,6 +112,7 @@ impl Default for ConnOptions {
             password: "".to_string(),
             auth_method: crate::auth::AuthMethod::default(),
             reconnect_after: Default::default(),
+            reconnect_jitter: 0.0,
             connect_timeout: Default::default(),
             send_buffer_flush_interval: Duration::from_millis(10),
             send_buffer_limit: 64000,
@@ -109,6 +122,28 @@ impl Default for ConnOptions {
     }
 }
 
+impl ConnOptions {
+    /// Returns the delay before the next reconnect attempt, i.e.
+    /// [`reconnect_after`](#structfield.reconnect_after) randomized according
+    /// to [`reconnect_jitter`](#structfield.reconnect_jitter).
+    pub fn reconnect_delay(&self) -> Duration {
+        let jitter = self.reconnect_jitter;
+        if self.reconnect_after.is_zero() || jitter.is_nan() || jitter <= 0.0 {
+            return self.reconnect_after;
+        }
+        let jitter = jitter.min(1.0);
+
+        // A uniformly distributed value in `[-1.0, 1.0)`. `RandomState` is
+        // seeded differently each time, which is good enough for our purpose
+        // and saves us a dependency.
+        let bits = RandomState::new().build_hasher().finish();
+        let unit = (bits >> 11) as f64 / (1_u64 << 53) as f64;
+        let factor = 1.0 + jitter * (2.0 * unit - 1.0);
+
+        self.reconnect_after.mul_f64(factor)
+    }
+}
+
 /// Provides triggers for connect, disconnect and schema reload events.
 pub trait ConnTriggers {
     /// Defines a trigger for execution when a new connection is established, and authentication and schema fetch are
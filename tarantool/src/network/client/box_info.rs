@@ -0,0 +1,94 @@
+//! Typed representation of the remote instance's `box.info`.
+//!
+//! See [`AsClient::box_info`](super::AsClient::box_info).
+
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+
+/// Lua code which converts `box.info` into a form which can be decoded into
+/// [`BoxInfo`].
+///
+/// The vclock is converted into a list of pairs, because lua tables with
+/// integer keys are encoded either as arrays or as maps depending on the keys.
+pub(crate) const BOX_INFO_LUA: &str = r#"
+local info = box.info
+local vclock = setmetatable({}, { __serialize = 'seq' })
+for id, lsn in pairs(info.vclock) do
+    table.insert(vclock, { id, lsn })
+end
+local replication = setmetatable({}, { __serialize = 'seq' })
+for _, r in pairs(info.replication) do
+    table.insert(replication, {
+        id = r.id,
+        uuid = r.uuid,
+        lsn = r.lsn,
+        upstream_status = r.upstream and r.upstream.status,
+        downstream_status = r.downstream and r.downstream.status,
+    })
+end
+return {
+    id = info.id,
+    uuid = info.uuid,
+    lsn = info.lsn,
+    status = info.status,
+    ro = info.ro,
+    vclock = vclock,
+    replication = replication,
+}
+"#;
+
+/// Information about a tarantool instance, a subset of `box.info`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct BoxInfo {
+    /// Id of the instance in the replica set. `None` if the instance is not
+    /// bootstrapped yet.
+    #[serde(default)]
+    pub id: Option<u32>,
+    /// UUID of the instance.
+    #[serde(default)]
+    pub uuid: String,
+    /// Log sequence number of the last entry in the instance's WAL.
+    #[serde(default)]
+    pub lsn: u64,
+    /// Status of the instance, e.g. `"running"` or `"orphan"`.
+    #[serde(default)]
+    pub status: String,
+    /// `true` if the instance is in read-only mode.
+    #[serde(default)]
+    pub ro: bool,
+    /// Vector clock: replica id to lsn.
+    #[serde(default, deserialize_with = "vclock_from_pairs")]
+    pub vclock: BTreeMap<u32, u64>,
+    /// Replication peers of the instance (including itself).
+    #[serde(default)]
+    pub replication: Vec<ReplicaInfo>,
+}
+
+/// Information about a replication peer, an element of `box.info.replication`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ReplicaInfo {
+    /// Id of the peer in the replica set.
+    pub id: u32,
+    /// UUID of the peer.
+    #[serde(default)]
+    pub uuid: String,
+    /// Log sequence number of the peer's last entry known to the instance.
+    #[serde(default)]
+    pub lsn: u64,
+    /// Status of the replication from the peer (`box.info.replication[id].upstream.status`).
+    /// `None` if there's no upstream.
+    #[serde(default)]
+    pub upstream_status: Option<String>,
+    /// Status of the replication to the peer (`box.info.replication[id].downstream.status`).
+    /// `None` if there's no downstream.
+    #[serde(default)]
+    pub downstream_status: Option<String>,
+}
+
+fn vclock_from_pairs<'de, D>(deserializer: D) -> Result<BTreeMap<u32, u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let pairs = Vec::<(u32, u64)>::deserialize(deserializer)?;
+    Ok(pairs.into_iter().collect())
+}
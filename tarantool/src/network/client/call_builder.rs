@@ -0,0 +1,139 @@
+//! Builder for remote stored procedure calls.
+//!
+//! See [`CallBuilder`] for details.
+
+use std::time::Duration;
+
+use super::{AsClient, ClientError};
+use crate::fiber::r#async::timeout::{self, IntoTimeout as _};
+use crate::tuple::{DecodeOwned, ToTupleBuffer, Tuple};
+
+/// A builder for a remote stored procedure call.
+///
+/// Can be used both with [`AsClient`] implementors (via
+/// [`AsClient::call_builder`]) and with [`net_box::Conn`] (via
+/// [`Conn::call_builder`]), so that the call options are specified the same
+/// way for both of them. The request is sent with `send`, which returns the
+/// response tuple, or with `send_and_decode`, which also decodes it.
+///
+/// The terminal methods differ between the backends in the same way the
+/// clients themselves do:
+/// - for [`AsClient`] implementors they are async and return
+///   [`timeout::Result`]`<_, `[`ClientError`]`>`, with
+///   [`timeout::Error::Expired`] on timeout;
+/// - for [`net_box::Conn`] they block the fiber and return
+///   [`crate::Result`], with an [`Error::IO`] of kind
+///   [`io::ErrorKind::TimedOut`] on timeout.
+///
+/// There's no option for reusing the buffer the request is encoded into,
+/// because both [`Client`](super::Client) and [`net_box::Conn`] already reuse
+/// their outgoing buffers between requests.
+///
+/// # Example
+/// ```no_run
+/// # async fn f(client: tarantool::network::client::Client) {
+/// use tarantool::network::client::AsClient as _;
+/// use std::time::Duration;
+///
+/// let (sum,): (i32,) = client
+///     .call_builder("sum")
+///     .args(&(1, 2))
+///     .timeout(Duration::from_secs(3))
+///     .send_and_decode()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+///
+/// [`net_box::Conn`]: crate::net_box::Conn
+/// [`Conn::call_builder`]: crate::net_box::Conn::call_builder
+/// [`Error::IO`]: crate::error::Error::IO
+/// [`io::ErrorKind::TimedOut`]: std::io::ErrorKind::TimedOut
+#[derive(Debug)]
+#[must_use = "the call is only sent once `send` is called"]
+pub struct CallBuilder<'a, C, A: ?Sized = ()> {
+    pub(crate) client: &'a C,
+    pub(crate) fn_name: &'a str,
+    pub(crate) args: &'a A,
+    pub(crate) timeout: Option<Duration>,
+}
+
+impl<'a, C> CallBuilder<'a, C> {
+    /// Create a builder for a call of the stored procedure `fn_name` via
+    /// `client` without arguments and without a timeout.
+    ///
+    /// Usually [`AsClient::call_builder`] or [`Conn::call_builder`] is used
+    /// instead.
+    ///
+    /// [`Conn::call_builder`]: crate::net_box::Conn::call_builder
+    #[inline(always)]
+    pub fn new(client: &'a C, fn_name: &'a str) -> Self {
+        Self {
+            client,
+            fn_name,
+            args: &(),
+            timeout: None,
+        }
+    }
+}
+
+impl<'a, C, A> CallBuilder<'a, C, A>
+where
+    A: ToTupleBuffer + ?Sized,
+{
+    /// Set the arguments of the call. By default the function is called
+    /// without arguments.
+    #[inline(always)]
+    pub fn args<B>(self, args: &'a B) -> CallBuilder<'a, C, B>
+    where
+        B: ToTupleBuffer + ?Sized,
+    {
+        CallBuilder {
+            client: self.client,
+            fn_name: self.fn_name,
+            args,
+            timeout: self.timeout,
+        }
+    }
+
+    /// Set the maximum time to wait for the response. By default there's no
+    /// timeout.
+    ///
+    /// Note that expiration of the timeout doesn't guarantee that the
+    /// execution of the function stops on the remote node.
+    #[inline(always)]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl<'a, C, A> CallBuilder<'a, C, A>
+where
+    C: AsClient,
+    A: ToTupleBuffer + ?Sized,
+{
+    /// Send the request and wait for the response.
+    ///
+    /// Returns [`timeout::Error::Expired`] if the timeout was set and the
+    /// response didn't arrive in time.
+    pub async fn send(self) -> timeout::Result<Tuple, ClientError> {
+        let call = self.client.call(self.fn_name, self.args);
+        match self.timeout {
+            Some(timeout) => call.timeout(timeout).await,
+            None => Ok(call.await?),
+        }
+    }
+
+    /// Send the request, wait for the response and decode it into `R`.
+    ///
+    /// See also [`Self::send`].
+    pub async fn send_and_decode<R>(self) -> timeout::Result<R, ClientError>
+    where
+        R: DecodeOwned,
+    {
+        let tuple = self.send().await?;
+        let res = tuple.decode().map_err(ClientError::ResponseDecode)?;
+        Ok(res)
+    }
+}
@@ -26,6 +26,18 @@
 //! gets back a correct response. Reducing the number of active sockets lowers the overhead of system calls and increases
 //! the overall server performance.
 //!
+//! # Authentication
+//! The credentials from [`protocol::Config::creds`] are sent once after the
+//! connection is established, and all the requests sent via the connection
+//! are executed on behalf of that user. This is how the iproto protocol works:
+//! the authenticated user belongs to the session (i.e. the connection), not to
+//! individual requests or streams, so there's no way to send some of the
+//! requests via the same connection as a different user.
+//!
+//! If requests need to be executed on behalf of several users (e.g. in a
+//! multi-tenant proxy), create a separate [`Client`] for each user and reuse
+//! it for that user's requests.
+//!
 //! # Implementation
 //! Internally the client uses [`Protocol`] to get bytes that it needs to send
 //! and push bytes that it gets from the network.
@@ -33,15 +45,22 @@
 //! On creation the client spawns sender and receiver worker threads. Which in turn
 //! use coio based [`TcpStream`] as the transport layer.
 
+pub mod box_info;
+pub mod call_builder;
 pub mod reconnect;
 pub mod tcp;
 
 use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::io;
 use std::io::Cursor;
+use std::net::SocketAddr;
 use std::rc::Rc;
 use std::sync::Arc;
 use std::time::Duration;
 
+use self::box_info::BoxInfo;
+pub use self::call_builder::CallBuilder;
 use self::tcp::TcpStream;
 
 use super::protocol::api::{Call, Eval, Execute, Ping, Request};
@@ -50,6 +69,7 @@ use crate::error;
 use crate::error::BoxError;
 use crate::fiber;
 use crate::fiber::r#async::oneshot;
+use crate::fiber::r#async::timeout::{self, IntoTimeout as _};
 use crate::fiber::r#async::IntoOnDrop as _;
 use crate::fiber::FiberId;
 use crate::fiber::NoYieldsRefCell;
@@ -105,6 +125,46 @@ impl From<ClientError> for crate::error::Error {
     }
 }
 
+/// Error returned by [`Client::connect_any`] if it failed to connect to each
+/// of the addresses.
+#[derive(Debug)]
+pub struct ConnectAnyError {
+    /// The addresses and the errors of the connection attempts in the order
+    /// they were made. Empty if there were no addresses to connect to.
+    pub errors: Vec<((String, u16), ClientError)>,
+}
+
+impl std::fmt::Display for ConnectAnyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.errors.is_empty() {
+            return f.write_str("no addresses to connect to");
+        }
+        f.write_str("failed to connect to any of the addresses: ")?;
+        for (i, ((url, port), e)) in self.errors.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{url}:{port}: {e}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConnectAnyError {
+    /// Returns the error of the last connection attempt.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        let (_, e) = self.errors.last()?;
+        Some(e)
+    }
+}
+
+impl From<ConnectAnyError> for crate::error::Error {
+    #[inline(always)]
+    fn from(err: ConnectAnyError) -> Self {
+        crate::error::Error::other(err)
+    }
+}
+
 #[derive(Clone, Debug)]
 enum State {
     Alive,
@@ -127,13 +187,22 @@ impl State {
 struct ClientInner {
     protocol: Protocol,
     awaiting_response: HashMap<SyncIndex, oneshot::Sender<Result<(), Arc<error::Error>>>>,
+    /// See [`protocol::Config::max_in_flight`].
+    max_in_flight: Option<usize>,
+    /// Requests waiting for a free slot when `max_in_flight` is reached.
+    in_flight_waiters: VecDeque<oneshot::Sender<()>>,
+    /// Number of slots given to the woken up waiters, which haven't taken
+    /// them yet.
+    reserved_in_flight_slots: usize,
     state: State,
     /// The same tcp stream sender & receiver fibers a working with. Only stored
     /// here for closing.
     stream: TcpStream,
     sender_fiber_id: Option<FiberId>,
     receiver_fiber_id: Option<FiberId>,
+    keepalive_fiber_id: Option<FiberId>,
     clients_count: usize,
+    buffer_pool: BufferPool,
 }
 
 impl ClientInner {
@@ -145,17 +214,62 @@ impl ClientInner {
             )
         }
         Self {
+            // Zero would mean no request can ever be sent
+            max_in_flight: config.max_in_flight.filter(|&max| max > 0),
+            in_flight_waiters: VecDeque::new(),
+            reserved_in_flight_slots: 0,
             protocol: Protocol::with_config(config),
             awaiting_response: HashMap::new(),
             state: State::Alive,
             stream,
             sender_fiber_id: None,
             receiver_fiber_id: None,
+            keepalive_fiber_id: None,
             clients_count: 1,
+            buffer_pool: BufferPool::default(),
         }
     }
 }
 
+/// A pool of buffers for outgoing data, which allows to avoid allocating
+/// a new buffer for each write to the stream.
+#[derive(Debug, Default)]
+struct BufferPool {
+    buffers: Vec<Vec<u8>>,
+    /// Number of times a buffer was requested while the pool was empty.
+    #[cfg(feature = "internal_test")]
+    misses: usize,
+}
+
+impl BufferPool {
+    /// Maximum number of buffers kept in the pool.
+    const MAX_BUFFERS: usize = 8;
+    /// Buffers with capacity greater than this are not returned to the
+    /// pool, so that the memory isn't held after a single large request.
+    const MAX_CAPACITY: usize = 1024 * 1024;
+
+    /// Returns an empty buffer from the pool or a new one if the pool is empty.
+    fn take(&mut self) -> Vec<u8> {
+        if let Some(buf) = self.buffers.pop() {
+            return buf;
+        }
+        #[cfg(feature = "internal_test")]
+        {
+            self.misses += 1;
+        }
+        Vec::new()
+    }
+
+    /// Returns the buffer into the pool.
+    fn put(&mut self, mut buf: Vec<u8>) {
+        if self.buffers.len() >= Self::MAX_BUFFERS || buf.capacity() > Self::MAX_CAPACITY {
+            return;
+        }
+        buf.clear();
+        self.buffers.push(buf);
+    }
+}
+
 /// Wakes sender if `protocol` has new outgoing data.
 fn maybe_wake_sender(client: &ClientInner) {
     if client.protocol.ready_outgoing_len() == 0 {
@@ -174,7 +288,17 @@ fn maybe_wake_sender(client: &ClientInner) {
 /// See [`super::client`] for examples and [`AsClient`] trait for API.
 // WARNING: Attention should be payed not to borrow inner client across await and yield points.
 #[derive(Debug)]
-pub struct Client(Rc<NoYieldsRefCell<ClientInner>>);
+pub struct Client(Rc<NoYieldsRefCell<ClientInner>>, Rc<Endpoint>);
+
+/// The address a [`Client`] is connected to. Never changes after the
+/// connection is established, so it's stored outside of [`ClientInner`] to
+/// allow borrowing from it.
+#[derive(Debug)]
+struct Endpoint {
+    url: String,
+    port: u16,
+    socket_addr: Option<SocketAddr>,
+}
 
 impl Client {
     /// Creates a new client and tries to establish connection
@@ -201,8 +325,14 @@ impl Client {
         config: protocol::Config,
     ) -> Result<Self, ClientError> {
         let timeout = config.connect_timeout.unwrap_or(Duration::MAX);
+        let keepalive_interval = config.keepalive_interval;
         let stream = TcpStream::connect_timeout(url, port, timeout)
             .map_err(|e| ClientError::ConnectionClosed(Arc::new(e.into())))?;
+        let endpoint = Endpoint {
+            url: url.into(),
+            port,
+            socket_addr: stream.peer_addr().ok(),
+        };
         let client = ClientInner::new(config, stream.clone());
         let client = Rc::new(NoYieldsRefCell::new(client));
 
@@ -218,21 +348,107 @@ impl Client {
             .start_non_joinable()
             .unwrap();
 
+        let keepalive_fiber_id = keepalive_interval.map(|interval| {
+            let client = client.clone();
+            fiber::Builder::new()
+                .func(move || keepalive(client, interval))
+                .name(format!("iproto-ping/{url}:{port}"))
+                .start_non_joinable()
+                .unwrap()
+        });
+
         {
             let mut client_mut = client.borrow_mut();
             client_mut.receiver_fiber_id = Some(receiver_fiber_id);
             client_mut.sender_fiber_id = Some(sender_fiber_id);
+            client_mut.keepalive_fiber_id = keepalive_fiber_id;
         }
 
-        Ok(Self(client))
+        Ok(Self(client, Rc::new(endpoint)))
     }
 
-    fn check_state(&self) -> Result<(), Arc<error::Error>> {
-        match &self.0.borrow().state {
-            State::Alive => Ok(()),
-            State::ClosedManually => unreachable!("All client handles are dropped at this point"),
-            State::ClosedWithError(err) => Err(err.clone()),
+    /// Tries to establish connection to each of the `addresses` in order
+    /// and returns the client for the first one which succeeded.
+    ///
+    /// Each attempt uses the given `config` (so `config.connect_timeout` is
+    /// applied to each address separately). Shuffle the addresses beforehand
+    /// if the load must be spread between them.
+    ///
+    /// # Errors
+    /// If connection to all of the addresses failed, a [`ConnectAnyError`]
+    /// containing the error for each of the addresses is returned.
+    pub async fn connect_any(
+        addresses: &[(&str, u16)],
+        config: protocol::Config,
+    ) -> Result<Self, ConnectAnyError> {
+        let mut errors = Vec::with_capacity(addresses.len());
+        for &(url, port) in addresses {
+            match Self::connect_with_config(url, port, config.clone()).await {
+                Ok(client) => return Ok(client),
+                Err(e) => errors.push(((url.to_owned(), port), e)),
+            }
         }
+        Err(ConnectAnyError { errors })
+    }
+
+    /// Returns the `url` and `port` the client was connected to, i.e. the
+    /// ones passed to [`Client::connect`].
+    pub fn addr(&self) -> (&str, u16) {
+        (&self.1.url, self.1.port)
+    }
+
+    /// Returns the socket address `url` was resolved to when connecting, or
+    /// `None` if it couldn't be determined.
+    pub fn socket_addr(&self) -> Option<SocketAddr> {
+        self.1.socket_addr
+    }
+
+    /// Returns the greeting received from the server, or `None` if it wasn't
+    /// received yet.
+    ///
+    /// The greeting is received in the background right after the connection
+    /// is established, so it's guaranteed to be available once any request
+    /// (e.g. [`AsClient::ping`]) completes. Note that if no credentials are
+    /// set in [`protocol::Config::creds`] the requests are sent without
+    /// authentication.
+    ///
+    /// The salt from the greeting can be used for authentication methods
+    /// which aren't supported out of the box, see [`Protocol::greeting`].
+    pub fn greeting(&self) -> Option<protocol::Greeting> {
+        self.0.borrow().protocol.greeting().cloned()
+    }
+
+    /// Returns the schema version of the server from the header of the last
+    /// received response, or `None` if no responses were received yet.
+    ///
+    /// See [`Protocol::schema_version`].
+    pub fn schema_version(&self) -> Option<u64> {
+        self.0.borrow().protocol.schema_version()
+    }
+
+    /// Send [`Request`] and wait for response, same as [`AsClient::send`],
+    /// but also return the round-trip latency of the request, i.e. the time
+    /// passed since the request was submitted until the response was received.
+    ///
+    /// The latency is measured with [`Instant::now_accurate`], so it includes
+    /// the time spent waiting for the request to be written to the socket.
+    ///
+    /// [`Instant::now_accurate`]: crate::time::Instant::now_accurate
+    pub async fn send_timed<R: Request>(
+        &self,
+        request: &R,
+    ) -> Result<(R::Response, Duration), ClientError> {
+        let start = crate::time::Instant::now_accurate();
+        let response = self.send(request).await?;
+        Ok((response, start.elapsed()))
+    }
+}
+
+fn check_state(state: &State) -> Result<(), Arc<error::Error>> {
+    match state {
+        State::Alive => Ok(()),
+        State::ClosedManually => unreachable!("All client handles are dropped at this point"),
+        State::ClosedWithError(err) => Err(err.clone()),
     }
 }
 
@@ -264,6 +480,16 @@ pub trait AsClient {
         self.send(&Call { fn_name, args }).await
     }
 
+    /// Start building a remote stored procedure call, which allows setting
+    /// additional options like a timeout. See [`CallBuilder`].
+    #[inline(always)]
+    fn call_builder<'a>(&'a self, fn_name: &'a str) -> CallBuilder<'a, Self>
+    where
+        Self: Sized,
+    {
+        CallBuilder::new(self, fn_name)
+    }
+
     /// Evaluates and executes the expression in Lua-string, which may be any statement or series of statements.
     ///
     /// An execute privilege is required; if the user does not have it, an administrator may grant it with
@@ -285,54 +511,182 @@ pub trait AsClient {
     {
         self.send(&Execute { sql, bind_params }).await
     }
+
+    /// Get the information about the remote instance, including its
+    /// replication status. See [`BoxInfo`].
+    ///
+    /// Requires the same privileges as [`AsClient::eval`].
+    async fn box_info(&self) -> Result<BoxInfo, ClientError> {
+        let tuple = self.eval(box_info::BOX_INFO_LUA, &()).await?;
+        let (info,) = tuple.decode().map_err(ClientError::ResponseDecode)?;
+        Ok(info)
+    }
+
+    /// Same as [`AsClient::ping`], but returns [`timeout::Error::Expired`] if
+    /// the response doesn't arrive within `timeout`.
+    async fn ping_timeout(&self, timeout: Duration) -> timeout::Result<(), ClientError> {
+        self.ping().timeout(timeout).await
+    }
+
+    /// Same as [`AsClient::call`], but returns [`timeout::Error::Expired`] if
+    /// the response doesn't arrive within `timeout`.
+    ///
+    /// Note that expiration of the timeout doesn't guarantee that the
+    /// execution of the function stops on the remote node.
+    async fn call_timeout<T>(
+        &self,
+        fn_name: &str,
+        args: &T,
+        timeout: Duration,
+    ) -> timeout::Result<Tuple, ClientError>
+    where
+        T: ToTupleBuffer + ?Sized,
+    {
+        self.call(fn_name, args).timeout(timeout).await
+    }
+
+    /// Same as [`AsClient::eval`], but returns [`timeout::Error::Expired`] if
+    /// the response doesn't arrive within `timeout`.
+    ///
+    /// Note that expiration of the timeout doesn't guarantee that the
+    /// execution of the expression stops on the remote node.
+    async fn eval_timeout<T>(
+        &self,
+        expr: &str,
+        args: &T,
+        timeout: Duration,
+    ) -> timeout::Result<Tuple, ClientError>
+    where
+        T: ToTupleBuffer + ?Sized,
+    {
+        self.eval(expr, args).timeout(timeout).await
+    }
+
+    /// Same as [`AsClient::execute`], but returns [`timeout::Error::Expired`]
+    /// if the response doesn't arrive within `timeout`.
+    async fn execute_timeout<T>(
+        &self,
+        sql: &str,
+        bind_params: &T,
+        timeout: Duration,
+    ) -> timeout::Result<Vec<Tuple>, ClientError>
+    where
+        T: ToTupleBuffer + ?Sized,
+    {
+        self.execute(sql, bind_params).timeout(timeout).await
+    }
 }
 
 #[async_trait::async_trait(?Send)]
 impl AsClient for Client {
+    #[inline(always)]
     async fn send<R: Request>(&self, request: &R) -> Result<R::Response, ClientError> {
-        if let Err(e) = self.check_state() {
-            return Err(ClientError::ConnectionClosed(e));
-        }
+        send_request(&self.0, request).await
+    }
+}
 
-        let res = self.0.borrow_mut().protocol.send_request(request);
-        let sync = unwrap_ok_or!(res,
-            Err(e) => {
-                return Err(ClientError::RequestEncode(e));
-            }
-        );
+/// Sends the `request` via the `client` and waits for the response.
+///
+/// Waits for a free slot first, see [`protocol::Config::max_in_flight`].
+async fn send_request<R: Request>(
+    client: &Rc<NoYieldsRefCell<ClientInner>>,
+    request: &R,
+) -> Result<R::Response, ClientError> {
+    if let Err(e) = check_state(&client.borrow().state) {
+        return Err(ClientError::ConnectionClosed(e));
+    }
+    wait_for_in_flight_slot(client).await?;
+    // NOTE: there must be no yields between getting the slot and taking it
+    // by adding an entry to `awaiting_response`.
+    send_request_unlimited(client, request).await
+}
 
-        let (tx, rx) = oneshot::channel();
-        self.0.borrow_mut().awaiting_response.insert(sync, tx);
-        maybe_wake_sender(&self.0.borrow());
-        // Cleanup `awaiting_response` entry in case of `send` future cancelation
-        // at this `.await`.
-        // `send` can be canceled for example with `Timeout`.
-        let res = rx
-            .on_drop(|| {
-                let _ = self.0.borrow_mut().awaiting_response.remove(&sync);
-            })
-            .await
-            .expect("Channel should be open");
-        if let Err(e) = res {
-            return Err(ClientError::ConnectionClosed(e));
+/// Waits until there's a free slot for a request, see
+/// [`protocol::Config::max_in_flight`].
+///
+/// The requests get the slots in the order they started waiting: once a slot
+/// is freed it's given to the first waiter, so that requests sent later can't
+/// take it.
+async fn wait_for_in_flight_slot(
+    client: &Rc<NoYieldsRefCell<ClientInner>>,
+) -> Result<(), ClientError> {
+    let rx = {
+        let mut client = client.borrow_mut();
+        match client.max_in_flight {
+            Some(max)
+                if client.awaiting_response.len() + client.reserved_in_flight_slots >= max => {}
+            _ => return Ok(()),
         }
+        let (tx, rx) = oneshot::channel();
+        client.in_flight_waiters.push_back(tx);
+        rx
+    };
+    let mut waiter = InFlightWaiter { client, rx };
+    // The sender is dropped if the connection is closed
+    let res = (&mut waiter.rx).await;
+    drop(waiter);
+    if res.is_ok() {
+        // The slot was given to us, it's taken by the caller
+        client.borrow_mut().reserved_in_flight_slots -= 1;
+    }
+    if let Err(e) = check_state(&client.borrow().state) {
+        return Err(ClientError::ConnectionClosed(e));
+    }
+    Ok(())
+}
 
-        let res = self
-            .0
-            .borrow_mut()
-            .protocol
-            .take_response::<R>(sync)
-            .expect("Is present at this point");
-        let response = unwrap_ok_or!(res,
-            Err(error::Error::Remote(response)) => {
-                return Err(ClientError::ErrorResponse(response));
-            }
-            Err(e) => {
-                return Err(ClientError::ResponseDecode(e));
+/// Sends the `request` via the `client` and waits for the response without
+/// checking [`protocol::Config::max_in_flight`].
+async fn send_request_unlimited<R: Request>(
+    client: &Rc<NoYieldsRefCell<ClientInner>>,
+    request: &R,
+) -> Result<R::Response, ClientError> {
+    if let Err(e) = check_state(&client.borrow().state) {
+        return Err(ClientError::ConnectionClosed(e));
+    }
+
+    let res = client.borrow_mut().protocol.send_request(request);
+    let sync = unwrap_ok_or!(res,
+        Err(e) => {
+            // The slot is not used after all
+            wake_in_flight_waiters(&mut client.borrow_mut());
+            return Err(ClientError::RequestEncode(e));
+        }
+    );
+
+    let (tx, rx) = oneshot::channel();
+    client.borrow_mut().awaiting_response.insert(sync, tx);
+    maybe_wake_sender(&client.borrow());
+    // Cleanup `awaiting_response` entry in case of `send` future cancelation
+    // at this `.await`.
+    // `send` can be canceled for example with `Timeout`.
+    let res = rx
+        .on_drop(|| {
+            let mut client = client.borrow_mut();
+            if client.awaiting_response.remove(&sync).is_some() {
+                wake_in_flight_waiters(&mut client);
             }
-        );
-        Ok(response)
+        })
+        .await
+        .expect("Channel should be open");
+    if let Err(e) = res {
+        return Err(ClientError::ConnectionClosed(e));
     }
+
+    let res = client
+        .borrow_mut()
+        .protocol
+        .take_response::<R>(sync)
+        .expect("Is present at this point");
+    let response = unwrap_ok_or!(res,
+        Err(error::Error::Remote(response)) => {
+            return Err(ClientError::ErrorResponse(response));
+        }
+        Err(e) => {
+            return Err(ClientError::ResponseDecode(e));
+        }
+    );
+    Ok(response)
 }
 
 impl Drop for Client {
@@ -345,6 +699,7 @@ impl Drop for Client {
 
             let receiver_fiber_id = client.receiver_fiber_id;
             let sender_fiber_id = client.sender_fiber_id;
+            let keepalive_fiber_id = client.keepalive_fiber_id;
 
             // We need to close the stream here, because otherwise receiver will
             // never wake up, because our async runtime blocks forever until the
@@ -366,6 +721,11 @@ impl Drop for Client {
                 fiber::cancel(id);
                 fiber::wakeup(id);
             }
+
+            if let Some(id) = keepalive_fiber_id {
+                fiber::cancel(id);
+                fiber::wakeup(id);
+            }
         } else {
             self.0.borrow_mut().clients_count -= 1;
         }
@@ -375,7 +735,7 @@ impl Drop for Client {
 impl Clone for Client {
     fn clone(&self) -> Self {
         self.0.borrow_mut().clients_count += 1;
-        Self(self.0.clone())
+        Self(self.0.clone(), self.1.clone())
     }
 }
 
@@ -384,20 +744,101 @@ macro_rules! handle_result {
         match $e {
             Ok(value) => value,
             Err(err) => {
-                let err = Arc::new(error::Error::from(err));
-                // Notify all subscribers on closing
-                let subscriptions: HashMap<_, _> = $client.awaiting_response.drain().collect();
-                for (_, subscription) in subscriptions {
-                    // We don't care about errors at this point
-                    let _ = subscription.send(Err(err.clone()));
-                }
-                $client.state = State::ClosedWithError(err);
+                close_with_error(&mut $client, error::Error::from(err));
                 return;
             }
         }
     };
 }
 
+/// Notifies all the subscribers about the error and marks the connection as
+/// closed. Does nothing if the connection is already closed, so that the
+/// original error is preserved.
+fn close_with_error(client: &mut ClientInner, err: error::Error) {
+    if client.state.is_closed() {
+        return;
+    }
+    let err = Arc::new(err);
+    // Notify all subscribers on closing
+    let subscriptions: HashMap<_, _> = client.awaiting_response.drain().collect();
+    for (_, subscription) in subscriptions {
+        // We don't care about errors at this point
+        let _ = subscription.send(Err(err.clone()));
+    }
+    client.state = State::ClosedWithError(err);
+    // Waiters will see the error once their channels are closed
+    client.in_flight_waiters.clear();
+}
+
+/// A request waiting for a free slot, see [`protocol::Config::max_in_flight`].
+///
+/// If the request is canceled after it was woken up, but before it took the
+/// slot, the wakeup is passed on to the next waiter, otherwise it would be
+/// lost and the slot would remain free until some other response arrives.
+struct InFlightWaiter<'a> {
+    client: &'a NoYieldsRefCell<ClientInner>,
+    rx: oneshot::Receiver<()>,
+}
+
+impl Drop for InFlightWaiter<'_> {
+    fn drop(&mut self) {
+        if self.rx.try_recv().is_some() {
+            let mut client = self.client.borrow_mut();
+            client.reserved_in_flight_slots -= 1;
+            wake_in_flight_waiters(&mut client);
+        }
+    }
+}
+
+/// Gives the free slots to the requests waiting for them in the order they
+/// started waiting, see [`protocol::Config::max_in_flight`]. Waiters which
+/// were canceled are skipped.
+fn wake_in_flight_waiters(client: &mut ClientInner) {
+    let Some(max) = client.max_in_flight else {
+        return;
+    };
+    while client.awaiting_response.len() + client.reserved_in_flight_slots < max {
+        let Some(waiter) = client.in_flight_waiters.pop_front() else {
+            break;
+        };
+        if waiter.send(()).is_ok() {
+            client.reserved_in_flight_slots += 1;
+        }
+    }
+}
+
+/// Keepalive work loop. Sends a ping every `interval` and closes the
+/// connection if the response doesn't arrive within the next `interval`.
+fn keepalive(client: Rc<NoYieldsRefCell<ClientInner>>, interval: Duration) {
+    loop {
+        fiber::sleep(interval);
+        if client.borrow().state.is_closed() || fiber::is_cancelled() {
+            return;
+        }
+        // The pings aren't subject to `max_in_flight`, otherwise slow but
+        // healthy requests could make them time out
+        let res = fiber::block_on(send_request_unlimited(&client, &Ping).timeout(interval));
+        match res {
+            Ok(()) => {}
+            Err(timeout::Error::Expired) => {
+                let err = io::Error::new(io::ErrorKind::TimedOut, "keepalive ping timed out");
+                let mut client = client.borrow_mut();
+                close_with_error(&mut client, err.into());
+                // Wake up the receiver, see `Client::drop`
+                if let Err(e) = client.stream.close() {
+                    crate::say_error!("keepalive: failed closing tcp stream: {e}");
+                }
+                if let Some(id) = client.sender_fiber_id {
+                    fiber::wakeup(id);
+                }
+                return;
+            }
+            // The connection is closed, nothing to do here
+            Err(timeout::Error::Failed(_)) => return,
+        }
+    }
+}
+
 /// Sender work loop. Yields on each iteration and during awaits.
 async fn sender(client: Rc<NoYieldsRefCell<ClientInner>>, mut writer: TcpStream) {
     loop {
@@ -405,13 +846,22 @@ async fn sender(client: Rc<NoYieldsRefCell<ClientInner>>, mut writer: TcpStream)
             return;
         }
         // TODO: limit max send size
-        let data = client.borrow_mut().protocol.take_outgoing_data();
-        if data.is_empty() {
-            // Wait for explicit wakeup, it should happen when there is new outgoing data
-            fiber::fiber_yield();
-        } else {
+        let data = {
+            let mut client = client.borrow_mut();
+            if client.protocol.ready_outgoing_len() == 0 {
+                None
+            } else {
+                let buf = client.buffer_pool.take();
+                Some(client.protocol.replace_outgoing_data(buf))
+            }
+        };
+        if let Some(data) = data {
             let result = writer.write_all(&data).await;
             handle_result!(client.borrow_mut(), result);
+            client.borrow_mut().buffer_pool.put(data);
+        } else {
+            // Wait for explicit wakeup, it should happen when there is new outgoing data
+            fiber::fiber_yield();
         }
     }
 }
@@ -447,12 +897,14 @@ async fn receiver(client_cell: Rc<NoYieldsRefCell<ClientInner>>, mut reader: Tcp
             .protocol
             .process_incoming(&mut Cursor::new(buf_slice));
         let result = handle_result!(client, result);
-        if let Some(sync) = result {
+        let released = client.protocol.take_released_responses();
+        for sync in result.into_iter().chain(released) {
             let subscription = client.awaiting_response.remove(&sync);
             if let Some(subscription) = subscription {
                 subscription
                     .send(Ok(()))
                     .expect("cannot be closed at this point");
+                wake_in_flight_waiters(&mut client);
             } else {
                 crate::say_warn!("received unwaited message for {sync:?}");
             }
@@ -467,7 +919,6 @@ async fn receiver(client_cell: Rc<NoYieldsRefCell<ClientInner>>, mut reader: Tcp
 mod tests {
     use super::*;
     use crate::error::TarantoolErrorCode;
-    use crate::fiber::r#async::timeout::IntoTimeout as _;
     use crate::space::Space;
     use crate::test::util::listen_port;
     use std::time::Duration;
@@ -510,6 +961,19 @@ mod tests {
         let _client = Client::connect("localhost", listen_port()).await.unwrap();
     }
 
+    #[crate::test(tarantool = "crate")]
+    async fn addr() {
+        let client = Client::connect("localhost", listen_port()).await.unwrap();
+        assert_eq!(client.addr(), ("localhost", listen_port()));
+        let socket_addr = client.socket_addr().unwrap();
+        assert_eq!(socket_addr.port(), listen_port());
+        assert!(socket_addr.ip().is_loopback());
+
+        let clone = client.clone();
+        assert_eq!(clone.addr(), client.addr());
+        assert_eq!(clone.socket_addr(), client.socket_addr());
+    }
+
     #[crate::test(tarantool = "crate")]
     async fn connect_failure() {
         // Can be any other unused port
@@ -526,6 +990,104 @@ mod tests {
         }
     }
 
+    #[crate::test(tarantool = "crate")]
+    async fn connect_any() {
+        let config = protocol::Config {
+            creds: Some(("test_user".into(), "password".into())),
+            ..Default::default()
+        };
+        let client = Client::connect_any(
+            &[("localhost", 0), ("localhost", listen_port())],
+            config.clone(),
+        )
+        .timeout(Duration::from_secs(3))
+        .await
+        .unwrap();
+        client.ping().timeout(Duration::from_secs(3)).await.unwrap();
+
+        let err = Client::connect_any(&[("localhost", 0), ("127.0.0.1", 0)], config.clone())
+            .timeout(Duration::from_secs(3))
+            .await
+            .unwrap_err();
+        let timeout::Error::Failed(err) = err else {
+            panic!("{}", err);
+        };
+        let addrs: Vec<_> = err.errors.iter().map(|(addr, _)| addr.clone()).collect();
+        assert_eq!(
+            addrs,
+            [("localhost".to_owned(), 0), ("127.0.0.1".to_owned(), 0)]
+        );
+        for (_, e) in &err.errors {
+            assert!(matches!(e, ClientError::ConnectionClosed(_)), "{}", e);
+        }
+        let source = std::error::Error::source(&err).unwrap().to_string();
+        assert_eq!(source, err.errors[1].1.to_string());
+        let message = err.to_string();
+        assert!(
+            message.starts_with("failed to connect to any of the addresses: localhost:0: "),
+            "{}",
+            message
+        );
+        assert!(message.contains("; 127.0.0.1:0: "), "{}", message);
+
+        let err = Client::connect_any(&[], config).await.unwrap_err();
+        assert!(err.errors.is_empty());
+        assert_eq!(err.to_string(), "no addresses to connect to");
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn outgoing_buffers_are_reused() {
+        let client = test_client().await;
+
+        for _ in 0..10_000 {
+            client.ping().timeout(Duration::from_secs(3)).await.unwrap();
+        }
+        // Only the first few writes had to allocate a new buffer, all the
+        // other ones got it from the pool
+        let misses = client.0.borrow().buffer_pool.misses;
+        assert!(misses <= 2, "{}", misses);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn send_timed() {
+        let client = test_client().await;
+
+        let ((), latency) = client
+            .send_timed(&Ping)
+            .timeout(Duration::from_secs(3))
+            .await
+            .unwrap();
+        assert!(latency > Duration::ZERO);
+
+        let (_, latency) = client
+            .send_timed(&Eval {
+                expr: "require('fiber').sleep(0.1)",
+                args: &(),
+            })
+            .timeout(Duration::from_secs(3))
+            .await
+            .unwrap();
+        assert!(latency >= Duration::from_millis(100), "{:?}", latency);
+        assert!(latency < Duration::from_secs(3), "{:?}", latency);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn greeting() {
+        // No credentials, so no authentication is done automatically
+        let client = Client::connect("localhost", listen_port())
+            .timeout(Duration::from_secs(3))
+            .await
+            .unwrap();
+        client.ping().timeout(Duration::from_secs(3)).await.unwrap();
+
+        let greeting = client.greeting().unwrap();
+        assert!(!greeting.salt.is_empty());
+        let version = crate::lua_state()
+            .eval::<String>("return box.info.version")
+            .unwrap();
+        assert_eq!(greeting.version, version);
+    }
+
     #[crate::test(tarantool = "crate")]
     fn ping_concurrent() {
         let client = fiber::block_on(test_client());
@@ -539,6 +1101,152 @@ mod tests {
         fiber_b.join();
     }
 
+    #[crate::test(tarantool = "crate")]
+    fn max_in_flight() {
+        use crate::network::protocol::IProtoType;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let evals = Arc::new(AtomicUsize::new(0));
+        let client = fiber::block_on(Client::connect_with_config(
+            "localhost",
+            listen_port(),
+            protocol::Config {
+                creds: Some(("test_user".into(), "password".into())),
+                max_in_flight: Some(2),
+                on_request: Some(protocol::Hook::new({
+                    let evals = evals.clone();
+                    move |_, ty| {
+                        if ty == IProtoType::Eval as u32 {
+                            evals.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                })),
+                ..Default::default()
+            },
+        ))
+        .unwrap();
+
+        let client = &client;
+        let slow = |secs: f64| async move {
+            client
+                .eval("require('fiber').sleep(...)", &(secs,))
+                .timeout(Duration::from_secs(3))
+                .await
+        };
+        let fiber_a = fiber::start_async(async { slow(0.2).await.unwrap() });
+        let fiber_b = fiber::start_async(async { slow(0.4).await.unwrap() });
+        let fiber_c = fiber::start_async(async { slow(0.0).await.unwrap() });
+
+        // The third request waits for a free slot
+        fiber::sleep(Duration::from_millis(100));
+        assert_eq!(evals.load(Ordering::Relaxed), 2);
+
+        // It is sent once the response to the first one arrives
+        fiber_a.join();
+        fiber_c.join();
+        assert_eq!(evals.load(Ordering::Relaxed), 3);
+        fiber_b.join();
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn max_in_flight_zero_means_no_limit() {
+        let client = Client::connect_with_config(
+            "localhost",
+            listen_port(),
+            protocol::Config {
+                creds: Some(("test_user".into(), "password".into())),
+                max_in_flight: Some(0),
+                ..Default::default()
+            },
+        )
+        .timeout(Duration::from_secs(3))
+        .await
+        .unwrap();
+        client.ping().timeout(Duration::from_secs(3)).await.unwrap();
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn keepalive_with_max_in_flight() {
+        use crate::network::protocol::IProtoType;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let pings = Arc::new(AtomicUsize::new(0));
+        let client = Client::connect_with_config(
+            "localhost",
+            listen_port(),
+            protocol::Config {
+                creds: Some(("test_user".into(), "password".into())),
+                keepalive_interval: Some(Duration::from_millis(50)),
+                max_in_flight: Some(1),
+                on_request: Some(protocol::Hook::new({
+                    let pings = pings.clone();
+                    move |_, ty| {
+                        if ty == IProtoType::Ping as u32 {
+                            pings.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                })),
+                ..Default::default()
+            },
+        )
+        .timeout(Duration::from_secs(3))
+        .await
+        .unwrap();
+
+        // The only slot is taken by a request which takes several keepalive
+        // intervals, but the pings are still sent and the connection is kept
+        client
+            .eval("require('fiber').sleep(...)", &(0.3,))
+            .timeout(Duration::from_secs(3))
+            .await
+            .unwrap();
+        let pings = pings.load(Ordering::Relaxed);
+        assert!(pings >= 3, "{}", pings);
+
+        client.ping().timeout(Duration::from_secs(3)).await.unwrap();
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn max_in_flight_woken_waiter_canceled() {
+        use std::task::Context;
+
+        let client = fiber::block_on(Client::connect_with_config(
+            "localhost",
+            listen_port(),
+            protocol::Config {
+                creds: Some(("test_user".into(), "password".into())),
+                max_in_flight: Some(1),
+                ..Default::default()
+            },
+        ))
+        .unwrap();
+
+        let client = &client;
+        let busy = fiber::start_async(async {
+            client
+                .eval("require('fiber').sleep(...)", &(0.1,))
+                .timeout(Duration::from_secs(3))
+                .await
+                .unwrap()
+        });
+
+        // Both requests wait for the slot taken by the busy one
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+        let mut first = client.ping();
+        let mut second = client.ping();
+        assert!(first.as_mut().poll(&mut cx).is_pending());
+        assert!(second.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(client.0.borrow().in_flight_waiters.len(), 2);
+
+        // The first waiter is woken up once the slot is freed, but it's
+        // canceled before it takes the slot
+        busy.join();
+        drop(first);
+
+        // So the second one gets the slot
+        fiber::block_on(second.timeout(Duration::from_secs(3))).unwrap();
+    }
+
     #[crate::test(tarantool = "crate")]
     async fn execute() {
         Space::find("test_s1")
@@ -588,6 +1296,261 @@ mod tests {
         assert_eq!(result.decode::<(i32,)>().unwrap(), (3,));
     }
 
+    #[crate::test(tarantool = "crate")]
+    async fn call_builder() {
+        let client = test_client().await;
+
+        let (sum,): (i32,) = client
+            .call_builder("test_stored_proc")
+            .args(&(1, 2))
+            .timeout(Duration::from_secs(3))
+            .send_and_decode()
+            .await
+            .unwrap();
+        assert_eq!(sum, 3);
+
+        // `test_timeout` sleeps for 1.5 seconds
+        let err = client
+            .call_builder("test_timeout")
+            .timeout(Duration::from_millis(10))
+            .send()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, timeout::Error::Expired));
+
+        // Client is still usable after the timeout
+        let tuple = client
+            .call_builder("test_stored_proc")
+            .args(&(2, 3))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(tuple.decode::<(i32,)>().unwrap(), (5,));
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn keepalive() {
+        use crate::network::protocol::IProtoType;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let pings = Arc::new(AtomicUsize::new(0));
+        let client = Client::connect_with_config(
+            "localhost",
+            listen_port(),
+            protocol::Config {
+                creds: Some(("test_user".into(), "password".into())),
+                keepalive_interval: Some(Duration::from_millis(50)),
+                on_request: Some(protocol::Hook::new({
+                    let pings = pings.clone();
+                    move |_, ty| {
+                        if ty == IProtoType::Ping as u32 {
+                            pings.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                })),
+                ..Default::default()
+            },
+        )
+        .timeout(Duration::from_secs(3))
+        .await
+        .unwrap();
+
+        // No requests are sent explicitly, but pings are
+        fiber::sleep(Duration::from_millis(300));
+        let pings = pings.load(Ordering::Relaxed);
+        assert!(pings >= 3, "{}", pings);
+
+        // The connection is still alive
+        let tuple = client
+            .call("test_stored_proc", &(1, 2))
+            .timeout(Duration::from_secs(3))
+            .await
+            .unwrap();
+        assert_eq!(tuple.decode::<(i32,)>().unwrap(), (3,));
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn request_response_hooks() {
+        use crate::network::protocol::IProtoType;
+        use std::sync::Mutex;
+
+        let requests = Arc::new(Mutex::new(vec![]));
+        let responses = Arc::new(Mutex::new(vec![]));
+        let client = Client::connect_with_config(
+            "localhost",
+            listen_port(),
+            protocol::Config {
+                creds: Some(("test_user".into(), "password".into())),
+                on_request: Some(protocol::Hook::new({
+                    let requests = requests.clone();
+                    move |sync, ty| requests.lock().unwrap().push((sync, ty))
+                })),
+                on_response: Some(protocol::Hook::new({
+                    let responses = responses.clone();
+                    move |sync, ty| responses.lock().unwrap().push((sync, ty))
+                })),
+                ..Default::default()
+            },
+        )
+        .timeout(Duration::from_secs(3))
+        .await
+        .unwrap();
+
+        for _ in 0..3 {
+            client.ping().timeout(Duration::from_secs(3)).await.unwrap();
+        }
+        for _ in 0..2 {
+            client
+                .call("test_stored_proc", &(1, 2))
+                .timeout(Duration::from_secs(3))
+                .await
+                .unwrap();
+        }
+        client
+            .call("unexistent_proc", &())
+            .timeout(Duration::from_secs(3))
+            .await
+            .unwrap_err();
+
+        let requests = requests.lock().unwrap();
+        let types: Vec<_> = requests.iter().map(|&(_, ty)| ty).collect();
+        let ping = IProtoType::Ping as u32;
+        let call = IProtoType::Call as u32;
+        // No schema requests are sent on connect (or ever)
+        assert_eq!(types, [ping, ping, ping, call, call, call]);
+
+        // Every request got a response with the same sync
+        let responses = responses.lock().unwrap();
+        assert_eq!(responses.len(), 6);
+        for (&(req_sync, _), &(resp_sync, _)) in requests.iter().zip(responses.iter()) {
+            assert_eq!(req_sync, resp_sync);
+        }
+        let ok = IProtoType::Ok as u32;
+        assert_eq!(responses[4].1, ok);
+        assert_ne!(responses[5].1, ok);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn lazy_auth() {
+        use crate::network::protocol::IProtoType;
+        use std::sync::Mutex;
+
+        let responses = Arc::new(Mutex::new(vec![]));
+        let client = Client::connect_with_config(
+            "localhost",
+            listen_port(),
+            protocol::Config {
+                creds: Some(("test_user".into(), "password".into())),
+                lazy_auth: true,
+                on_response: Some(protocol::Hook::new({
+                    let responses = responses.clone();
+                    move |_, ty| responses.lock().unwrap().push(ty)
+                })),
+                ..Default::default()
+            },
+        )
+        .timeout(Duration::from_secs(3))
+        .await
+        .unwrap();
+        // The request requires the privileges of test_user
+        let tuple = client
+            .call_timeout("test_stored_proc", &(1, 2), Duration::from_secs(3))
+            .await
+            .unwrap();
+        assert_eq!(tuple.decode::<(i32,)>().unwrap(), (3,));
+        // Only the response to the call is handled by the hook
+        assert_eq!(*responses.lock().unwrap(), [IProtoType::Ok as u32]);
+
+        // Failed authentication fails every pipelined request, even the ones
+        // which don't require any privileges
+        let client = Client::connect_with_config(
+            "localhost",
+            listen_port(),
+            protocol::Config {
+                creds: Some(("test_user".into(), "wrong password".into())),
+                lazy_auth: true,
+                ..Default::default()
+            },
+        )
+        .timeout(Duration::from_secs(3))
+        .await
+        .unwrap();
+        let (call, ping) = futures::join!(
+            client.call_timeout("test_stored_proc", &(1, 2), Duration::from_secs(3)),
+            client.ping_timeout(Duration::from_secs(3)),
+        );
+        for err in [call.unwrap_err(), ping.unwrap_err()] {
+            let err = match err {
+                timeout::Error::Failed(ClientError::ConnectionClosed(err)) => err,
+                err => panic!("unexpected error: {}", err),
+            };
+            assert!(err.to_string().contains("PasswordMismatch"), "{}", err);
+        }
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn schema_version() {
+        let client = test_client().await;
+        client.ping().timeout(Duration::from_secs(3)).await.unwrap();
+        let before = client.schema_version().unwrap();
+
+        let space = Space::builder("client_schema_version_test")
+            .create()
+            .unwrap();
+        client.ping().timeout(Duration::from_secs(3)).await.unwrap();
+        let after = client.schema_version().unwrap();
+        assert!(after > before, "{} <= {}", after, before);
+
+        space.drop().unwrap();
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn privileges_are_per_connection() {
+        let username = "test_client_limited_user";
+        let password = "password";
+        crate::lua_state()
+            .exec_with(
+                "local username, password = ...
+                box.schema.user.create(username, { if_not_exists = true, password = password })",
+                (username, password),
+            )
+            .unwrap();
+        let _guard = crate::test::util::on_scope_exit(|| {
+            crate::lua_state()
+                .exec_with("box.schema.user.drop(...)", username)
+                .unwrap();
+        });
+
+        let limited = Client::connect_with_config(
+            "localhost",
+            listen_port(),
+            protocol::Config {
+                creds: Some((username.into(), password.into())),
+                ..Default::default()
+            },
+        )
+        .timeout(Duration::from_secs(3))
+        .await
+        .unwrap();
+        let privileged = test_client().await;
+
+        // Both connections are used concurrently, but each request is executed
+        // with the privileges of the user of its connection
+        let (limited_res, privileged_res) = futures::join!(
+            limited.eval("return box.session.user()", &()),
+            privileged.eval("return box.session.user()", &()),
+        );
+
+        let err = error::Error::from(limited_res.unwrap_err());
+        let error::Error::Remote(err) = err else {
+            panic!("{}", err)
+        };
+        assert_eq!(err.error_code(), TarantoolErrorCode::AccessDenied as u32);
+
+        let user: (String,) = privileged_res.unwrap().decode().unwrap();
+        assert_eq!(user.0, "test_user");
+    }
+
     #[crate::test(tarantool = "crate")]
     async fn invalid_call() {
         let client = test_client().await;
@@ -599,6 +1562,7 @@ mod tests {
             .unwrap_err();
 
         let err = error::Error::from(err);
+        assert!(!err.is_retriable());
         let error::Error::Remote(err) = err else {
             panic!()
         };
@@ -636,6 +1600,27 @@ mod tests {
         assert_eq!(err.error_code(), 420);
     }
 
+    #[crate::test(tarantool = "crate")]
+    async fn box_info() {
+        let client = test_client().await;
+
+        let info = client
+            .box_info()
+            .timeout(Duration::from_secs(3))
+            .await
+            .unwrap();
+
+        let lua = crate::lua_state();
+        let (id, uuid): (u32, String) = lua.eval("return box.info.id, box.info.uuid").unwrap();
+        assert_eq!(info.id, Some(id));
+        assert_eq!(info.uuid, uuid);
+        assert_eq!(info.status, "running");
+        assert!(!info.vclock.is_empty());
+        assert_eq!(info.vclock.get(&id), Some(&info.lsn));
+        let me = info.replication.iter().find(|r| r.id == id).unwrap();
+        assert_eq!(me.uuid, uuid);
+    }
+
     /// A regression test for https://git.picodata.io/picodata/picodata/tarantool-module/-/merge_requests/302
     #[crate::test(tarantool = "crate")]
     async fn client_count_regression() {
@@ -662,7 +1647,7 @@ mod tests {
         assert_eq!(Rc::strong_count(&client.0), 1);
 
         // This would panic on unreachable if previous drop have set the state
-        client.check_state().unwrap_err();
+        check_state(&client.0.borrow().state).unwrap_err();
     }
 
     #[crate::test(tarantool = "crate")]
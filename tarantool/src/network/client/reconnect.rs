@@ -3,6 +3,9 @@ use crate::error::Error;
 use crate::fiber::r#async::Mutex;
 use crate::network::client::ClientError;
 use crate::network::protocol;
+use crate::network::protocol::codec::IProtoType;
+use std::cell::Cell;
+use std::collections::HashSet;
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -21,6 +24,9 @@ type ClientOrConnectionClosedError = Result<super::Client, Arc<Error>>;
 #[derive(Debug, Clone)]
 pub struct Client {
     client: Rc<Mutex<Option<ClientOrConnectionClosedError>>>,
+    /// Incremented on each connection attempt, so that a failure of an old
+    /// connection can be told apart from a failure of the current one.
+    generation: Rc<Cell<u64>>,
     url: String,
     port: u16,
     protocol_config: protocol::Config,
@@ -35,14 +41,24 @@ pub struct Client {
 impl Client {
     /// Provides an access to the underlying client behind mutex.
     /// If it is `None` - reconnects implicitly and returns a new client.
+    #[inline(always)]
     async fn client(&self) -> Result<super::Client, ClientError> {
+        self.client_and_generation().await.0
+    }
+
+    /// Same as [`Self::client`], but also returns the generation of the
+    /// connection, see [`Self::reconnect_if_current`].
+    async fn client_and_generation(&self) -> (Result<super::Client, ClientError>, u64) {
         let mut client = self.client.lock().await;
         match &*client {
             Some(Ok(client)) => {
-                return Ok(client.clone());
+                return (Ok(client.clone()), self.generation.get());
             }
             Some(Err(e)) => {
-                return Err(ClientError::ConnectionClosed(e.clone()));
+                return (
+                    Err(ClientError::ConnectionClosed(e.clone())),
+                    self.generation.get(),
+                );
             }
             None => {}
         }
@@ -51,6 +67,8 @@ impl Client {
         {
             self.reconnect_count.fetch_add(1, Ordering::Relaxed);
         }
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
 
         let res =
             super::Client::connect_with_config(&self.url, self.port, self.protocol_config.clone())
@@ -58,11 +76,11 @@ impl Client {
         match res {
             Ok(new_client) => {
                 *client = Some(Ok(new_client.clone()));
-                return Ok(new_client);
+                (Ok(new_client), generation)
             }
             Err(ClientError::ConnectionClosed(e)) => {
                 *client = Some(Err(e.clone()));
-                return Err(ClientError::ConnectionClosed(e));
+                (Err(ClientError::ConnectionClosed(e)), generation)
             }
             Err(_) => unreachable!(
                 "Client::connect_with_config should only return `ConnectionClosed` errors"
@@ -85,6 +103,18 @@ impl Client {
         }
     }
 
+    /// Same as [`Self::reconnect`], but only if `generation` is the
+    /// generation of the current connection. Does nothing if the client has
+    /// already reconnected since, so that a late failure of an old connection
+    /// doesn't tear down the new one.
+    fn reconnect_if_current(&self, generation: u64) {
+        if let Some(mut client) = self.client.try_lock() {
+            if self.generation.get() == generation {
+                *client = None;
+            }
+        }
+    }
+
     /// Force reconnection.
     ///
     /// If one of the cloned clients (used in other fibers/places) has already reconnected,
@@ -116,6 +146,7 @@ impl Client {
     pub fn with_config(url: String, port: u16, config: protocol::Config) -> Self {
         Self {
             client: Default::default(),
+            generation: Default::default(),
             url,
             port,
             protocol_config: config,
@@ -136,36 +167,140 @@ impl Client {
     }
 }
 
-#[async_trait::async_trait(?Send)]
-impl AsClient for Client {
-    async fn send<R: protocol::api::Request>(
+impl Client {
+    /// Sends the `request` and returns the result along with the generation
+    /// of the connection it was sent over.
+    async fn send_with_generation<R: protocol::api::Request>(
         &self,
         request: &R,
-    ) -> Result<R::Response, ClientError> {
-        let client = self.client().await?;
+    ) -> (Result<R::Response, ClientError>, u64) {
+        let (client, generation) = self.client_and_generation().await;
+        let client = match client {
+            Ok(client) => client,
+            Err(e) => return (Err(e), generation),
+        };
 
         #[cfg(not(feature = "internal_test"))]
         {
-            client.send(request).await
+            (client.send(request).await, generation)
         }
         // Allow error injection in tests
         #[cfg(feature = "internal_test")]
         {
             let inject_error = self.inject_error.borrow_mut().take();
             if let Some(error) = inject_error {
-                Err(error)
+                (Err(error), generation)
             } else {
-                client.send(request).await
+                (client.send(request).await, generation)
             }
         }
     }
 }
 
+#[async_trait::async_trait(?Send)]
+impl AsClient for Client {
+    #[inline(always)]
+    async fn send<R: protocol::api::Request>(
+        &self,
+        request: &R,
+    ) -> Result<R::Response, ClientError> {
+        self.send_with_generation(request).await.0
+    }
+}
+
+/// A version of [`Client`] which reconnects automatically.
+///
+/// If a request fails with [`ClientError::ConnectionClosed`], the client
+/// reconnects and, if the request's type is marked as retriable, sends the
+/// request once again over the new connection. Requests of other types return
+/// the original error, but any subsequent request will use the new connection.
+///
+/// By default only [`IProtoType::Ping`] requests are retried. Retrying a
+/// request which is not idempotent may result in it being executed twice on
+/// the remote side, so other request types (e.g. [`IProtoType::Call`],
+/// [`IProtoType::Eval`]) must be explicitly opted in via [`AutoClient::retry`].
+///
+/// Can be cloned to utilize the same connection from multiple fibers, all the
+/// clones share the same underlying connection and reconnect together.
+///
+/// See [`AsClient`] for the full API.
+#[derive(Debug, Clone)]
+pub struct AutoClient {
+    client: Client,
+    retriable: HashSet<IProtoType>,
+}
+
+impl AutoClient {
+    /// Creates a new client but does not yet try to establish connection
+    /// to `url:port`. This will happen at the first call through [`AsClient`] methods.
+    pub fn new(url: String, port: u16) -> Self {
+        Self::with_config(url, port, Default::default())
+    }
+
+    /// Creates a new client but does not yet try to establish connection
+    /// to `url:port`. This will happen at the first call through [`AsClient`] methods.
+    ///
+    /// Takes explicit `config` in comparison to [`Self::new`]
+    /// where default values are used.
+    pub fn with_config(url: String, port: u16, config: protocol::Config) -> Self {
+        Self {
+            client: Client::with_config(url, port, config),
+            retriable: std::iter::once(IProtoType::Ping).collect(),
+        }
+    }
+
+    /// Mark requests of type `request_type` as retriable, i.e. they will be
+    /// resent after a reconnect caused by a closed connection.
+    ///
+    /// Only mark requests retriable if executing them twice is safe.
+    #[inline]
+    pub fn retry(mut self, request_type: IProtoType) -> Self {
+        self.retriable.insert(request_type);
+        self
+    }
+
+    /// Returns `true` if requests of type `request_type` will be resent after
+    /// a reconnect.
+    #[inline(always)]
+    pub fn is_retriable(&self, request_type: IProtoType) -> bool {
+        self.retriable.contains(&request_type)
+    }
+
+    /// Returns the underlying reconnecting client.
+    #[inline(always)]
+    pub fn inner(&self) -> &Client {
+        &self.client
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl AsClient for AutoClient {
+    async fn send<R: protocol::api::Request>(
+        &self,
+        request: &R,
+    ) -> Result<R::Response, ClientError> {
+        let (res, generation) = self.client.send_with_generation(request).await;
+        let err = match res {
+            Err(e @ ClientError::ConnectionClosed(_)) => e,
+            res => return res,
+        };
+
+        // Another fiber may have already reconnected after this connection
+        // was closed, in which case the new connection must be left alone.
+        self.client.reconnect_if_current(generation);
+        if !self.is_retriable(R::TYPE) {
+            return Err(err);
+        }
+
+        self.client.send(request).await
+    }
+}
+
 #[cfg(feature = "internal_test")]
 mod tests {
     use super::*;
     use crate::fiber;
-    use crate::fiber::r#async::timeout::IntoTimeout as _;
+    use crate::fiber::r#async::timeout::{self, IntoTimeout as _};
     use crate::test::util::listen_port;
     use std::time::Duration;
 
@@ -313,4 +448,84 @@ mod tests {
         }
         assert_eq!(client.reconnect_count(), 1);
     }
+
+    #[crate::test(tarantool = "crate")]
+    async fn auto_client_reconnects_after_connection_killed() {
+        let client = AutoClient::with_config(
+            "localhost".into(),
+            listen_port(),
+            protocol::Config {
+                creds: Some(("test_user".into(), "password".into())),
+                ..Default::default()
+            },
+        );
+        assert!(client.is_retriable(IProtoType::Ping));
+        assert!(!client.is_retriable(IProtoType::Call));
+
+        client.ping().timeout(_3_SEC).await.unwrap();
+        assert_eq!(client.inner().reconnect_count(), 0);
+
+        // Kill the connection from under the client
+        let inner = client.inner().client().await.unwrap();
+        inner.0.borrow().stream.close().unwrap();
+        drop(inner);
+
+        // Ping is retried on a new connection, the handle stays the same
+        client.ping().timeout(_3_SEC).await.unwrap();
+        assert_eq!(client.inner().reconnect_count(), 1);
+
+        // Non-retriable requests fail, but the next one reconnects
+        let inner = client.inner().client().await.unwrap();
+        inner.0.borrow().stream.close().unwrap();
+        drop(inner);
+        client
+            .eval("return 1", &())
+            .timeout(_3_SEC)
+            .await
+            .unwrap_err();
+        client.eval("return 1", &()).timeout(_3_SEC).await.unwrap();
+        assert_eq!(client.inner().reconnect_count(), 2);
+
+        // Opted-in requests are retried
+        let client = client.retry(IProtoType::Eval);
+        let inner = client.inner().client().await.unwrap();
+        inner.0.borrow().stream.close().unwrap();
+        drop(inner);
+        client.eval("return 1", &()).timeout(_3_SEC).await.unwrap();
+        assert_eq!(client.inner().reconnect_count(), 3);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn auto_client_late_failure_keeps_new_connection() {
+        let client = AutoClient::with_config(
+            "localhost".into(),
+            listen_port(),
+            protocol::Config {
+                creds: Some(("test_user".into(), "password".into())),
+                ..Default::default()
+            },
+        );
+        client.ping().timeout(_3_SEC).await.unwrap();
+
+        // A request is about to be sent over the old connection
+        let (old, old_generation) = client.inner().client_and_generation().await;
+        let old = old.unwrap();
+
+        // Meanwhile the connection is killed and another request reconnects
+        old.0.borrow().stream.close().unwrap();
+        client.ping().timeout(_3_SEC).await.unwrap();
+        assert_eq!(client.inner().reconnect_count(), 1);
+
+        // The request over the old connection fails after that
+        let err = old.ping().timeout(_3_SEC).await.unwrap_err();
+        assert!(matches!(
+            err,
+            timeout::Error::Failed(ClientError::ConnectionClosed(_))
+        ));
+        client.inner().reconnect_if_current(old_generation);
+
+        // The new connection is still used
+        client.ping().timeout(_3_SEC).await.unwrap();
+        assert_eq!(client.inner().reconnect_count(), 1);
+    }
 }
@@ -28,7 +28,7 @@ use std::pin::Pin;
 use std::rc::Rc;
 use std::task::{Context, Poll};
 use std::time::Duration;
-use std::{io, marker};
+use std::{io, marker, net};
 
 #[cfg(feature = "async-std")]
 use async_std::io::{Read as AsyncRead, Write as AsyncWrite};
@@ -294,6 +294,26 @@ impl TcpStream {
     pub fn close(&self) -> io::Result<()> {
         self.inner.close()
     }
+
+    /// Returns the local socket address of this stream.
+    ///
+    /// Returns an error if the stream is closed.
+    #[inline]
+    pub fn local_addr(&self) -> io::Result<net::SocketAddr> {
+        let fd = self.inner.fd()?;
+        // SAFETY: safe because `fd` is open
+        socket_addr(|addr, len| unsafe { libc::getsockname(fd, addr, len) })
+    }
+
+    /// Returns the socket address of the remote peer of this stream.
+    ///
+    /// Returns an error if the stream is closed or is not connected yet.
+    #[inline]
+    pub fn peer_addr(&self) -> io::Result<net::SocketAddr> {
+        let fd = self.inner.fd()?;
+        // SAFETY: safe because `fd` is open
+        socket_addr(|addr, len| unsafe { libc::getpeername(fd, addr, len) })
+    }
 }
 
 /// SAFETY: completely unsafe, but we are allowed to do this cause sending/sharing following stream to/from another thread
@@ -561,6 +581,41 @@ fn check_socket_error(fd: &impl AsRawFd) -> io::Result<()> {
     }
 }
 
+/// Calls `f` (`getsockname` or `getpeername`) and converts the address it
+/// stores into a [`net::SocketAddr`].
+fn socket_addr(
+    f: impl FnOnce(*mut libc::sockaddr, *mut libc::socklen_t) -> libc::c_int,
+) -> io::Result<net::SocketAddr> {
+    // SAFETY: all zeroes is a valid value for `sockaddr_storage`
+    let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+    let mut len = mem::size_of_val(&storage) as libc::socklen_t;
+    cvt(f(
+        &mut storage as *mut libc::sockaddr_storage as *mut libc::sockaddr,
+        &mut len,
+    ))?;
+    match storage.ss_family as libc::c_int {
+        libc::AF_INET => {
+            // SAFETY: safe because the address family is AF_INET
+            let addr = unsafe { *(&storage as *const _ as *const libc::sockaddr_in) };
+            let ip = net::Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr));
+            let port = u16::from_be(addr.sin_port);
+            Ok(net::SocketAddrV4::new(ip, port).into())
+        }
+        libc::AF_INET6 => {
+            // SAFETY: safe because the address family is AF_INET6
+            let addr = unsafe { *(&storage as *const _ as *const libc::sockaddr_in6) };
+            let ip = net::Ipv6Addr::from(addr.sin6_addr.s6_addr);
+            let port = u16::from_be(addr.sin6_port);
+            let addr = net::SocketAddrV6::new(ip, port, addr.sin6_flowinfo, addr.sin6_scope_id);
+            Ok(addr.into())
+        }
+        af => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            Error::UnknownAddressFamily(af as u16),
+        )),
+    }
+}
+
 #[derive(Debug)]
 enum SockAddr {
     V4(libc::sockaddr_in),
@@ -774,6 +829,28 @@ mod tests {
         let _ = TcpStream::connect("localhost", listen_port()).unwrap();
     }
 
+    #[crate::test(tarantool = "crate")]
+    fn local_and_peer_addr() {
+        let stream = TcpStream::connect("localhost", listen_port()).unwrap();
+        let peer = stream.peer_addr().unwrap();
+        assert_eq!(peer.port(), listen_port());
+        assert!(peer.ip().is_loopback());
+        let local = stream.local_addr().unwrap();
+        assert!(local.ip().is_loopback());
+        assert_ne!(local.port(), 0);
+
+        // Not connected yet
+        let fd = nonblocking_socket(libc::AF_INET).unwrap();
+        let stream = TcpStream::from(fd);
+        let err = stream.peer_addr().unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::ENOTCONN));
+
+        // Closed
+        stream.close().unwrap();
+        let err = stream.local_addr().unwrap_err();
+        assert_eq!(err.to_string(), "socket closed already");
+    }
+
     #[crate::test(tarantool = "crate")]
     fn connect_async() {
         let _ = fiber::block_on(TcpStream::connect_async("localhost", listen_port())).unwrap();
@@ -1,9 +1,7 @@
 use std::io::{Cursor, Write};
 
 use crate::error::Error;
-use crate::index::IndexId;
 use crate::index::IteratorType;
-use crate::space::SpaceId;
 use crate::tuple::Encode;
 use crate::tuple::{ToTupleBuffer, Tuple};
 
@@ -141,8 +139,8 @@ impl Request for Auth<'_, '_, '_> {
 }
 
 pub struct Select<'a, T: ?Sized> {
-    pub space_id: SpaceId,
-    pub index_id: IndexId,
+    pub space_id: u32,
+    pub index_id: u32,
     pub limit: u32,
     pub offset: u32,
     pub iterator_type: IteratorType,
@@ -179,7 +177,7 @@ pub struct Insert<'a, T>
 where
     T: ?Sized,
 {
-    pub space_id: SpaceId,
+    pub space_id: u32,
     pub value: &'a T,
 }
 
@@ -206,7 +204,7 @@ pub struct Replace<'a, T>
 where
     T: ?Sized,
 {
-    pub space_id: SpaceId,
+    pub space_id: u32,
     pub value: &'a T,
 }
 
@@ -233,8 +231,8 @@ pub struct Update<'a, T, Op>
 where
     T: ?Sized,
 {
-    pub space_id: SpaceId,
-    pub index_id: IndexId,
+    pub space_id: u32,
+    pub index_id: u32,
     pub key: &'a T,
     pub ops: &'a [Op],
 }
@@ -263,8 +261,8 @@ pub struct Upsert<'a, T, Op>
 where
     T: ?Sized,
 {
-    pub space_id: SpaceId,
-    pub index_id: IndexId,
+    pub space_id: u32,
+    pub index_id: u32,
     pub value: &'a T,
     pub ops: &'a [Op],
 }
@@ -293,8 +291,8 @@ pub struct Delete<'a, T>
 where
     T: ?Sized,
 {
-    pub space_id: SpaceId,
-    pub index_id: IndexId,
+    pub space_id: u32,
+    pub index_id: u32,
     pub key: &'a T,
 }
 
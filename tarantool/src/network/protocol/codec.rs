@@ -654,11 +654,43 @@ pub fn decode_string(stream: &mut impl Read) -> Result<String, Error> {
     Ok(res)
 }
 
+/// The greeting message which the server sends right after the connection is
+/// established.
+///
+/// See [tarantool docs](https://www.tarantool.io/en/doc/latest/dev_guide/internals/iproto/authentication/#greeting-message).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Greeting {
+    /// Server version, e.g. `"2.11.1-0-g96877bd"`. Empty if the server didn't
+    /// specify it.
+    pub version: String,
+    /// Random salt which must be used for computing the authentication
+    /// payload.
+    pub salt: Vec<u8>,
+}
+
+impl Greeting {
+    /// Decodes the 128 byte greeting message.
+    pub fn decode(stream: &mut impl Read) -> Result<Self, Error> {
+        let mut buf = [0; 128];
+        stream.read_exact(&mut buf)?;
+        // The first line looks like "Tarantool 2.11.1-0-g96877bd (Binary) <uuid>"
+        let version = String::from_utf8_lossy(&buf[0..64])
+            .split_whitespace()
+            .nth(1)
+            .unwrap_or_default()
+            .to_owned();
+        let salt = base64::decode(&buf[64..108]).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid salt in greeting: {e}"),
+            )
+        })?;
+        Ok(Self { version, salt })
+    }
+}
+
 pub fn decode_greeting(stream: &mut impl Read) -> Result<Vec<u8>, Error> {
-    let mut buf = [0; 128];
-    stream.read_exact(&mut buf)?;
-    let salt = base64::decode(&buf[64..108]).unwrap();
-    Ok(salt)
+    Greeting::decode(stream).map(|greeting| greeting.salt)
 }
 
 pub fn decode_call(buffer: &mut Cursor<Vec<u8>>) -> Result<Tuple, Error> {
@@ -16,6 +16,7 @@ use crate::error;
 use crate::error::TarantoolError;
 use std::collections::HashMap;
 use std::io::{Cursor, Read, Seek};
+use std::sync::Arc;
 use std::time::Duration;
 
 #[deprecated = "use `ProtocolError` instead"]
@@ -72,6 +73,11 @@ enum State {
 }
 
 /// Configuration of [`Protocol`].
+///
+/// Note that unlike [`net_box`](crate::net_box) the protocol never fetches the
+/// schema of the remote instance, so connecting doesn't involve any requests
+/// besides authentication and the only requests sent are the ones explicitly
+/// made by the user (and the keepalive pings if enabled).
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
 #[non_exhaustive]
 pub struct Config {
@@ -81,9 +87,84 @@ pub struct Config {
     pub auth_method: AuthMethod,
     /// Connection establishment timeout.
     pub connect_timeout: Option<Duration>,
+    /// Called with the sync and the request type (see [`IProtoType`]) every
+    /// time [`Protocol::send_request`] assigns a sync to a request.
+    pub on_request: Option<Hook>,
+    /// Called with the sync and the response type (`IPROTO_REQUEST_TYPE` from
+    /// the response header) every time a response is received.
+    pub on_response: Option<Hook>,
+    /// If set, the client sends a ping every `keepalive_interval`, so that
+    /// idle connections aren't dropped by load balancers and the like. If the
+    /// response to the ping doesn't arrive within the next
+    /// `keepalive_interval`, the connection is closed with an error.
+    ///
+    /// Only used by [`Client`](super::client::Client).
+    pub keepalive_interval: Option<Duration>,
+    /// If `true`, the requests are sent right after the authentication
+    /// request without waiting for its response, which saves a round-trip
+    /// before the first request. Only makes sense if [`Self::creds`] are set.
+    ///
+    /// The responses which arrive before the authentication response are held
+    /// until it arrives. If the authentication fails the connection is closed
+    /// with an error and all the pending requests fail with it, including the
+    /// ones which already got a response. Note however that in this case the
+    /// requests may still have been executed on the server with the
+    /// privileges of the `guest` user.
+    pub lazy_auth: bool,
+    /// Maximum number of requests awaiting a response at the same time. If
+    /// the limit is reached, sending a new request waits until a response
+    /// to one of the previous requests arrives. `None` means no limit, and so
+    /// does `Some(0)`.
+    ///
+    /// The waiting requests are sent in the order they started waiting. The
+    /// pings sent because of [`Self::keepalive_interval`] aren't subject to
+    /// the limit.
+    ///
+    /// Only used by [`Client`](super::client::Client).
+    pub max_in_flight: Option<usize>,
     // TODO: add buffer limits here
 }
 
+/// A callback which is invoked with the [`SyncIndex`] and the iproto type of
+/// a message. Can be used for tracing and metrics.
+///
+/// See [`Config::on_request`] and [`Config::on_response`].
+///
+/// The callback must be `Send + Sync`, so that [`Config`] can be passed
+/// between threads.
+#[derive(Clone)]
+pub struct Hook(pub Arc<dyn Fn(SyncIndex, u32) + Send + Sync>);
+
+impl Hook {
+    #[inline(always)]
+    pub fn new(f: impl Fn(SyncIndex, u32) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
+const _: () = {
+    /// Assert Config implements Send + Sync
+    const fn if_this_compiles_the_type_implements_send_and_sync<T: Send + Sync>() {}
+    if_this_compiles_the_type_implements_send_and_sync::<Config>();
+};
+
+impl std::fmt::Debug for Hook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Hook")
+            .field(&Arc::as_ptr(&self.0).cast::<()>())
+            .finish()
+    }
+}
+
+impl PartialEq for Hook {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Hook {}
+
 /// A sans-io connection handler.
 ///
 /// Buffers incoming and outgoing bytes and provides an API for
@@ -104,6 +185,18 @@ pub struct Protocol {
     /// (user, password)
     creds: Option<(String, String)>,
     auth_method: AuthMethod,
+    lazy_auth: bool,
+    /// Sync of the authentication request, while its response is awaited.
+    auth_sync: Option<SyncIndex>,
+    /// Responses which arrived before the authentication response.
+    held_responses: Vec<(SyncIndex, Result<Vec<u8>, TarantoolError>)>,
+    /// Syncs of the held responses which became available after the
+    /// authentication succeeded.
+    released_responses: Vec<SyncIndex>,
+    greeting: Option<Greeting>,
+    schema_version: Option<u64>,
+    on_request: Option<Hook>,
+    on_response: Option<Hook>,
 }
 
 impl Default for Protocol {
@@ -121,6 +214,14 @@ impl Protocol {
             pending_outgoing: Vec::new(),
             creds: None,
             auth_method: AuthMethod::default(),
+            lazy_auth: false,
+            auth_sync: None,
+            held_responses: Vec::new(),
+            released_responses: Vec::new(),
+            greeting: None,
+            schema_version: None,
+            on_request: None,
+            on_response: None,
             outgoing: Vec::new(),
             incoming: HashMap::new(),
             // Greeting is exactly 128 bytes
@@ -133,6 +234,9 @@ impl Protocol {
         let mut protocol = Self::new();
         protocol.creds = config.creds;
         protocol.auth_method = config.auth_method;
+        protocol.lazy_auth = config.lazy_auth;
+        protocol.on_request = config.on_request;
+        protocol.on_response = config.on_response;
         protocol
     }
 
@@ -146,13 +250,35 @@ impl Protocol {
         matches!(self.state, State::Ready)
     }
 
+    /// Returns the greeting received from the server, or `None` if it wasn't
+    /// received yet.
+    ///
+    /// The greeting contains the salt needed for computing an authentication
+    /// payload, which is useful for authentication methods which aren't
+    /// supported out of the box. In this case [`Config::creds`] must be left
+    /// empty and the [`api::Auth`] request must be sent explicitly.
+    pub fn greeting(&self) -> Option<&Greeting> {
+        self.greeting.as_ref()
+    }
+
+    /// Returns the schema version of the server from the header of the last
+    /// received response, or `None` if no responses were received yet.
+    ///
+    /// The schema version changes every time the server's schema is changed
+    /// (e.g. a space is created), so this can be used to detect that any
+    /// cached schema information is stale.
+    #[inline(always)]
+    pub fn schema_version(&self) -> Option<u64> {
+        self.schema_version
+    }
+
     /// Processes incoming request and buffers generated outgoing bytes.
     /// Outgoing bytes can be retrieved with [`Protocol::take_outgoing_data`]
     ///
     /// Data can be sent independently of whether the protocol [`Self::is_ready`].
     /// If the protocol is not ready data will be queued and eventually processed
     /// after auth is done.
-    pub fn send_request(&mut self, request: &impl Request) -> Result<SyncIndex, error::Error> {
+    pub fn send_request<R: Request>(&mut self, request: &R) -> Result<SyncIndex, error::Error> {
         let end = self.pending_outgoing.len();
         let mut buf = Cursor::new(&mut self.pending_outgoing);
         buf.set_position(end as u64);
@@ -164,7 +290,11 @@ impl Protocol {
         // It's pretty easy to fix, so we probably should...
         write_to_buffer(&mut buf, self.sync, request)?;
         self.process_pending_data();
-        Ok(self.sync.next_index())
+        let sync = self.sync.next_index();
+        if let Some(Hook(on_request)) = &self.on_request {
+            on_request(sync, R::TYPE as u32);
+        }
+        Ok(sync)
     }
 
     /// Take existing response by [`SyncIndex`].
@@ -179,6 +309,15 @@ impl Protocol {
         Some(R::decode_response_body(&mut Cursor::new(response)))
     }
 
+    /// Returns the [`SyncIndex`]es of the responses which arrived before the
+    /// authentication response (see [`Config::lazy_auth`]) and became
+    /// available once the authentication succeeded. These aren't returned
+    /// from [`Self::process_incoming`], so this should be checked after each
+    /// call to it.
+    pub fn take_released_responses(&mut self) -> Vec<SyncIndex> {
+        std::mem::take(&mut self.released_responses)
+    }
+
     /// Drop response by [`SyncIndex`] if it exists. If not - does nothing.
     pub fn drop_response(&mut self, sync: SyncIndex) {
         self.incoming.remove(&sync);
@@ -231,7 +370,7 @@ impl Protocol {
     ) -> Result<Option<SyncIndex>, error::Error> {
         let sync = match self.state {
             State::Init => {
-                let salt = codec::decode_greeting(message)?;
+                let greeting = self.greeting.insert(Greeting::decode(message)?);
                 if let Some((user, pass)) = self.creds.as_ref() {
                     // Auth
                     self.state = State::Auth;
@@ -239,13 +378,14 @@ impl Protocol {
                     debug_assert!(self.outgoing.is_empty());
                     let mut buf = Cursor::new(&mut self.outgoing);
                     let sync = self.sync.next_index();
+                    self.auth_sync = Some(sync);
                     write_to_buffer(
                         &mut buf,
                         sync,
                         &api::Auth {
                             user,
                             pass,
-                            salt: &salt,
+                            salt: &greeting.salt,
                             method: self.auth_method,
                         },
                     )?;
@@ -257,24 +397,32 @@ impl Protocol {
             }
             State::Auth => {
                 let header = codec::Header::decode(message)?;
+                self.schema_version = Some(header.schema_version);
+                if Some(header.sync) != self.auth_sync {
+                    // A response to a request sent along with the
+                    // authentication request, it's held until we know
+                    // whether the authentication succeeded
+                    let response = self.read_response(message, &header)?;
+                    self.held_responses.push((header.sync, response));
+                    return Ok(None);
+                }
+                self.auth_sync = None;
                 if header.iproto_type == IProtoType::Error as u32 {
+                    self.held_responses.clear();
                     let error = codec::decode_error(message, &header)?;
                     return Err(error::Error::Remote(error));
                 }
                 self.state = State::Ready;
+                for (sync, response) in std::mem::take(&mut self.held_responses) {
+                    self.incoming.insert(sync, response);
+                    self.released_responses.push(sync);
+                }
                 None
             }
             State::Ready => {
                 let header = codec::Header::decode(message)?;
-                let response;
-                if header.iproto_type == IProtoType::Error as u32 {
-                    response = Err(codec::decode_error(message, &header)?);
-                } else {
-                    // FIXME: we know the exact size of the body at this point
-                    let mut buf = Vec::new();
-                    message.read_to_end(&mut buf)?;
-                    response = Ok(buf);
-                };
+                self.schema_version = Some(header.schema_version);
+                let response = self.read_response(message, &header)?;
                 self.incoming.insert(header.sync, response);
                 Some(header.sync)
             }
@@ -283,6 +431,26 @@ impl Protocol {
         Ok(sync)
     }
 
+    fn read_response<R: Read + Seek>(
+        &self,
+        message: &mut R,
+        header: &codec::Header,
+    ) -> Result<Result<Vec<u8>, TarantoolError>, error::Error> {
+        let response;
+        if header.iproto_type == IProtoType::Error as u32 {
+            response = Err(codec::decode_error(message, header)?);
+        } else {
+            // FIXME: we know the exact size of the body at this point
+            let mut buf = Vec::new();
+            message.read_to_end(&mut buf)?;
+            response = Ok(buf);
+        };
+        if let Some(Hook(on_response)) = &self.on_response {
+            on_response(header.sync, header.iproto_type);
+        }
+        Ok(response)
+    }
+
     /// Returns a number of outgoing data bytes.
     pub fn ready_outgoing_len(&self) -> usize {
         self.outgoing.len()
@@ -296,11 +464,36 @@ impl Protocol {
         std::mem::take(&mut self.outgoing)
     }
 
+    /// Returns buffered outgoing data replacing the buffer with `buf`.
+    ///
+    /// Same as [`Self::take_outgoing_data`], but allows reusing the buffers
+    /// to avoid allocations, i.e. the returned buffer can be cleared after
+    /// the data is sent and passed to the next call to this function.
+    ///
+    /// `buf` must be empty, otherwise its contents are discarded.
+    pub fn replace_outgoing_data(&mut self, mut buf: Vec<u8>) -> Vec<u8> {
+        buf.clear();
+        std::mem::replace(&mut self.outgoing, buf)
+    }
+
     fn process_pending_data(&mut self) {
-        if self.is_ready() {
-            let mut pending_data = std::mem::take(&mut self.pending_outgoing);
-            // TODO: limit the ready vec size
-            self.outgoing.append(&mut pending_data);
+        let can_send = match self.state {
+            State::Ready => true,
+            // The authentication request is already in the outgoing buffer,
+            // so the requests will be processed after it
+            State::Auth => self.lazy_auth,
+            State::Init => false,
+        };
+        if can_send {
+            if self.outgoing.is_empty() {
+                // Swap the buffers instead of moving the data, so that
+                // the allocated capacity of both of them is reused
+                std::mem::swap(&mut self.outgoing, &mut self.pending_outgoing);
+            } else {
+                // TODO: limit the ready vec size
+                self.outgoing.extend_from_slice(&self.pending_outgoing);
+                self.pending_outgoing.clear();
+            }
         }
     }
 }
@@ -359,6 +552,150 @@ mod tests {
         assert!(conn.is_ready())
     }
 
+    #[crate::test(tarantool = "crate")]
+    fn greeting_decoded() {
+        let mut conn = Protocol::new();
+        assert!(conn.greeting().is_none());
+
+        let mut greeting = fake_greeting();
+        let version_line = b"Tarantool 2.11.1-0-g96877bd (Binary) 3a72fc83";
+        greeting[..version_line.len()].copy_from_slice(version_line);
+        conn.process_incoming(&mut Cursor::new(greeting)).unwrap();
+
+        let greeting = conn.greeting().unwrap();
+        assert_eq!(greeting.version, "2.11.1-0-g96877bd");
+        assert_eq!(greeting.salt.len(), 32);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn invalid_greeting() {
+        let mut greeting = fake_greeting();
+        greeting[64..70].copy_from_slice(b"!!!!!!");
+        let err = codec::Greeting::decode(&mut Cursor::new(greeting)).unwrap_err();
+        assert!(
+            err.to_string().contains("invalid salt in greeting"),
+            "{}",
+            err
+        );
+
+        let mut conn = Protocol::new();
+        let mut greeting = fake_greeting();
+        greeting[64..70].copy_from_slice(b"!!!!!!");
+        conn.process_incoming(&mut Cursor::new(greeting))
+            .unwrap_err();
+        assert!(conn.greeting().is_none());
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn lazy_auth() {
+        let count_requests = |data: &[u8]| {
+            let mut cur = Cursor::new(data);
+            let mut types = vec![];
+            while (cur.position() as usize) < data.len() {
+                let len = rmp::decode::read_u32(&mut cur).unwrap() as u64;
+                let start = cur.position();
+                types.push(codec::Header::decode(&mut cur).unwrap().iproto_type);
+                cur.set_position(start + len);
+            }
+            types
+        };
+        let auth = IProtoType::Auth as u32;
+        let ping = IProtoType::Ping as u32;
+
+        for lazy_auth in [false, true] {
+            let mut conn = Protocol::with_config(Config {
+                creds: Some(("user".into(), "password".into())),
+                lazy_auth,
+                ..Default::default()
+            });
+            conn.send_request(&api::Ping).unwrap();
+            assert_eq!(conn.ready_outgoing_len(), 0);
+
+            conn.process_incoming(&mut Cursor::new(fake_greeting()))
+                .unwrap();
+            assert!(!conn.is_ready());
+            conn.send_request(&api::Ping).unwrap();
+            let types = count_requests(&conn.take_outgoing_data());
+            if lazy_auth {
+                // The requests are sent along with the authentication
+                assert_eq!(types, [auth, ping, ping]);
+            } else {
+                // The requests wait for the authentication response
+                assert_eq!(types, [auth]);
+            }
+        }
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn lazy_auth_holds_responses() {
+        use codec::iproto_key::{ERROR, REQUEST_TYPE, SCHEMA_VERSION, SYNC};
+
+        let response = |sync: SyncIndex, error: Option<&str>| {
+            let mut msg = Vec::new();
+            rmp::encode::write_map_len(&mut msg, 3).unwrap();
+            rmp::encode::write_pfix(&mut msg, REQUEST_TYPE).unwrap();
+            let iproto_type = if error.is_some() {
+                IProtoType::Error as u32 | 1
+            } else {
+                0
+            };
+            rmp::encode::write_uint(&mut msg, iproto_type as _).unwrap();
+            rmp::encode::write_pfix(&mut msg, SYNC).unwrap();
+            rmp::encode::write_uint(&mut msg, sync.get()).unwrap();
+            rmp::encode::write_pfix(&mut msg, SCHEMA_VERSION).unwrap();
+            rmp::encode::write_uint(&mut msg, 1).unwrap();
+            if let Some(error) = error {
+                rmp::encode::write_map_len(&mut msg, 1).unwrap();
+                rmp::encode::write_pfix(&mut msg, ERROR).unwrap();
+                rmp::encode::write_str(&mut msg, error).unwrap();
+            } else {
+                rmp::encode::write_map_len(&mut msg, 0).unwrap();
+            }
+            msg
+        };
+        let receive = |conn: &mut Protocol, msg: Vec<u8>| {
+            let mut hint = Vec::new();
+            rmp::encode::write_u32(&mut hint, msg.len() as _).unwrap();
+            assert_eq!(conn.process_incoming(&mut Cursor::new(hint)).unwrap(), None);
+            conn.process_incoming(&mut Cursor::new(msg))
+        };
+        let connect = || {
+            let mut conn = Protocol::with_config(Config {
+                creds: Some(("user".into(), "password".into())),
+                lazy_auth: true,
+                ..Default::default()
+            });
+            let first = conn.send_request(&api::Ping).unwrap();
+            conn.process_incoming(&mut Cursor::new(fake_greeting()))
+                .unwrap();
+            let auth = conn.auth_sync.unwrap();
+            let second = conn.send_request(&api::Ping).unwrap();
+            (conn, auth, first, second)
+        };
+
+        // The responses which arrive before the authentication response are
+        // only available after it
+        let (mut conn, auth, first, second) = connect();
+        assert_eq!(receive(&mut conn, response(second, None)).unwrap(), None);
+        assert!(conn.take_response::<api::Ping>(second).is_none());
+        assert!(conn.take_released_responses().is_empty());
+        assert_eq!(receive(&mut conn, response(auth, None)).unwrap(), None);
+        assert!(conn.is_ready());
+        assert_eq!(conn.take_released_responses(), [second]);
+        assert!(conn.take_released_responses().is_empty());
+        conn.take_response::<api::Ping>(second).unwrap().unwrap();
+        let res = receive(&mut conn, response(first, None)).unwrap();
+        assert_eq!(res, Some(first));
+
+        // If the authentication fails, the held responses are dropped
+        let (mut conn, auth, first, _) = connect();
+        assert_eq!(receive(&mut conn, response(first, None)).unwrap(), None);
+        let err = receive(&mut conn, response(auth, Some("denied"))).unwrap_err();
+        assert!(err.to_string().contains("denied"), "{}", err);
+        assert!(conn.take_released_responses().is_empty());
+        assert!(conn.take_response::<api::Ping>(first).is_none());
+    }
+
     #[crate::test(tarantool = "crate")]
     fn send_bytes_generated() {
         let mut conn = Protocol::new();
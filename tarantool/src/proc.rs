@@ -85,6 +85,73 @@ impl Proc {
     pub const fn is_public(&self) -> bool {
         self.public
     }
+
+    /// Returns the lua code which registers the stored procedure in tarantool
+    /// via `box.schema.func.create`. `lib_name` is the name of the shared
+    /// library the proc is defined in (without the `lib` prefix and the file
+    /// extension), the same one used in `require`.
+    ///
+    /// The function is created with `if_not_exists = true`, so the code can
+    /// be executed multiple times.
+    ///
+    /// The `returns` option is not specified, so tarantool uses its default
+    /// `'any'`, i.e. the result is treated as an opaque value. The return type
+    /// of the proc isn't recorded in [`Proc`], so a more specific type can't
+    /// be generated. If needed, it can be specified via
+    /// [`Self::create_ddl_with_options`].
+    ///
+    /// Combined with [`all_procs`] this can be used to register all of the
+    /// module's stored procedures at once:
+    /// ```no_run
+    /// for proc in tarantool::proc::all_procs() {
+    ///     tarantool::lua_state().exec(&proc.create_ddl("mylib")).unwrap();
+    /// }
+    /// ```
+    ///
+    /// [`all_procs`]: all_procs()
+    #[inline(always)]
+    pub fn create_ddl(&self, lib_name: &str) -> String {
+        self.create_ddl_with_options(lib_name, "")
+    }
+
+    /// Same as [`Self::create_ddl`], but `options` are added to the options
+    /// table passed to `box.schema.func.create`. `options` must be a comma
+    /// separated list of lua table fields, which is inserted into the code
+    /// as is.
+    ///
+    /// ```no_run
+    /// for proc in tarantool::proc::all_procs() {
+    ///     let ddl = proc.create_ddl_with_options("mylib", "exports = {'LUA', 'SQL'}");
+    ///     tarantool::lua_state().exec(&ddl).unwrap();
+    /// }
+    /// ```
+    pub fn create_ddl_with_options(&self, lib_name: &str, options: &str) -> String {
+        let mut res = String::with_capacity(64 + lib_name.len() + self.name.len() + options.len());
+        res.push_str("box.schema.func.create('");
+        push_escaped_lua_str(&mut res, lib_name);
+        res.push('.');
+        push_escaped_lua_str(&mut res, self.name);
+        res.push_str("', {language = 'C', if_not_exists = true");
+        if !options.is_empty() {
+            res.push_str(", ");
+            res.push_str(options);
+        }
+        res.push_str("})");
+        return res;
+
+        fn push_escaped_lua_str(res: &mut String, s: &str) {
+            for c in s.chars() {
+                match c {
+                    '\\' => res.push_str("\\\\"),
+                    '\'' => res.push_str("\\'"),
+                    '\n' => res.push_str("\\n"),
+                    '\r' => res.push_str("\\r"),
+                    '\0' => res.push_str("\\0"),
+                    c => res.push(c),
+                }
+            }
+        }
+    }
 }
 
 // In picodata, we cannot guarantee that tarantool module will be linked
@@ -97,6 +164,7 @@ pub use stored_procs_slice::*;
 #[cfg(feature = "stored_procs_slice")]
 mod stored_procs_slice {
     use super::*;
+    use std::sync::{Mutex, PoisonError};
 
     // Linkme distributed_slice exports a symbol with the given name, so we must
     // make sure the name is unique, so as not to conflict with distributed slices
@@ -107,23 +175,114 @@ mod stored_procs_slice {
     #[::linkme::distributed_slice]
     pub static TARANTOOL_MODULE_STORED_PROCS: [Proc] = [..];
 
-    /// Returns a slice of all stored procedures defined using the
+    /// Stored procedures registered via [`register`]. The list is append-only
+    /// and each proc is allocated separately, so that the references returned
+    /// by [`all_procs`] stay valid.
+    static DYNAMIC_PROCS: Mutex<Vec<&'static Proc>> = Mutex::new(Vec::new());
+
+    /// Returns an iterator over all stored procedures defined using the
+    /// `#[`[`tarantool::proc`]`]` macro attribute or registered at runtime
+    /// via [`register`].
+    ///
+    /// The order of procs is undefined.
+    ///
+    /// [`tarantool::proc`]: macro@crate::proc
+    #[inline]
+    pub fn all_procs() -> AllProcs {
+        let dynamic_procs = DYNAMIC_PROCS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        AllProcs {
+            static_procs: TARANTOOL_MODULE_STORED_PROCS.iter(),
+            dynamic_procs: dynamic_procs.into_iter(),
+        }
+    }
+
+    /// Registers a stored procedure at runtime, so that it is returned by
+    /// [`all_procs`] along with the ones defined using the
     /// `#[`[`tarantool::proc`]`]` macro attribute.
     ///
-    /// The order of procs in the slice is undefined.
+    /// This is useful for exposing functions of modules which are loaded
+    /// dynamically. Note that this function doesn't create the procedure in
+    /// tarantool (`box.schema.func.create`), it only records its description.
+    ///
+    /// Returns an error if a proc with the same `name` already exists.
+    ///
+    /// The description of a registered proc is never freed.
     ///
     /// [`tarantool::proc`]: macro@crate::proc
-    #[inline(always)]
-    pub fn all_procs() -> &'static [Proc] {
-        &TARANTOOL_MODULE_STORED_PROCS
+    pub fn register(
+        name: &'static str,
+        proc: ffi::Proc,
+        public: bool,
+    ) -> Result<(), AlreadyRegistered> {
+        let mut dynamic_procs = DYNAMIC_PROCS.lock().unwrap_or_else(PoisonError::into_inner);
+        let exists = TARANTOOL_MODULE_STORED_PROCS
+            .iter()
+            .chain(dynamic_procs.iter().copied())
+            .any(|p| p.name() == name);
+        if exists {
+            return Err(AlreadyRegistered(name));
+        }
+
+        let proc = Box::new(Proc::new(name, proc).with_public(public));
+        dynamic_procs.push(Box::leak(proc));
+        Ok(())
     }
+
+    /// Error returned by [`register`] if a stored procedure with the given
+    /// name already exists.
+    #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+    #[error("stored procedure `{0}` is already registered")]
+    pub struct AlreadyRegistered(pub &'static str);
 }
 
 #[cfg(not(feature = "stored_procs_slice"))]
-pub fn all_procs() -> &'static [Proc] {
+pub fn all_procs() -> AllProcs {
     panic!("`stored_procs_slice` feature is disabled, calling this function doesn't make sense");
 }
 
+/// An iterator over all stored procedures returned by [`all_procs`].
+///
+/// [`all_procs`]: all_procs()
+#[derive(Debug, Clone)]
+pub struct AllProcs {
+    static_procs: std::slice::Iter<'static, Proc>,
+    dynamic_procs: std::vec::IntoIter<&'static Proc>,
+}
+
+impl AllProcs {
+    /// Returns a copy of the iterator. Makes it possible to use the
+    /// iterator the same way as the slice returned by [`all_procs`] in older
+    /// versions.
+    ///
+    /// [`all_procs`]: all_procs()
+    #[inline(always)]
+    pub fn iter(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl Iterator for AllProcs {
+    type Item = &'static Proc;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.static_procs
+            .next()
+            .or_else(|| self.dynamic_procs.next())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.static_procs.len() + self.dynamic_procs.len();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for AllProcs {}
+
 ////////////////////////////////////////////////////////////////////////////////
 // module_name
 ////////////////////////////////////////////////////////////////////////////////
@@ -214,6 +373,20 @@ pub fn module_path(sym: *const ()) -> Option<&'static Path> {
 /// }
 /// ```
 ///
+/// # returning a reference
+///
+/// `T` can also be a reference to a serializable value, in which case the
+/// value is serialized in place without cloning. This works with the
+/// `custom_ret` attribute parameter as well:
+///
+/// ```no_run
+/// #[tarantool::proc(custom_ret)]
+/// fn foo<'a>(#[inject(&global_data())] data: &'a Vec<u64>) -> &'a Vec<u64> {
+///     data
+/// }
+/// # fn global_data() -> Vec<u64> { vec![] }
+/// ```
+///
 /// [`tarantool::proc`]: macro@crate::proc
 pub struct ReturnMsgpack<T>(pub T);
 
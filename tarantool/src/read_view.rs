@@ -16,17 +16,29 @@ use std::ptr::NonNull;
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct ReadView {
     inner: NonNull<ffi::box_read_view_t>,
-    space_indexes: Vec<(SpaceId, IndexId)>,
+    space_indexes: Vec<(u32, u32)>,
 }
 
 impl ReadView {
     /// Open a read view on the given space indexes.
+    ///
+    /// The ids can be either [`SpaceId`] & [`IndexId`] or plain `u32`s.
     #[inline]
     #[track_caller]
-    pub fn for_space_indexes(space_indexes: Vec<(SpaceId, IndexId)>) -> crate::Result<Self> {
+    pub fn for_space_indexes<S, I>(
+        space_indexes: impl IntoIterator<Item = (S, I)>,
+    ) -> crate::Result<Self>
+    where
+        S: Into<SpaceId>,
+        I: Into<IndexId>,
+    {
+        let space_indexes: Vec<(u32, u32)> = space_indexes
+            .into_iter()
+            .map(|(s, i)| (s.into().0, i.into().0))
+            .collect();
         const _: () = {
-            assert!(size_of::<(SpaceId, IndexId)>() == size_of::<ffi::space_index_id>());
-            assert!(align_of::<(SpaceId, IndexId)>() == align_of::<ffi::space_index_id>());
+            assert!(size_of::<(u32, u32)>() == size_of::<ffi::space_index_id>());
+            assert!(align_of::<(u32, u32)>() == align_of::<ffi::space_index_id>());
         };
 
         let loc = std::panic::Location::caller();
@@ -53,7 +65,7 @@ impl ReadView {
     /// Get the list of spaces and idexes for which the read view was opened, if
     /// it's available.
     #[inline(always)]
-    pub fn space_indexes(&self) -> Option<&[(SpaceId, IndexId)]> {
+    pub fn space_indexes(&self) -> Option<&[(u32, u32)]> {
         // NOTE: Currently the data is always available but in the future we
         // may add other ways of specifying spaces for the read view (e.g. all
         // spaces, etc.). In that case the slice of space & index ids would not
@@ -66,15 +78,15 @@ impl ReadView {
     #[inline]
     pub fn iter_all(
         &self,
-        space: SpaceId,
-        index: IndexId,
+        space: impl Into<SpaceId>,
+        index: impl Into<IndexId>,
     ) -> crate::Result<Option<ReadViewIterator>> {
         unsafe {
             let mut iter = MaybeUninit::uninit();
             let rc = ffi::box_read_view_iterator_all(
                 self.inner.as_ptr(),
-                space,
-                index,
+                space.into().0,
+                index.into().0,
                 iter.as_mut_ptr(),
             );
             if rc != 0 {
@@ -150,12 +162,13 @@ mod tests {
         s.insert(&(1, 2, 3)).unwrap();
         s.insert(&(2, "hello")).unwrap();
 
-        let rv = ReadView::for_space_indexes(vec![(s.id(), 0)]).unwrap();
+        let rv = ReadView::for_space_indexes([(s.space_id(), IndexId(0))]).unwrap();
+        assert_eq!(rv.space_indexes(), Some(&[(s.space_id().0, 0)][..]));
 
         // Space is not in the read view.
-        assert_eq!(rv.iter_all(SystemSpace::Space as _, 0).unwrap(), None);
+        assert_eq!(rv.iter_all(SystemSpace::Space as u32, 0).unwrap(), None);
 
-        let mut iter = rv.iter_all(s.id(), 0).unwrap().unwrap();
+        let mut iter = rv.iter_all(s.space_id(), 0).unwrap().unwrap();
         assert_eq!(iter.next(), Some(&b"\x93\x01\x02\x03"[..]));
         assert_eq!(iter.next(), Some(&b"\x92\x02\xa5hello"[..]));
         assert_eq!(iter.next(), None);
@@ -71,6 +71,10 @@ pub fn create_space(name: &str, opts: &SpaceCreateOptions) -> Result<Space, Erro
         SpaceType::DataLocal => {
             flags.insert("group_id".into(), 1.into());
         }
+        SpaceType::DataLocalTemporary => {
+            flags.insert("group_id".into(), 1.into());
+            flags.insert("temporary".into(), true.into());
+        }
         SpaceType::Synchronous => {
             flags.insert("is_sync".into(), true.into());
         }
@@ -112,7 +116,7 @@ pub fn create_space(name: &str, opts: &SpaceCreateOptions) -> Result<Space, Erro
         if opts.id.is_none() && opts.space_type != SpaceType::Temporary {
             let sys_schema = SystemSpace::Schema.as_space();
             if let Some(t) = sys_schema.get(&["max_id"])? {
-                if let Ok(Some(max_id)) = t.field::<SpaceId>(1) {
+                if let Ok(Some(max_id)) = t.field::<u32>(1) {
                     if id > max_id {
                         sys_schema.replace(&("max_id", id))?;
                     }
@@ -140,7 +144,7 @@ pub fn create_space(name: &str, opts: &SpaceCreateOptions) -> Result<Space, Erro
 
     // Safety: this is safe because inserting into _space didn't fail, so the
     // space has been created.
-    let space = unsafe { Space::from_id_unchecked(id) };
+    let space = unsafe { Space::new_unchecked(SpaceId(id)) };
     Ok(space)
 }
 
@@ -149,7 +153,7 @@ pub type SpaceMetadata<'a> = Metadata<'a>;
 
 /// Implementation ported from box_generate_space_id.
 /// <https://github.com/tarantool/tarantool/blob/70e423e92fc00df2ffe385f31dae9ea8e1cc1732/src/box/box.cc#L5737>
-pub fn generate_space_id(is_temporary: bool) -> Result<SpaceId, Error> {
+pub fn generate_space_id(is_temporary: bool) -> Result<u32, Error> {
     let sys_space = SystemSpace::Space.as_space();
     let (id_range_min, id_range_max);
     if is_temporary {
@@ -168,16 +172,16 @@ pub fn generate_space_id(is_temporary: bool) -> Result<SpaceId, Error> {
 
     let mut iter = sys_space.select(IteratorType::LT, &[id_range_max])?;
     let tuple = iter.next().expect("there's always at least system spaces");
-    let mut max_id: SpaceId = tuple
+    let mut max_id: u32 = tuple
         .field(0)
         .expect("space metadata should decode fine")
         .expect("space id should always be present");
 
-    let find_next_unused_id = |start: SpaceId| -> Result<SpaceId, Error> {
+    let find_next_unused_id = |start: u32| -> Result<u32, Error> {
         let iter = sys_space.select(IteratorType::GE, &[start])?;
         let mut next_id = start;
         for tuple in iter {
-            let id: SpaceId = tuple
+            let id: u32 = tuple
                 .field(0)
                 .expect("space metadata should decode fine")
                 .expect("space id should always be present");
@@ -206,14 +210,17 @@ pub fn generate_space_id(is_temporary: bool) -> Result<SpaceId, Error> {
     Ok(space_id)
 }
 
-pub fn space_metadata(space_id: SpaceId) -> Result<Metadata<'static>, Error> {
+pub fn space_metadata(space_id: impl Into<SpaceId>) -> Result<Metadata<'static>, Error> {
     let sys_space = SystemSpace::VSpace.as_space();
-    let tuple = sys_space.get(&[space_id])?.ok_or(Error::MetaNotFound)?;
+    let tuple = sys_space
+        .get(&[space_id.into().0])?
+        .ok_or(Error::MetaNotFound)?;
     tuple.decode::<Metadata>()
 }
 
 /// Drop a space.
-pub fn drop_space(space_id: SpaceId) -> Result<(), Error> {
+pub fn drop_space(space_id: impl Into<SpaceId>) -> Result<(), Error> {
+    let space_id = space_id.into().0;
     // Delete automatically generated sequence.
     let sys_space_sequence: Space = SystemSpace::SpaceSequence.into();
     if let Some(t) = sys_space_sequence.get(&(space_id,))? {
@@ -6,10 +6,10 @@
 //! See also:
 //! - [Lua reference: Submodule box.space](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_space/)
 //! - [C API reference: Module box](https://www.tarantool.io/en/doc/latest/dev_guide/reference_capi/box/)
-use crate::error::{Error, TarantoolError};
+use crate::error::{BoxError, Error, TarantoolError, TarantoolErrorCode};
 use crate::ffi::tarantool as ffi;
-use crate::index::{Index, IndexIterator, IteratorType};
-use crate::tuple::{Encode, ToTupleBuffer, Tuple, TupleBuffer};
+use crate::index::{Index, IndexId, IndexIterator, IteratorType, YieldingIndexIterator};
+use crate::tuple::{DecodeOwned, Encode, RawBytes, ToTupleBuffer, Tuple, TupleBuffer};
 use crate::unwrap_or;
 use crate::util::Value;
 use crate::{msgpack, tuple_from_box_api};
@@ -19,11 +19,12 @@ use std::borrow::Cow;
 use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::marker::PhantomData;
 use std::ops::Range;
 use std::os::raw::c_char;
 
 /// End of the reserved range of system spaces.
-pub const SYSTEM_ID_MAX: SpaceId = 511;
+pub const SYSTEM_ID_MAX: u32 = 511;
 
 /// Maximum possible space id.
 ///
@@ -36,9 +37,81 @@ pub const SYSTEM_ID_MAX: SpaceId = 511;
 ///
 /// You can also use `box.schema.SPACE_MAX` from lua to know for a fact what is
 /// the maximum allowed space id on your tarantool version.
-pub const SPACE_ID_MAX: SpaceId = (i32::MAX as SpaceId) - 1;
+pub const SPACE_ID_MAX: u32 = (i32::MAX as u32) - 1;
 
-pub type SpaceId = u32;
+/// Defines a newtype wrapper around a `u32` tarantool object id.
+///
+/// The wrapper is encoded/decoded (both msgpack and serde) as a plain integer,
+/// but it doesn't implicitly convert to other ids or integers.
+macro_rules! define_id_type {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+            serde::Serialize, serde::Deserialize,
+        )]
+        #[serde(transparent)]
+        #[repr(transparent)]
+        pub struct $name(pub u32);
+
+        impl From<u32> for $name {
+            #[inline(always)]
+            fn from(id: u32) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<$name> for u32 {
+            #[inline(always)]
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            #[inline(always)]
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl $crate::msgpack::Encode for $name {
+            #[inline(always)]
+            fn encode(
+                &self,
+                w: &mut impl std::io::Write,
+                context: &$crate::msgpack::Context,
+            ) -> std::result::Result<(), $crate::msgpack::EncodeError> {
+                <u32 as $crate::msgpack::Encode>::encode(&self.0, w, context)
+            }
+        }
+
+        impl<'de> $crate::msgpack::Decode<'de> for $name {
+            #[inline(always)]
+            fn decode(
+                r: &mut &'de [u8],
+                context: &$crate::msgpack::Context,
+            ) -> std::result::Result<Self, $crate::msgpack::DecodeError> {
+                <u32 as $crate::msgpack::Decode>::decode(r, context).map(Self)
+            }
+        }
+    };
+}
+pub(crate) use define_id_type;
+
+define_id_type! {
+    /// Id of a tarantool space.
+    ///
+    /// Use this instead of a bare `u32` to avoid mixing up space ids with
+    /// index ids or other numbers.
+    ///
+    /// ```compile_fail
+    /// use tarantool::{index::IndexId, space::SpaceId};
+    /// let index_id = IndexId(0);
+    /// let space_id: SpaceId = index_id; // error: mismatched types
+    /// ```
+    SpaceId
+}
 
 /// Provides access to system spaces
 ///
@@ -176,7 +249,7 @@ impl Default for SpaceEngineType {
 pub struct SpaceCreateOptions {
     pub if_not_exists: bool,
     pub engine: SpaceEngineType,
-    pub id: Option<SpaceId>,
+    pub id: Option<u32>,
     pub field_count: u32,
     pub user: Option<String>,
     pub space_type: SpaceType,
@@ -210,6 +283,13 @@ pub enum SpaceType {
     /// Same as `{ is_local = true }` in lua.
     DataLocal,
 
+    /// Space is created on all replicas and exists after restart,
+    /// but the data is neither persisted nor replicated.
+    /// Unlike [`SpaceType::DataTemporary`] the space is also replication-local.
+    ///
+    /// Same as `{ is_local = true, temporary = true }` in lua.
+    DataLocalTemporary,
+
     /// Space is created on all replicas and exists after restart,
     /// data is persisted and replicated synchronously, see
     /// <https://www.tarantool.io/en/doc/latest/concepts/replication/repl_sync/>
@@ -334,6 +414,85 @@ impl Field {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// TriggerHandle
+////////////////////////////////////////////////////////////////////////////////
+
+/// A handle to an `on_replace` trigger of a space, see
+/// [`Space::on_replace_triggers`].
+///
+/// The handle keeps a reference to the trigger's lua function, so the
+/// function isn't garbage collected while the handle is alive even if the
+/// trigger is removed.
+///
+/// The reference is released in the lua state of the tx thread when the
+/// handle is dropped, so the handle can't be sent to other threads.
+pub struct TriggerHandle {
+    space_id: u32,
+    /// Reference to the trigger function in the lua registry.
+    func_ref: i32,
+    /// Makes the handle `!Send` and `!Sync`.
+    marker: PhantomData<*const ()>,
+}
+
+impl TriggerHandle {
+    /// Id of the space the trigger was set on.
+    #[inline(always)]
+    pub fn space_id(&self) -> SpaceId {
+        SpaceId(self.space_id)
+    }
+
+    /// Removes the trigger from the space. Does nothing if the trigger was
+    /// already removed.
+    pub fn remove(self) -> Result<(), Error> {
+        crate::lua_state()
+            .exec_with(
+                "local space_id, f = ...
+                local space = box.space[space_id]
+                for _, t in ipairs(space:on_replace()) do
+                    if t == f then
+                        space:on_replace(nil, f)
+                        return
+                    end
+                end",
+                (self.space_id, &self),
+            )
+            .map_err(crate::tlua::LuaError::from)?;
+        Ok(())
+    }
+}
+
+impl<L: crate::tlua::AsLua> crate::tlua::Push<L> for TriggerHandle {
+    type Err = crate::tlua::Void;
+
+    #[inline(always)]
+    fn push_to_lua(&self, lua: L) -> crate::tlua::PushResult<L, Self> {
+        use crate::tlua::ffi as lua_ffi;
+        unsafe {
+            lua_ffi::lua_rawgeti(lua.as_lua(), lua_ffi::LUA_REGISTRYINDEX, self.func_ref);
+            Ok(crate::tlua::PushGuard::new(lua, 1))
+        }
+    }
+}
+
+impl<L: crate::tlua::AsLua> crate::tlua::PushOne<L> for TriggerHandle {}
+
+impl Drop for TriggerHandle {
+    fn drop(&mut self) {
+        use crate::tlua::{ffi as lua_ffi, AsLua};
+        let lua = crate::lua_state();
+        unsafe { lua_ffi::luaL_unref(lua.as_lua(), lua_ffi::LUA_REGISTRYINDEX, self.func_ref) }
+    }
+}
+
+impl std::fmt::Debug for TriggerHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TriggerHandle")
+            .field("space_id", &self.space_id)
+            .finish_non_exhaustive()
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // FieldType
 ////////////////////////////////////////////////////////////////////////////////
@@ -363,6 +522,167 @@ crate::define_str_enum! {
     }
 }
 
+impl FieldType {
+    /// Returns `true` if values of a tuple field of this type can be decoded
+    /// into `T`.
+    ///
+    /// This can be used to check at startup that the format of a space
+    /// corresponds to the rust types used for decoding its tuples, instead of
+    /// getting decoding errors later.
+    ///
+    /// Note that only the kind of values is checked, so decoding may still
+    /// fail for some values, e.g. if an `unsigned` value doesn't fit into an
+    /// `u8`. Nullability of the field is not checked either.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use tarantool::space::FieldType;
+    ///
+    /// assert!(FieldType::String.is_compatible_with::<String>());
+    /// assert!(!FieldType::String.is_compatible_with::<i32>());
+    /// assert!(FieldType::Unsigned.is_compatible_with::<Option<u64>>());
+    /// ```
+    #[inline(always)]
+    pub fn is_compatible_with<T>(self) -> bool
+    where
+        T: FieldTypeCompat + ?Sized,
+    {
+        T::accepts_field_type(self)
+    }
+}
+
+/// Rust types which can hold the values of tuple fields of some
+/// [`FieldType`]s.
+///
+/// See [`FieldType::is_compatible_with`].
+pub trait FieldTypeCompat {
+    /// Returns `true` if values of a tuple field of type `field_type` can be
+    /// decoded into `Self`.
+    fn accepts_field_type(field_type: FieldType) -> bool;
+}
+
+macro_rules! impl_field_type_compat {
+    ($($t:ty),+ => |$field_type:ident| $accepts:expr) => {
+        $(
+            impl FieldTypeCompat for $t {
+                #[inline(always)]
+                fn accepts_field_type($field_type: FieldType) -> bool {
+                    $accepts
+                }
+            }
+        )+
+    };
+}
+
+use FieldType as FT;
+impl_field_type_compat! { u8, u16, u32, u64, usize => |t| t == FT::Unsigned }
+impl_field_type_compat! {
+    i8, i16, i32, i64, isize => |t| matches!(t, FT::Unsigned | FT::Integer)
+}
+impl_field_type_compat! {
+    f32, f64 => |t| matches!(t, FT::Unsigned | FT::Integer | FT::Double | FT::Number)
+}
+impl_field_type_compat! { bool => |t| t == FT::Boolean }
+impl_field_type_compat! {
+    str, String, Box<str>, std::rc::Rc<str>, std::sync::Arc<str> => |t| t == FT::String
+}
+impl_field_type_compat! {
+    serde_bytes::Bytes, serde_bytes::ByteBuf => |t| t == FT::Varbinary
+}
+#[cfg(any(feature = "picodata", feature = "standalone_decimal"))]
+impl_field_type_compat! { crate::decimal::Decimal => |t| t == FT::Decimal }
+impl_field_type_compat! { crate::uuid::Uuid => |t| t == FT::Uuid }
+impl_field_type_compat! { crate::datetime::Datetime => |t| t == FT::Datetime }
+
+impl FieldTypeCompat for rmpv::Value {
+    #[inline(always)]
+    fn accepts_field_type(_: FieldType) -> bool {
+        true
+    }
+}
+
+impl<T: FieldTypeCompat + ?Sized> FieldTypeCompat for &T {
+    #[inline(always)]
+    fn accepts_field_type(field_type: FieldType) -> bool {
+        T::accepts_field_type(field_type)
+    }
+}
+
+impl<T: FieldTypeCompat> FieldTypeCompat for Option<T> {
+    #[inline(always)]
+    fn accepts_field_type(field_type: FieldType) -> bool {
+        T::accepts_field_type(field_type)
+    }
+}
+
+impl<T: ?Sized + ToOwned> FieldTypeCompat for std::borrow::Cow<'_, T>
+where
+    T: FieldTypeCompat,
+{
+    #[inline(always)]
+    fn accepts_field_type(field_type: FieldType) -> bool {
+        T::accepts_field_type(field_type)
+    }
+}
+
+impl<T> FieldTypeCompat for [T] {
+    #[inline(always)]
+    fn accepts_field_type(field_type: FieldType) -> bool {
+        field_type == FieldType::Array
+    }
+}
+
+impl<T> FieldTypeCompat for Vec<T> {
+    #[inline(always)]
+    fn accepts_field_type(field_type: FieldType) -> bool {
+        field_type == FieldType::Array
+    }
+}
+
+impl<T, const N: usize> FieldTypeCompat for [T; N] {
+    #[inline(always)]
+    fn accepts_field_type(field_type: FieldType) -> bool {
+        field_type == FieldType::Array
+    }
+}
+
+impl<K, V, S> FieldTypeCompat for std::collections::HashMap<K, V, S> {
+    #[inline(always)]
+    fn accepts_field_type(field_type: FieldType) -> bool {
+        field_type == FieldType::Map
+    }
+}
+
+impl<K, V> FieldTypeCompat for std::collections::BTreeMap<K, V> {
+    #[inline(always)]
+    fn accepts_field_type(field_type: FieldType) -> bool {
+        field_type == FieldType::Map
+    }
+}
+
+impl From<crate::tuple::FieldType> for FieldType {
+    #[rustfmt::skip]
+    fn from(t: crate::tuple::FieldType) -> Self {
+        use crate::tuple::FieldType as T;
+        match t {
+            T::Any       => Self::Any,
+            T::Unsigned  => Self::Unsigned,
+            T::String    => Self::String,
+            T::Number    => Self::Number,
+            T::Double    => Self::Double,
+            T::Integer   => Self::Integer,
+            T::Boolean   => Self::Boolean,
+            T::Varbinary => Self::Varbinary,
+            T::Scalar    => Self::Scalar,
+            T::Decimal   => Self::Decimal,
+            T::Uuid      => Self::Uuid,
+            T::Datetime  => Self::Datetime,
+            T::Array     => Self::Array,
+            T::Map       => Self::Map,
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // IsNullable
 ////////////////////////////////////////////////////////////////////////////////
@@ -480,7 +800,7 @@ pub fn clear_cache() {
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Space {
-    id: SpaceId,
+    id: u32,
 }
 
 impl Space {
@@ -536,6 +856,14 @@ impl Space {
         }
     }
 
+    /// Find space by name. Same as [`Space::find`], but returns
+    /// [`Error::SpaceNotFound`] with the requested name if the space doesn't
+    /// exist.
+    #[inline]
+    pub fn find_or_err(name: &str) -> Result<Self, Error> {
+        Self::find(name).ok_or_else(|| Error::SpaceNotFound { name: name.into() })
+    }
+
     /// Memorized version of [`Space::find`] function.
     ///
     /// The function performs SELECT request to `_vspace` system space only if
@@ -557,22 +885,56 @@ impl Space {
         SPACE_CACHE.with(|cache| cache.space(name))
     }
 
+    /// Find space by id.
+    ///
+    /// This function performs SELECT request to `_vspace` system space.
+    /// - `id` - space id, either a [`SpaceId`] or a plain `u32`
+    ///
+    /// Returns:
+    /// - `Ok(None)` if not found
+    /// - `Ok(Some(space))` otherwise
+    /// - `Err(e)` if the request to `_vspace` failed
+    #[inline]
+    pub fn find_by_id(id: impl Into<SpaceId>) -> Result<Option<Self>, Error> {
+        let id = id.into().0;
+        let vspace = SystemSpace::VSpace.as_space();
+        Ok(vspace.get(&[id])?.map(|_| Self { id }))
+    }
+
+    /// Create a `Space` with `id`.
+    ///
+    /// # Safety
+    /// `id` must be a valid tarantool space id. Only use this function with
+    /// ids acquired from tarantool in some way, e.g. from lua code.
+    #[inline(always)]
+    pub const unsafe fn new_unchecked(id: SpaceId) -> Self {
+        Self { id: id.0 }
+    }
+
     /// Create a `Space` with `id`.
     ///
     /// # Safety
     /// `id` must be a valid tarantool space id. Only use this function with
     /// ids acquired from tarantool in some way, e.g. from lua code.
+    #[deprecated = "use `Space::new_unchecked` which accepts a `SpaceId` instead"]
     #[inline(always)]
-    pub const unsafe fn from_id_unchecked(id: SpaceId) -> Self {
-        Self { id }
+    pub const unsafe fn from_id_unchecked(id: u32) -> Self {
+        Self::new_unchecked(SpaceId(id))
     }
 
     /// Get space ID.
+    #[deprecated = "use `Space::space_id` which returns a `SpaceId` instead"]
     #[inline(always)]
-    pub const fn id(&self) -> SpaceId {
+    pub const fn id(&self) -> u32 {
         self.id
     }
 
+    /// Get space ID as a [`SpaceId`].
+    #[inline(always)]
+    pub const fn space_id(&self) -> SpaceId {
+        SpaceId(self.id)
+    }
+
     /// Create new index.
     ///
     /// - `name` - name of index to create, which should conform to the rules for object names.
@@ -615,6 +977,17 @@ impl Space {
         }
     }
 
+    /// Find index by name. Same as [`Space::index`], but returns
+    /// [`Error::IndexNotFound`] with the requested name if the index doesn't
+    /// exist.
+    #[inline]
+    pub fn index_or_err(&self, name: &str) -> Result<Index, Error> {
+        self.index(name).ok_or_else(|| Error::IndexNotFound {
+            space_id: self.id,
+            name: name.into(),
+        })
+    }
+
     /// Memorized version of [`Space::index`] function.
     ///
     /// This function performs SELECT request to `_vindex` system space.
@@ -636,6 +1009,22 @@ impl Space {
         SPACE_CACHE.with(|cache| cache.index(self, name))
     }
 
+    /// Find index by id.
+    ///
+    /// This function performs SELECT request to `_vindex` system space.
+    /// - `id` - index id, either an [`IndexId`] or a plain `u32`
+    ///
+    /// Returns:
+    /// - `Ok(None)` if not found
+    /// - `Ok(Some(index))` otherwise
+    /// - `Err(e)` if the request to `_vindex` failed
+    #[inline]
+    pub fn index_by_id(&self, id: impl Into<IndexId>) -> Result<Option<Index>, Error> {
+        let id = id.into().0;
+        let vindex = SystemSpace::VIndex.as_space();
+        Ok(vindex.get(&[self.id, id])?.map(|_| Index::new(self.id, id)))
+    }
+
     /// Returns index with id = 0
     #[inline(always)]
     pub fn primary_key(&self) -> Index {
@@ -670,6 +1059,44 @@ impl Space {
         .map(|t| t.expect("Returned tuple cannot be null"))
     }
 
+    /// Insert a `value` into a space, or get the existing tuple if one with
+    /// the same primary key already exists.
+    ///
+    /// Returns [`InsertOutcome::Inserted`] with the new tuple if the insert
+    /// succeeded and [`InsertOutcome::Conflict`] with the existing tuple if
+    /// there was a duplicate key error. In the latter case the primary key is
+    /// extracted from `value` to fetch the conflicting tuple.
+    ///
+    /// If the duplicate key error was caused by a unique secondary index, the
+    /// error is returned as is.
+    pub fn insert_or_get<T>(&self, value: &T) -> Result<InsertOutcome, Error>
+    where
+        T: ToTupleBuffer + ?Sized,
+    {
+        let buf;
+        let data = unwrap_or!(value.tuple_data(), {
+            buf = value.to_tuple_buffer()?;
+            buf.as_ref()
+        });
+
+        let err = match self.insert(RawBytes::new(data)) {
+            Ok(tuple) => return Ok(InsertOutcome::Inserted(tuple)),
+            Err(Error::Tarantool(e)) if e.error_code() == TarantoolErrorCode::TupleFound as u32 => {
+                Error::Tarantool(e)
+            }
+            Err(e) => return Err(e),
+        };
+
+        let primary_key = self.primary_key();
+        let key_def = primary_key.meta()?.try_to_key_def().map_err(Error::other)?;
+        let key = key_def.extract_key(&Tuple::try_from_slice(data)?)?;
+        match primary_key.get(&key)? {
+            Some(existing) => Ok(InsertOutcome::Conflict(existing)),
+            // The conflict is in a unique secondary index
+            None => Err(err),
+        }
+    }
+
     /// Insert a `value` into a space.
     ///
     /// If a tuple with the same primary key already exists, it is replaced
@@ -704,6 +1131,64 @@ impl Space {
         .map(|t| t.expect("Returned tuple cannot be null"))
     }
 
+    /// Insert a `value` into a space replacing the tuple with the same primary
+    /// key if one exists, and return the replaced tuple.
+    ///
+    /// Returns `Ok(None)` if there was no tuple with the same primary key.
+    ///
+    /// First `value` is inserted, which succeeds if there is no tuple with the
+    /// same primary key. Otherwise the existing tuple is fetched by the primary
+    /// key extracted from `value` and then replaced. This is all done in a
+    /// single transaction (unless there is an active one already), so the
+    /// returned tuple is exactly the one which was replaced.
+    ///
+    /// Any errors are returned as is, e.g. if `value` doesn't match the space
+    /// format.
+    ///
+    /// ```no_run
+    /// use tarantool::space::Space;
+    ///
+    /// let space = Space::find("users").unwrap();
+    /// if let Some(old) = space.replace_returning_old(&(1, "John")).unwrap() {
+    ///     println!("replaced {:?}", old);
+    /// }
+    /// ```
+    pub fn replace_returning_old<T>(&self, value: &T) -> Result<Option<Tuple>, Error>
+    where
+        T: ToTupleBuffer + ?Sized,
+    {
+        let buf;
+        let data = unwrap_or!(value.tuple_data(), {
+            buf = value.to_tuple_buffer()?;
+            buf.as_ref()
+        });
+
+        let do_replace = || -> Result<Option<Tuple>, Error> {
+            match self.insert(RawBytes::new(data)) {
+                Ok(_) => return Ok(None),
+                Err(Error::Tarantool(e))
+                    if e.error_code() == TarantoolErrorCode::TupleFound as u32 => {}
+                Err(e) => return Err(e),
+            }
+            let primary_key = self.primary_key();
+            // SAFETY: the tuple has passed the format check of the insert
+            let key =
+                unsafe { primary_key.extract_key_from_conforming(&Tuple::try_from_slice(data)?)? };
+            let old = primary_key.get(&key)?;
+            self.replace(RawBytes::new(data))?;
+            Ok(old)
+        };
+
+        if crate::transaction::is_in_transaction() {
+            return do_replace();
+        }
+        match crate::transaction::transaction(do_replace) {
+            Ok(old) => Ok(old),
+            Err(crate::transaction::TransactionError::RolledBack(e)) => Err(e),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// Insert a tuple into a space. If a tuple with the same primary key already exists, it replaces the existing tuple
     /// with a new one. Alias for [space.replace()](#method.replace)
     #[inline(always)]
@@ -745,9 +1230,71 @@ impl Space {
     ///
     /// This number, which is stored in Tarantool’s internal memory, represents the total number of bytes in all tuples,
     /// excluding index keys. For a measure of index size, see [index.bsize()](../index/struct.Index.html#method.bsize).
-    #[inline(always)]
+    ///
+    /// **NOTE**: unlike [`Self::len`] this is not implemented via the ffi.
+    /// Tarantool's module C API only exposes the size of an index
+    /// (`box_index_bsize`), while the size of the tuples is stored in the
+    /// engine specific part of the space (e.g. `memtx_space::bsize`), which
+    /// isn't part of the public API and whose layout differs between
+    /// Tarantool versions. So the value is retrieved by calling lua
+    /// `space_object:bsize()`, which means this function has the overhead of
+    /// a lua call and must only be called from the tx thread.
+    #[inline]
     pub fn bsize(&self) -> Result<usize, Error> {
-        self.primary_key().bsize()
+        let bsize = crate::lua_state()
+            .eval_with("return box.space[...]:bsize()", self.id)
+            .map_err(crate::tlua::LuaError::from)?;
+        Ok(bsize)
+    }
+
+    /// Returns the handles of the `on_replace` triggers currently set on the
+    /// space, in the same order as lua `space_object:on_replace()` returns
+    /// them.
+    ///
+    /// The triggers may have been set from lua. Each handle can be used to
+    /// remove the corresponding trigger, see [`TriggerHandle::remove`].
+    pub fn on_replace_triggers(&self) -> Result<Vec<TriggerHandle>, Error> {
+        use crate::tlua::{ffi as lua_ffi, AsLua};
+
+        let lua = crate::lua_state();
+        let list: crate::tlua::LuaTable<_> = lua
+            .eval_with("return box.space[...]:on_replace()", self.id)
+            .map_err(crate::tlua::LuaError::from)?;
+        let l = list.as_lua();
+        let mut res = vec![];
+        for i in 1.. {
+            // SAFETY: the list is at the top of the stack and each value
+            // pushed onto the stack is either popped or moved to the registry
+            unsafe {
+                lua_ffi::lua_rawgeti(l, -1, i);
+                if lua_ffi::lua_isnil(l, -1) {
+                    lua_ffi::lua_pop(l, 1);
+                    break;
+                }
+                let func_ref = lua_ffi::luaL_ref(l, lua_ffi::LUA_REGISTRYINDEX);
+                res.push(TriggerHandle {
+                    space_id: self.id,
+                    func_ref,
+                    marker: PhantomData,
+                });
+            }
+        }
+        Ok(res)
+    }
+
+    /// Removes all of the `on_replace` triggers set on the space, including
+    /// the ones set from lua. The `before_replace` triggers are left intact.
+    pub fn clear_on_replace_triggers(&self) -> Result<(), Error> {
+        crate::lua_state()
+            .exec_with(
+                "local space = box.space[...]
+                for _, f in ipairs(space:on_replace()) do
+                    space:on_replace(nil, f)
+                end",
+                self.id,
+            )
+            .map_err(crate::tlua::LuaError::from)?;
+        Ok(())
     }
 
     /// Search for a tuple in the given space.
@@ -759,11 +1306,46 @@ impl Space {
         self.primary_key().get(key)
     }
 
+    /// Search for a tuple in the given space and decode it as `T`.
+    ///
+    /// This is a shorthand for [`Self::get`] followed by [`Tuple::decode`].
+    /// Returns `Ok(None)` if there's no tuple with the given `key` and an
+    /// error if the tuple is found, but can't be decoded as `T`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tarantool::space::Space;
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct User {
+    ///     id: u64,
+    ///     name: String,
+    /// }
+    ///
+    /// let space = Space::find("users").unwrap();
+    /// let user: Option<User> = space.get_as(&(1,)).unwrap();
+    /// ```
+    #[inline]
+    pub fn get_as<K, T>(&self, key: &K) -> Result<Option<T>, Error>
+    where
+        K: ToTupleBuffer + ?Sized,
+        T: DecodeOwned,
+    {
+        match self.get(key)? {
+            Some(tuple) => Ok(Some(tuple.decode()?)),
+            None => Ok(None),
+        }
+    }
+
     /// Search for a tuple or a set of tuples in the given space. This method doesn’t yield
     /// (for details see [Сooperative multitasking](https://www.tarantool.io/en/doc/latest/book/box/atomic_index/#atomic-cooperative-multitasking)).
     ///
     /// - `type` - iterator type
     /// - `key` - encoded key in the MsgPack Array format (`[part1, part2, ...]`).
+    ///
+    /// `key` may be a prefix of the primary key parts, see
+    /// [`Index::select`] for details.
     #[inline(always)]
     pub fn select<K>(&self, iterator_type: IteratorType, key: &K) -> Result<IndexIterator, Error>
     where
@@ -772,11 +1354,65 @@ impl Space {
         self.primary_key().select(iterator_type, key)
     }
 
+    /// Same as [`Self::select`], but the returned iterator yields the current
+    /// fiber after every `yield_every` tuples to keep the instance responsive
+    /// while iterating over a large number of tuples. See
+    /// [`YieldingIndexIterator`] for details.
+    #[inline(always)]
+    pub fn select_yielding<K>(
+        &self,
+        iterator_type: IteratorType,
+        key: &K,
+        yield_every: usize,
+    ) -> Result<YieldingIndexIterator, Error>
+    where
+        K: ToTupleBuffer + ?Sized,
+    {
+        self.primary_key()
+            .select_yielding(iterator_type, key, yield_every)
+    }
+
+    /// Iterate over all the tuples of a key-value space, decoding each tuple
+    /// as a `(key, value)` pair.
+    ///
+    /// This is a shorthand for `select(IteratorType::All, &())` followed by
+    /// [`Tuple::decode`] of every tuple.
+    ///
+    /// Decoding errors are reported lazily, i.e. each item of the iterator is
+    /// a `Result`, so a single malformed tuple doesn't prevent iterating over
+    /// the rest of them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tarantool::space::Space;
+    ///
+    /// let space = Space::find("kv").unwrap();
+    /// for pair in space.pairs::<String, u64>().unwrap() {
+    ///     let (key, value) = pair.unwrap();
+    ///     println!("{key}: {value}");
+    /// }
+    /// ```
+    #[inline]
+    pub fn pairs<K, V>(&self) -> Result<impl Iterator<Item = Result<(K, V), Error>>, Error>
+    where
+        (K, V): DecodeOwned,
+    {
+        let iter = self.select(IteratorType::All, &())?;
+        Ok(iter.map(|tuple| tuple.decode()))
+    }
+
     /// Return the number of tuples. Compared with [space.len()](#method.len), this method works slower because
     /// [space.count()](#method.count) scans the entire space to count the tuples.
     ///
     /// - `type` - iterator type
     /// - `key` - encoded key in the MsgPack Array format (`[part1, part2, ...]`).
+    ///
+    /// The tuples are counted by the primary index natively (see
+    /// [`Index::count`]), so this is faster than `select(...)?.count()`,
+    /// because the tuples aren't returned to the caller. Use
+    /// [`Index::count`] to count the tuples matching a key of a secondary
+    /// index.
     #[inline(always)]
     pub fn count<K>(&self, iterator_type: IteratorType, key: &K) -> Result<usize, Error>
     where
@@ -817,6 +1453,11 @@ impl Space {
     /// - `key` - encoded key in the MsgPack Array format (`[part1, part2, ...]`).
     /// - `ops` - encoded operations in the MsgPack array format, e.g. `[['=', field_id, value], ['!', 2, 'xxx']]`
     ///
+    /// As with [`get`](#method.get), `key` can be any [`ToTupleBuffer`]:
+    /// a tuple like `&(id,)`, a slice like `&[id]` or a struct implementing
+    /// [`Encode`](crate::tuple::Encode). It must encode as an array whose
+    /// elements match the parts of the primary index in order.
+    ///
     /// Returns a new tuple.
     ///
     /// See also: [space.upsert()](#method.upsert)
@@ -865,6 +1506,71 @@ impl Space {
         self.primary_key().update_raw(key, ops)
     }
 
+    /// Atomically add `delta` to the integer field `field` (zero based) of the
+    /// tuple with the given `key` and return the new value of the field.
+    ///
+    /// If there's no tuple with such key, a new one is inserted consisting of
+    /// the `key` fields followed by `delta`. So in this case the counter
+    /// field must immediately follow the primary key fields (e.g. a space
+    /// with format `(name, count)`), otherwise an error is returned.
+    ///
+    /// The read and the write are done in a single transaction (unless there
+    /// is an active one already), so concurrent increments from other fibers
+    /// don't interfere.
+    ///
+    /// ```no_run
+    /// use tarantool::space::Space;
+    ///
+    /// let counters = Space::find("counters").unwrap();
+    /// let n = counters.increment(&("requests",), 1, 1).unwrap();
+    /// ```
+    pub fn increment<K>(&self, key: &K, field: u32, delta: i64) -> Result<i64, Error>
+    where
+        K: ToTupleBuffer + ?Sized,
+    {
+        let key = key.to_tuple_buffer()?;
+        let key = key.as_ref();
+
+        let do_increment = || -> Result<i64, Error> {
+            let mut ops = UpdateOps::new();
+            ops.add(field, delta)?;
+            if let Some(tuple) = self.update(RawBytes::new(key), ops)? {
+                let value = tuple.field::<i64>(field)?;
+                return value.ok_or_else(|| {
+                    BoxError::new(
+                        TarantoolErrorCode::IllegalParams,
+                        format!("field {field} is missing after increment"),
+                    )
+                    .into()
+                });
+            }
+
+            let mut key_fields = key;
+            let key_len = rmp::decode::read_array_len(&mut key_fields)?;
+            if field != key_len {
+                return Err(BoxError::new(
+                    TarantoolErrorCode::IllegalParams,
+                    format!(
+                        "cannot insert a new counter: field {field} doesn't follow the {key_len} key field(s)"
+                    ),
+                )
+                .into());
+            }
+            let mut tuple = Vec::with_capacity(key.len() + 10);
+            rmp::encode::write_array_len(&mut tuple, key_len + 1)?;
+            tuple.extend_from_slice(key_fields);
+            rmp::encode::write_sint(&mut tuple, delta)?;
+            self.insert(RawBytes::new(&tuple))?;
+            Ok(delta)
+        };
+
+        if crate::transaction::is_in_transaction() {
+            do_increment()
+        } else {
+            Ok(crate::transaction::transaction(do_increment)?)
+        }
+    }
+
     /// Update or insert a tuple.
     ///
     /// If there is an existing tuple which matches the tuple key fields, then the request has the same effect as
@@ -930,6 +1636,20 @@ impl Space {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// InsertOutcome
+////////////////////////////////////////////////////////////////////////////////
+
+/// The result of [`Space::insert_or_get`].
+#[derive(Debug)]
+pub enum InsertOutcome {
+    /// The tuple was inserted. Contains the new tuple.
+    Inserted(Tuple),
+    /// A tuple with the same primary key already exists. Contains the
+    /// existing tuple.
+    Conflict(Tuple),
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Metadata
 ////////////////////////////////////////////////////////////////////////////////
@@ -955,6 +1675,7 @@ impl Encode for Metadata<'_> {}
 pub struct Builder<'a> {
     name: &'a str,
     opts: SpaceCreateOptions,
+    space_type_error: Option<String>,
 }
 
 macro_rules! define_setters {
@@ -975,42 +1696,87 @@ impl<'a> Builder<'a> {
         Self {
             name,
             opts: Default::default(),
+            space_type_error: None,
         }
     }
 
     define_setters! {
         if_not_exists(if_not_exists: bool)
         engine(engine: SpaceEngineType)
-        id(id: SpaceId)
         field_count(field_count: u32)
         user(user: String)
         space_type(space_type: SpaceType)
     }
 
-    #[deprecated = "use Builder::space_type instead"]
+    /// Set the id of the space, either a [`SpaceId`] or a plain `u32`.
     #[inline(always)]
-    pub fn is_local(mut self, is_local: bool) -> Self {
-        if is_local {
-            self.opts.space_type = SpaceType::DataLocal
-        }
+    pub fn id(mut self, id: impl Into<SpaceId>) -> Self {
+        self.opts.id = Some(id.into().0);
         self
     }
 
+    /// Make the space replication-local (same as `{ is_local = true }` in lua).
+    ///
+    /// Can be combined with [`Builder::temporary`], in which case the space
+    /// type becomes [`SpaceType::DataLocalTemporary`]. Combining it with
+    /// [`Builder::is_sync`] or [`SpaceType::Temporary`] is an error which is
+    /// reported by [`Builder::create`].
     #[deprecated = "use Builder::space_type instead"]
     #[inline(always)]
-    pub fn temporary(mut self, temporary: bool) -> Self {
-        if temporary {
-            self.opts.space_type = SpaceType::DataTemporary
-        }
-        self
+    pub fn is_local(self, is_local: bool) -> Self {
+        self.with_space_type_flag(is_local, SpaceType::DataLocal)
+    }
+
+    /// Make the space data-temporary (same as `{ temporary = true }` in lua).
+    ///
+    /// See [`Builder::is_local`] for the allowed combinations.
+    #[deprecated = "use Builder::space_type instead"]
+    #[inline(always)]
+    pub fn temporary(self, temporary: bool) -> Self {
+        self.with_space_type_flag(temporary, SpaceType::DataTemporary)
     }
 
+    /// Make the space synchronous (same as `{ is_sync = true }` in lua).
+    ///
+    /// Cannot be combined with [`Builder::is_local`] or [`Builder::temporary`].
     #[deprecated = "use Builder::space_type instead"]
     #[inline(always)]
-    pub fn is_sync(mut self, is_sync: bool) -> Self {
-        if is_sync {
-            self.opts.space_type = SpaceType::Synchronous
+    pub fn is_sync(self, is_sync: bool) -> Self {
+        self.with_space_type_flag(is_sync, SpaceType::Synchronous)
+    }
+
+    fn with_space_type_flag(mut self, enabled: bool, flag: SpaceType) -> Self {
+        use SpaceType::*;
+
+        if !enabled || self.space_type_error.is_some() {
+            return self;
         }
+
+        self.opts.space_type = match (self.opts.space_type, flag) {
+            (Normal, flag) => flag,
+            (current, flag) if current == flag => current,
+            (DataLocal | DataTemporary | DataLocalTemporary, DataLocal | DataTemporary) => {
+                DataLocalTemporary
+            }
+            (current, flag) => {
+                fn lua_option(space_type: SpaceType) -> &'static str {
+                    match space_type {
+                        Normal => "type = 'normal'",
+                        DataTemporary => "temporary",
+                        Temporary => "type = 'temporary'",
+                        DataLocal => "is_local",
+                        DataLocalTemporary => "is_local and temporary",
+                        Synchronous => "is_sync",
+                    }
+                }
+                self.space_type_error = Some(format!(
+                    "space option `{}` is incompatible with `{}`",
+                    lua_option(flag),
+                    lua_option(current),
+                ));
+                return self;
+            }
+        };
         self
     }
 
@@ -1065,15 +1831,25 @@ impl<'a> Builder<'a> {
     /// of an error. This shouldn't be a problem if you always consider this
     /// function returning an error to be worthy of a transcation roll back,
     /// which you should.
+    ///
+    /// Returns an error if incompatible space type options were specified,
+    /// see [`Builder::is_local`].
     #[inline(always)]
     pub fn create(self) -> crate::Result<Space> {
-        crate::schema::space::create_space(self.name, &self.opts)
+        let (name, opts) = self.into_parts()?;
+        crate::schema::space::create_space(name, &opts)
     }
 
     /// Destructure the builder struct into a tuple of name and space options.
+    ///
+    /// Returns an error if incompatible space type options were specified,
+    /// see [`Builder::is_local`].
     #[inline(always)]
-    pub fn into_parts(self) -> (&'a str, SpaceCreateOptions) {
-        (self.name, self.opts)
+    pub fn into_parts(self) -> crate::Result<(&'a str, SpaceCreateOptions)> {
+        if let Some(message) = self.space_type_error {
+            return Err(BoxError::new(TarantoolErrorCode::IllegalParams, message).into());
+        }
+        Ok((self.name, self.opts))
     }
 }
 
@@ -1366,8 +2142,8 @@ macro_rules! upsert {
 
 /// Returns `None` if fully temporary spaces aren't supported in the current
 /// tarantool executable.
-pub fn space_id_temporary_min() -> Option<SpaceId> {
-    static mut VALUE: Option<Option<SpaceId>> = None;
+pub fn space_id_temporary_min() -> Option<u32> {
+    static mut VALUE: Option<Option<u32>> = None;
     // SAFETY: this is safe as we only call this from tx thread.
     unsafe {
         if (*std::ptr::addr_of!(VALUE)).is_none() {
@@ -1434,7 +2210,7 @@ mod test {
 
         let space = Space::builder(&crate::temp_space_name!()).create().unwrap();
         // We just take the next available space id.
-        assert_eq!(space.id(), spaces.last().unwrap().id() + 1);
+        assert_eq!(space.space_id().0, spaces.last().unwrap().space_id().0 + 1);
         // Whatever was in _schema.max_id is still there.
         let not_max_id = sys_schema
             .get(&["max_id"])
@@ -1458,13 +2234,13 @@ mod test {
             .get(&["max_id"])
             .unwrap()
             .unwrap()
-            .field::<SpaceId>(1)
+            .field::<u32>(1)
             .unwrap()
             .unwrap();
         // We don't actually read value of _schema.max_id.
-        assert_ne!(space.id(), max_id_before + 1);
+        assert_ne!(space.space_id().0, max_id_before + 1);
         // But we do set it to the maximum known space id.
-        assert_eq!(space.id(), max_id);
+        assert_eq!(space.space_id().0, max_id);
         spaces.push(space);
 
         //
@@ -1479,13 +2255,13 @@ mod test {
             .get(&["max_id"])
             .unwrap()
             .unwrap()
-            .field::<SpaceId>(1)
+            .field::<u32>(1)
             .unwrap()
             .unwrap();
         // We just take the next available space id.
-        assert_eq!(space.id(), spaces.last().unwrap().id() + 1);
+        assert_eq!(space.space_id().0, spaces.last().unwrap().space_id().0 + 1);
         // And we never decrease _schema.max_id.
-        assert!(space.id() < max_id);
+        assert!(space.space_id().0 < max_id);
         assert_eq!(max_id, max_id_before);
         spaces.push(space);
 
@@ -1504,11 +2280,11 @@ mod test {
                 .get(&["max_id"])
                 .unwrap()
                 .unwrap()
-                .field::<SpaceId>(1)
+                .field::<u32>(1)
                 .unwrap()
                 .unwrap();
             // Space has a greater id
-            assert!(space.id() > max_id);
+            assert!(space.space_id().0 > max_id);
             // But _schema.max_id is unchanged.
             assert_eq!(max_id, max_id_before);
             spaces.push(space);
@@ -1519,4 +2295,28 @@ mod test {
             space.drop().unwrap();
         }
     }
+
+    #[crate::test(tarantool = "crate")]
+    fn field_type_compatibility() {
+        assert!(FieldType::String.is_compatible_with::<String>());
+        assert!(FieldType::String.is_compatible_with::<&str>());
+        assert!(!FieldType::String.is_compatible_with::<i32>());
+
+        assert!(FieldType::Unsigned.is_compatible_with::<u32>());
+        assert!(FieldType::Unsigned.is_compatible_with::<i64>());
+        assert!(FieldType::Integer.is_compatible_with::<i64>());
+        assert!(!FieldType::Integer.is_compatible_with::<u64>());
+        assert!(FieldType::Integer.is_compatible_with::<f64>());
+        assert!(!FieldType::Double.is_compatible_with::<i64>());
+
+        assert!(FieldType::Boolean.is_compatible_with::<Option<bool>>());
+        assert!(FieldType::Array.is_compatible_with::<Vec<String>>());
+        assert!(!FieldType::Map.is_compatible_with::<Vec<String>>());
+        assert!(FieldType::Map.is_compatible_with::<HashMap<String, i32>>());
+        assert!(FieldType::Any.is_compatible_with::<rmpv::Value>());
+        assert!(!FieldType::Any.is_compatible_with::<String>());
+
+        assert!(crate::tuple::FieldType::String.is_compatible_with::<String>());
+        assert!(!crate::tuple::FieldType::String.is_compatible_with::<i32>());
+    }
 }
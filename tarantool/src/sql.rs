@@ -1,10 +1,14 @@
 #![cfg(any(feature = "picodata", doc))]
 
-use crate::error::TarantoolError;
+use crate::error::{TarantoolError, TarantoolErrorCode};
 use crate::ffi;
 use crate::ffi::sql::ObufWrapper;
+use crate::network::protocol::codec::iproto_key;
+use crate::tuple::Tuple;
 use serde::Serialize;
-use std::io::Read;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
 use std::os::raw::c_char;
 use std::str;
 
@@ -20,6 +24,42 @@ pub fn prepare_and_execute_raw<IN>(
     bind_params: &IN,
     vdbe_max_steps: u64,
 ) -> crate::Result<impl Read>
+where
+    IN: Serialize,
+{
+    prepare_and_execute_obuf(query, bind_params, vdbe_max_steps)
+}
+
+/// Executes an SQL query without storing the prepared statement in the instance
+/// cache and decodes the result.
+///
+/// Returns [`SqlResult::Rows`] for queries returning rows (e.g. `SELECT`) and
+/// [`SqlResult::Info`] with the number of affected rows and the generated
+/// autoincrement ids for the other ones (e.g. `INSERT`, `UPDATE`, `DELETE`).
+///
+/// # Example
+/// ```no_run
+/// use tarantool::sql::{self, SqlResult};
+///
+/// let res = sql::execute("INSERT INTO T (V) VALUES (?)", &("foo",), 100).unwrap();
+/// if let SqlResult::Info(info) = res {
+///     assert_eq!(info.row_count, 1);
+///     println!("new id: {:?}", info.autoincrement_ids);
+/// }
+/// ```
+pub fn execute<IN>(query: &str, bind_params: &IN, vdbe_max_steps: u64) -> crate::Result<SqlResult>
+where
+    IN: Serialize,
+{
+    let mut buf = prepare_and_execute_obuf(query, bind_params, vdbe_max_steps)?;
+    SqlResult::decode(&mut buf)
+}
+
+fn prepare_and_execute_obuf<IN>(
+    query: &str,
+    bind_params: &IN,
+    vdbe_max_steps: u64,
+) -> crate::Result<ObufWrapper>
 where
     IN: Serialize,
 {
@@ -120,23 +160,363 @@ impl Statement {
     where
         IN: Serialize,
     {
-        let mut buf = ObufWrapper::new(1024);
-        // 0x90 is an empty mp array
-        let mut param_data = vec![0x90];
-        if std::mem::size_of::<IN>() != 0 {
-            param_data = rmp_serde::to_vec(bind_params)?;
-            debug_assert!(
-                crate::msgpack::skip_value(&mut std::io::Cursor::new(&param_data)).is_ok()
-            );
+        execute_prepared_raw(self.id(), bind_params, vdbe_max_steps)
+    }
+}
+
+fn execute_prepared_raw<IN>(
+    stmt_id: u32,
+    bind_params: &IN,
+    vdbe_max_steps: u64,
+) -> crate::Result<ObufWrapper>
+where
+    IN: Serialize,
+{
+    let mut buf = ObufWrapper::new(1024);
+    // 0x90 is an empty mp array
+    let mut param_data = vec![0x90];
+    if std::mem::size_of::<IN>() != 0 {
+        param_data = rmp_serde::to_vec(bind_params)?;
+        debug_assert!(crate::msgpack::skip_value(&mut std::io::Cursor::new(&param_data)).is_ok());
+    }
+    let param_ptr = param_data.as_ptr() as *const u8;
+    let execute_result = unsafe {
+        ffi::sql::sql_execute_prepared_ext(stmt_id, param_ptr, vdbe_max_steps, buf.obuf())
+    };
+
+    if execute_result < 0 {
+        return Err(TarantoolError::last().into());
+    }
+    Ok(buf)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// SqlResult
+////////////////////////////////////////////////////////////////////////////////
+
+/// Decoded result of an SQL query execution, see [`execute`].
+#[derive(Debug)]
+pub enum SqlResult {
+    /// Rows returned by a query like `SELECT`, each row is a tuple.
+    Rows(Vec<Tuple>),
+    /// Information about the effect of a query like `INSERT`, `UPDATE` or
+    /// `DELETE`.
+    Info(SqlInfo),
+}
+
+/// Information about the effect of an SQL query which doesn't return rows
+/// (`IPROTO_SQL_INFO` in the response).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct SqlInfo {
+    /// Number of rows affected by the query.
+    pub row_count: u64,
+    /// Values generated for the autoincrement fields of the inserted rows.
+    pub autoincrement_ids: Vec<i64>,
+}
+
+impl SqlResult {
+    /// Decodes the raw msgpack result of a query execution, e.g. the one
+    /// returned by [`prepare_and_execute_raw`], [`Statement::execute_raw`] or
+    /// [`execute_cached`].
+    pub fn decode(stream: &mut impl Read) -> crate::Result<Self> {
+        let mut data = vec![];
+        stream.read_to_end(&mut data)?;
+        let mut cursor = Cursor::new(data.as_slice());
+
+        let mut result = None;
+        let map_len = rmp::decode::read_map_len(&mut cursor)?;
+        for _ in 0..map_len {
+            let key = rmp::decode::read_pfix(&mut cursor)?;
+            match key {
+                ffi::sql::IPROTO_DATA => {
+                    let n_rows = rmp::decode::read_array_len(&mut cursor)?;
+                    let mut rows = Vec::with_capacity(n_rows as _);
+                    for _ in 0..n_rows {
+                        let start = cursor.position() as usize;
+                        crate::msgpack::skip_value(&mut cursor)?;
+                        let end = cursor.position() as usize;
+                        rows.push(Tuple::try_from_slice(&data[start..end])?);
+                    }
+                    result = Some(Self::Rows(rows));
+                }
+                iproto_key::SQL_INFO => {
+                    result = Some(Self::Info(SqlInfo::decode(&mut cursor)?));
+                }
+                _ => {
+                    // E.g. IPROTO_METADATA
+                    crate::msgpack::skip_value(&mut cursor)?;
+                }
+            }
+        }
+
+        result.ok_or_else(|| {
+            crate::error::Error::other("neither IPROTO_DATA nor IPROTO_SQL_INFO found in result")
+        })
+    }
+}
+
+impl SqlInfo {
+    fn decode(stream: &mut Cursor<&[u8]>) -> crate::Result<Self> {
+        let mut info = Self::default();
+        let map_len = rmp::decode::read_map_len(stream)?;
+        for _ in 0..map_len {
+            let key = rmp::decode::read_pfix(stream)?;
+            match key {
+                ffi::sql::SQL_INFO_ROW_COUNT => {
+                    info.row_count = rmp::decode::read_int(stream)?;
+                }
+                ffi::sql::SQL_INFO_AUTOINCREMENT_IDS => {
+                    let n_ids = rmp::decode::read_array_len(stream)?;
+                    info.autoincrement_ids = Vec::with_capacity(n_ids as _);
+                    for _ in 0..n_ids {
+                        info.autoincrement_ids.push(rmp::decode::read_int(stream)?);
+                    }
+                }
+                _ => {
+                    crate::msgpack::skip_value(stream)?;
+                }
+            }
+        }
+        Ok(info)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// StatementCache
+////////////////////////////////////////////////////////////////////////////////
+
+/// Default maximum number of statements kept by [`execute_cached`].
+pub const DEFAULT_STATEMENT_CACHE_CAPACITY: usize = 128;
+
+/// Counters of the prepared statement cache used by [`execute_cached`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatementCacheStats {
+    /// Number of times a cached prepared statement was reused.
+    pub hits: u64,
+    /// Number of times a statement had to be prepared.
+    pub misses: u64,
+}
+
+struct StatementCache {
+    /// Prepared statements keyed by the session they belong to and the query
+    /// text, along with the tick of their last use.
+    statements: HashMap<(u64, String), (Statement, u64)>,
+    capacity: usize,
+    tick: u64,
+    schema_version: u64,
+    stats: StatementCacheStats,
+}
+
+impl StatementCache {
+    fn new() -> Self {
+        Self {
+            statements: HashMap::new(),
+            capacity: DEFAULT_STATEMENT_CACHE_CAPACITY,
+            tick: 0,
+            schema_version: 0,
+            stats: StatementCacheStats::default(),
+        }
+    }
+
+    /// Removes all of the statements if the schema version has changed since
+    /// they were prepared, because they may have become invalid.
+    ///
+    /// Returns the removed statements, which must be unprepared by the caller.
+    fn take_stale(&mut self) -> Vec<Statement> {
+        let schema_version = unsafe { ffi::tarantool::box_schema_version() };
+        if schema_version == self.schema_version {
+            return vec![];
+        }
+        self.schema_version = schema_version;
+        self.take_all()
+    }
+
+    /// Returns the id of the cached statement for `key` and marks it as the
+    /// most recently used.
+    fn get(&mut self, key: &(u64, String)) -> Option<u32> {
+        self.tick += 1;
+        let (stmt, last_used) = self.statements.get_mut(key)?;
+        *last_used = self.tick;
+        self.stats.hits += 1;
+        Some(stmt.id())
+    }
+
+    /// Adds a newly prepared statement to the cache and returns its id.
+    ///
+    /// Also returns the least recently used statements removed to stay within
+    /// the capacity, which must be unprepared by the caller.
+    fn insert(&mut self, key: (u64, String), stmt: Statement) -> (u32, Vec<Statement>) {
+        self.tick += 1;
+        self.stats.misses += 1;
+        // NOTE: if the statement got cached while it was being prepared, it's
+        // the same statement, because tarantool references a query only once
+        // per session, so it must not be unprepared.
+        let (stmt, last_used) = self.statements.entry(key).or_insert((stmt, 0));
+        *last_used = self.tick;
+        let stmt_id = stmt.id();
+        (stmt_id, self.evict_to(self.capacity))
+    }
+
+    /// Removes the least recently used statements until at most `len` are
+    /// left. Returns the removed statements, which must be unprepared by the
+    /// caller.
+    fn evict_to(&mut self, len: usize) -> Vec<Statement> {
+        let mut evicted = vec![];
+        while self.statements.len() > len {
+            let lru = self
+                .statements
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone())
+                .expect("isn't empty");
+            let (stmt, _) = self.statements.remove(&lru).expect("was just found");
+            evicted.push(stmt);
+        }
+        evicted
+    }
+
+    /// Removes all of the statements. Returns the removed statements, which
+    /// must be unprepared by the caller.
+    fn take_all(&mut self) -> Vec<Statement> {
+        std::mem::take(&mut self.statements)
+            .into_values()
+            .map(|(stmt, _)| stmt)
+            .collect()
+    }
+}
+
+/// Returns the id of the prepared statement for `query` in the current
+/// session, preparing it if needed, or `None` if the cache is disabled.
+///
+/// Errors from unpreparing the statements evicted from the cache are logged
+/// and ignored, because they aren't related to `query`.
+///
+/// NOTE: the cache is never borrowed while calling into tarantool.
+fn get_or_prepare_cached(query: &str) -> crate::Result<Option<u32>> {
+    let session_id = unsafe { ffi::tarantool::box_session_id() };
+    let key = (session_id, query.to_owned());
+
+    let lookup = STATEMENT_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.capacity == 0 {
+            return None;
         }
-        let param_ptr = param_data.as_ptr() as *const u8;
-        let execute_result = unsafe {
-            ffi::sql::sql_execute_prepared_ext(self.id(), param_ptr, vdbe_max_steps, buf.obuf())
-        };
+        let stale = cache.take_stale();
+        Some((stale, cache.get(&key)))
+    });
+    let (stale, cached) = match lookup {
+        Some(lookup) => lookup,
+        None => return Ok(None),
+    };
+    unprepare_evicted(stale);
+    if let Some(stmt_id) = cached {
+        return Ok(Some(stmt_id));
+    }
+
+    let stmt = prepare(query.to_owned())?;
+    let (stmt_id, evicted) = STATEMENT_CACHE.with(|cache| cache.borrow_mut().insert(key, stmt));
+    unprepare_evicted(evicted);
+    Ok(Some(stmt_id))
+}
+
+/// Unprepares the statements removed from the cache, logging the errors.
+fn unprepare_evicted(stmts: Vec<Statement>) {
+    for stmt in stmts {
+        if let Err(e) = unprepare_cached(stmt) {
+            crate::say_warn!("failed to unprepare a cached sql statement: {e}");
+        }
+    }
+}
+
+/// Unprepares the statements removed from the cache. Returns the first error
+/// if some of the statements failed to unprepare.
+fn unprepare_all(stmts: Vec<Statement>) -> crate::Result<()> {
+    let mut res = Ok(());
+    for stmt in stmts {
+        res = res.and(unprepare_cached(stmt));
+    }
+    res
+}
 
-        if execute_result < 0 {
-            return Err(TarantoolError::last().into());
+/// Unprepares a statement removed from the cache. The statements of closed
+/// sessions are unprepared by tarantool along with the session, so those are
+/// just forgotten.
+fn unprepare_cached(stmt: Statement) -> crate::Result<()> {
+    if unsafe { ffi::sql::sql_unprepare_ext(stmt.id(), stmt.session_id()) } < 0 {
+        let e = TarantoolError::last();
+        if e.error_code() != TarantoolErrorCode::NoSuchSession as u32 {
+            return Err(e.into());
         }
-        Ok(buf)
     }
+    Ok(())
+}
+
+thread_local! {
+    static STATEMENT_CACHE: RefCell<StatementCache> = RefCell::new(StatementCache::new());
+}
+
+/// Executes an SQL query reusing a prepared statement for the same query text
+/// if there is one in the cache and returns a wrapper around the raw msgpack
+/// bytes.
+///
+/// Prepared statements belong to the session which prepared them, so the
+/// cache is keyed by both the session id and the query text. At most
+/// [`DEFAULT_STATEMENT_CACHE_CAPACITY`] statements are cached by default (see
+/// [`set_statement_cache_capacity`]), the least recently used ones are
+/// unprepared when the limit is reached. All cached statements are
+/// unprepared once the schema version changes.
+///
+/// Statements removed from the cache are unprepared from the session they
+/// belong to, even if it's not the current one. Statements of closed sessions
+/// are just forgotten, because tarantool unprepares them along with the
+/// session. If unpreparing an evicted statement fails, the error is logged
+/// and the query is executed anyway.
+///
+/// If the capacity is set to `0`, this is equivalent to
+/// [`prepare_and_execute_raw`].
+pub fn execute_cached<IN>(
+    query: &str,
+    bind_params: &IN,
+    vdbe_max_steps: u64,
+) -> crate::Result<impl Read>
+where
+    IN: Serialize,
+{
+    let stmt_id = get_or_prepare_cached(query)?;
+
+    // NOTE: the cache must not be borrowed here, because execution may yield.
+    match stmt_id {
+        Some(stmt_id) => execute_prepared_raw(stmt_id, bind_params, vdbe_max_steps),
+        None => prepare_and_execute_obuf(query, bind_params, vdbe_max_steps),
+    }
+}
+
+/// Sets the maximum number of prepared statements kept by [`execute_cached`].
+/// Excess statements are unprepared immediately.
+///
+/// Returns an error if unpreparing a statement fails. The statement is removed
+/// from the cache anyway.
+pub fn set_statement_cache_capacity(capacity: usize) -> crate::Result<()> {
+    let evicted = STATEMENT_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        cache.capacity = capacity;
+        cache.evict_to(capacity)
+    });
+    unprepare_all(evicted)
+}
+
+/// Removes and unprepares all statements from the cache used by
+/// [`execute_cached`].
+///
+/// Returns an error if unpreparing a statement fails. The statement is removed
+/// from the cache anyway.
+pub fn clear_statement_cache() -> crate::Result<()> {
+    let stmts = STATEMENT_CACHE.with(|cache| cache.borrow_mut().take_all());
+    unprepare_all(stmts)
+}
+
+/// Returns the counters of the prepared statement cache used by
+/// [`execute_cached`].
+pub fn statement_cache_stats() -> StatementCacheStats {
+    STATEMENT_CACHE.with(|cache| cache.borrow().stats)
 }
@@ -27,8 +27,9 @@
 //! - [Lua reference: Functions for transaction management](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_txn_management/)
 //! - [C API reference: Module txn](https://www.tarantool.io/en/doc/latest/dev_guide/reference_capi/txn/)
 
-use crate::error::TarantoolError;
+use crate::error::{TarantoolError, TarantoolErrorCode};
 use crate::ffi::tarantool as ffi;
+use std::future::Future;
 
 /// Transaction-related error cases
 #[derive(Debug, thiserror::Error)]
@@ -44,6 +45,12 @@ pub enum TransactionError<E> {
 
     #[error("transaction rolled-back: {0}")]
     RolledBack(E),
+
+    /// The transaction couldn't be committed, because the fiber yielded
+    /// inside it. Memtx transactions are aborted on a yield unless the MVCC
+    /// engine is enabled.
+    #[error("transaction aborted, because the fiber yielded inside it (e.g. in fiber::sleep, a network request or an `.await`), memtx transactions can only yield if `memtx_use_mvcc_engine` is enabled: {0}")]
+    Yielded(TarantoolError),
 }
 
 /// Executes a transaction in the current fiber.
@@ -56,6 +63,15 @@ pub enum TransactionError<E> {
 /// Returns result of function `f` execution. Depending on the function result:
 /// - will **commit** - if function completes successfully
 /// - will **rollback** - if function completes with any error
+///
+/// If `f` panics, the transaction is rolled back before the panic is
+/// propagated.
+///
+/// If `f` yields (e.g. calls [`fiber::sleep`]) after a write, **memtx**
+/// aborts the transaction (unless the MVCC engine is enabled), in which case
+/// [`TransactionError::Yielded`] is returned.
+///
+/// [`fiber::sleep`]: crate::fiber::sleep
 pub fn transaction<T, E, F>(f: F) -> Result<T, TransactionError<E>>
 where
     F: FnOnce() -> Result<T, E>,
@@ -64,11 +80,74 @@ where
         return Err(TransactionError::AlreadyStarted);
     }
 
+    let guard = RollbackOnDrop;
     let result = f();
+    std::mem::forget(guard);
+    finish(result)
+}
+
+/// Executes a transaction in the current fiber, awaiting the future returned
+/// by `f` inside it. This is the async version of [`transaction`].
+///
+/// Returns result of the future. Depending on the result:
+/// - will **commit** - if the future completes successfully
+/// - will **rollback** - if the future completes with any error
+///
+/// If the returned future is dropped before completion (e.g. because of a
+/// timeout) the transaction is rolled back.
+///
+/// # Yields
+///
+/// A transaction is bound to the fiber which started it, so the future must
+/// be awaited by the same fiber (which is always the case with
+/// [`fiber::block_on`]). Note that any other futures polled by the same
+/// fiber while this one is pending (e.g. via `futures::join!`) will also be
+/// executed inside this transaction. An attempt to start another transaction
+/// on the same fiber returns [`TransactionError::AlreadyStarted`].
+///
+/// Also note, that **memtx** transactions are aborted if the fiber yields
+/// after the first write, unless the MVCC engine is enabled via
+/// `box.cfg { memtx_use_mvcc_engine = true }`. In this case the commit
+/// fails. Yielding before any writes is fine though.
+///
+/// [`fiber::block_on`]: crate::fiber::block_on
+pub async fn transaction_async<T, E, F, Fut>(f: F) -> Result<T, TransactionError<E>>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    if unsafe { ffi::box_txn_begin() } < 0 {
+        return Err(TransactionError::AlreadyStarted);
+    }
+
+    let guard = RollbackOnDrop;
+    let result = f().await;
+    std::mem::forget(guard);
+    finish(result)
+}
+
+/// Rolls back the active transaction if the function panics or the future is
+/// dropped before completion.
+struct RollbackOnDrop;
+
+impl Drop for RollbackOnDrop {
+    fn drop(&mut self) {
+        if unsafe { ffi::box_txn_rollback() } < 0 {
+            let error = TarantoolError::last();
+            crate::say_warn!("failed to rollback an unfinished transaction: {error}");
+        }
+    }
+}
+
+/// Commits or rolls back the active transaction depending on `result`.
+fn finish<T, E>(result: Result<T, E>) -> Result<T, TransactionError<E>> {
     match &result {
         Ok(_) => {
             if unsafe { ffi::box_txn_commit() } < 0 {
                 let error = TarantoolError::last();
+                if error.error_code() == TarantoolErrorCode::TransactionYield as u32 {
+                    return Err(TransactionError::Yielded(error));
+                }
                 return Err(TransactionError::FailedToCommit(error));
             }
         }
@@ -14,6 +14,7 @@ use std::convert::TryFrom;
 use std::ffi::{CStr, CString};
 use std::fmt::{self, Debug, Formatter};
 use std::io::Write;
+use std::marker::PhantomData;
 use std::ops::Range;
 use std::os::raw::{c_char, c_int};
 use std::ptr::{null, NonNull};
@@ -164,6 +165,67 @@ impl Tuple {
         }
     }
 
+    /// Returns an iterator over the tuple's fields, each of which is decoded
+    /// as a `T`.
+    ///
+    /// This is convenient for homogeneous tuples, e.g. a tuple of integers.
+    /// Iteration doesn't stop at the first field which fails to decode, so
+    /// the caller decides whether to skip the error or bail out.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use tarantool::tuple::Tuple;
+    ///
+    /// let tuple = Tuple::new(&[1, 2, 3]).unwrap();
+    /// let sum: i64 = tuple.iter_as::<i64>().unwrap().map(Result::unwrap).sum();
+    /// assert_eq!(sum, 6);
+    /// ```
+    #[inline]
+    pub fn iter_as<T>(&self) -> Result<TupleIterAs<T>>
+    where
+        T: DecodeOwned,
+    {
+        Ok(TupleIterAs {
+            inner: self.iter()?,
+            marker: PhantomData,
+        })
+    }
+
+    /// Returns an iterator over raw references to the tuple's fields.
+    ///
+    /// Unlike [`Tuple::iter_as`] this doesn't require the fields to be of the
+    /// same type: each [`FieldRef`] provides the field's msgpack data along
+    /// with its type information, and can be decoded separately.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use tarantool::tuple::{FieldType, Tuple};
+    ///
+    /// let tuple = Tuple::new(&(1, "two", 3.5)).unwrap();
+    /// for field in tuple.fields() {
+    ///     match field.field_type() {
+    ///         FieldType::Unsigned => println!("{}", field.decode::<u64>().unwrap()),
+    ///         FieldType::String => println!("{}", field.decode::<&str>().unwrap()),
+    ///         other => println!("field of type {}", other),
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn fields(&self) -> TupleFields<'_> {
+        let len = self.len();
+        let ptr = if len == 0 {
+            null()
+        } else {
+            // Safety: safe because `self.ptr` is valid
+            unsafe { ffi::box_tuple_field(self.ptr.as_ptr(), 0) }
+        };
+        TupleFields {
+            ptr: ptr as _,
+            remaining: len,
+            marker: PhantomData,
+        }
+    }
+
     /// Deserialize a tuple field specified by zero-based array index.
     ///
     /// - `fieldno` - zero-based index in MsgPack array.
@@ -248,6 +310,16 @@ impl Tuple {
         self.try_get(key).expect("Error during getting tuple field")
     }
 
+    /// Calculate the hash of this tuple's key described by `key_def`.
+    ///
+    /// This is equivalent to [`KeyDef::hash`], see its documentation for
+    /// details.
+    #[inline(always)]
+    #[track_caller]
+    pub fn hash(&self, key_def: &KeyDef) -> u32 {
+        key_def.hash(self)
+    }
+
     /// Decode tuple contents as `T`.
     ///
     /// **NOTE**: Because [`Tuple`] implements [`DecodeOwned`], you can do
@@ -291,6 +363,33 @@ impl Tuple {
         buf
     }
 
+    /// Convert tuple contents into a JSON array.
+    ///
+    /// This is useful when the tuple needs to be sent somewhere as JSON, but
+    /// there's no rust type describing its structure.
+    ///
+    /// Msgpack values are converted as follows:
+    /// - maps with non-string keys get their keys converted to JSON strings,
+    /// - binary strings are encoded with base64,
+    /// - NaN and infinite floats become `null`,
+    /// - decimals, uuids and datetimes become strings, same as their
+    ///   [`Display`](std::fmt::Display) implementation,
+    /// - other extension types become strings of form `ext(<type>, "<hex>")`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use tarantool::tuple::Tuple;
+    ///
+    /// let tuple = Tuple::new(&(1, "two", 3.5)).unwrap();
+    /// assert_eq!(tuple.to_json(), serde_json::json!([1, "two", 3.5]));
+    /// ```
+    pub fn to_json(&self) -> serde_json::Value {
+        let data = self.to_vec();
+        let value = rmpv::decode::read_value(&mut data.as_slice())
+            .expect("tuple data is always valid msgpack");
+        msgpack_value_to_json(value)
+    }
+
     /// Return pointer to underlying tuple.
     #[inline(always)]
     pub fn as_ptr(&self) -> *mut ffi::BoxTuple {
@@ -298,6 +397,48 @@ impl Tuple {
     }
 }
 
+fn msgpack_value_to_json(value: rmpv::Value) -> serde_json::Value {
+    use rmpv::Value as MP;
+    use serde_json::Value as JSON;
+
+    match value {
+        MP::Nil => JSON::Null,
+        MP::Boolean(v) => JSON::Bool(v),
+        MP::Integer(v) => {
+            if let Some(v) = v.as_u64() {
+                JSON::from(v)
+            } else if let Some(v) = v.as_i64() {
+                JSON::from(v)
+            } else {
+                unreachable!("msgpack integers fit into either u64 or i64")
+            }
+        }
+        MP::F32(v) => JSON::from(v),
+        MP::F64(v) => JSON::from(v),
+        MP::String(v) => JSON::String(String::from_utf8_lossy(v.as_bytes()).into()),
+        MP::Binary(v) => JSON::String(base64::encode(v)),
+        MP::Array(v) => JSON::Array(v.into_iter().map(msgpack_value_to_json).collect()),
+        MP::Map(v) => JSON::Object(
+            v.into_iter()
+                .map(|(k, v)| {
+                    let k = match msgpack_value_to_json(k) {
+                        JSON::String(k) => k,
+                        k => k.to_string(),
+                    };
+                    (k, msgpack_value_to_json(v))
+                })
+                .collect(),
+        ),
+        MP::Ext(ty, data) => match crate::msgpack::ext_to_string(ty, &data) {
+            Some(v) => JSON::String(v),
+            None => {
+                let hex: String = data.iter().map(|b| format!("{:02x}", b)).collect();
+                JSON::String(format!("ext({}, \"{}\")", ty, hex))
+            }
+        },
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // TupleIndex
 ////////////////////////////////////////////////////////////////////////////////
@@ -822,6 +963,152 @@ impl Drop for TupleIterator {
 
 impl TupleIterator {}
 
+////////////////////////////////////////////////////////////////////////////////
+// TupleIterAs
+////////////////////////////////////////////////////////////////////////////////
+
+/// An iterator over tuple fields decoded as `T`.
+///
+/// See [`Tuple::iter_as`].
+pub struct TupleIterAs<T> {
+    inner: TupleIterator,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Debug for TupleIterAs<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("TupleIterAs")
+            .field("position", &self.inner.position())
+            .finish()
+    }
+}
+
+impl<T> Iterator for TupleIterAs<T>
+where
+    T: DecodeOwned,
+{
+    type Item = Result<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Result<T>> {
+        self.inner.next::<T>().transpose()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TupleFields
+////////////////////////////////////////////////////////////////////////////////
+
+/// An iterator over references to tuple fields.
+///
+/// See [`Tuple::fields`].
+#[derive(Debug)]
+pub struct TupleFields<'a> {
+    ptr: *const u8,
+    remaining: u32,
+    marker: PhantomData<&'a Tuple>,
+}
+
+impl<'a> Iterator for TupleFields<'a> {
+    type Item = FieldRef<'a>;
+
+    fn next(&mut self) -> Option<FieldRef<'a>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        // Safety: safe because tuple fields are stored contiguously and
+        // `self.remaining` fields are left after `self.ptr`
+        let data = unsafe { field_slice_from_ptr(self.ptr) }
+            .ok()
+            .flatten()
+            .expect("tuple data is always valid msgpack");
+        self.remaining -= 1;
+        // Safety: safe because `data` is a subslice of the tuple's data
+        self.ptr = unsafe { self.ptr.add(data.len()) };
+        Some(FieldRef { data })
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining as _, Some(self.remaining as _))
+    }
+}
+
+impl ExactSizeIterator for TupleFields<'_> {}
+
+////////////////////////////////////////////////////////////////////////////////
+// FieldRef
+////////////////////////////////////////////////////////////////////////////////
+
+/// A reference to a single tuple field.
+///
+/// See [`Tuple::fields`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldRef<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> FieldRef<'a> {
+    /// Returns the msgpack encoded field data.
+    #[inline(always)]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the msgpack marker of the field's value.
+    #[inline(always)]
+    pub fn marker(&self) -> Marker {
+        Marker::from_u8(self.data[0])
+    }
+
+    /// Returns the most specific [`FieldType`] the field's value conforms to.
+    ///
+    /// Nonnegative integers are reported as [`FieldType::Unsigned`], negative
+    /// ones as [`FieldType::Integer`]. `nil` and unknown extension types are
+    /// reported as [`FieldType::Any`].
+    pub fn field_type(&self) -> FieldType {
+        use crate::ffi::{datetime::MP_DATETIME, decimal::MP_DECIMAL, uuid::MP_UUID};
+
+        match self.marker() {
+            Marker::FixPos(_) | Marker::U8 | Marker::U16 | Marker::U32 | Marker::U64 => {
+                FieldType::Unsigned
+            }
+            Marker::FixNeg(_) | Marker::I8 | Marker::I16 | Marker::I32 | Marker::I64 => {
+                FieldType::Integer
+            }
+            Marker::F32 | Marker::F64 => FieldType::Double,
+            Marker::True | Marker::False => FieldType::Boolean,
+            Marker::FixStr(_) | Marker::Str8 | Marker::Str16 | Marker::Str32 => FieldType::String,
+            Marker::Bin8 | Marker::Bin16 | Marker::Bin32 => FieldType::Varbinary,
+            Marker::FixArray(_) | Marker::Array16 | Marker::Array32 => FieldType::Array,
+            Marker::FixMap(_) | Marker::Map16 | Marker::Map32 => FieldType::Map,
+            Marker::FixExt1
+            | Marker::FixExt2
+            | Marker::FixExt4
+            | Marker::FixExt8
+            | Marker::FixExt16
+            | Marker::Ext8
+            | Marker::Ext16
+            | Marker::Ext32 => match rmp::decode::read_ext_meta(&mut &self.data[..]) {
+                Ok(meta) if meta.typeid == MP_DECIMAL => FieldType::Decimal,
+                Ok(meta) if meta.typeid == MP_UUID => FieldType::Uuid,
+                Ok(meta) if meta.typeid == MP_DATETIME => FieldType::Datetime,
+                _ => FieldType::Any,
+            },
+            Marker::Null | Marker::Reserved => FieldType::Any,
+        }
+    }
+
+    /// Decode the field's value as `T`.
+    #[inline(always)]
+    pub fn decode<T>(&self) -> Result<T>
+    where
+        T: Decode<'a>,
+    {
+        T::decode(self.data)
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // FieldType
 ////////////////////////////////////////////////////////////////////////////////
@@ -874,6 +1161,22 @@ impl From<index::FieldType> for FieldType {
     }
 }
 
+impl FieldType {
+    /// Returns `true` if values of a tuple field of this type can be decoded
+    /// into `T`.
+    ///
+    /// See [`space::FieldType::is_compatible_with`] for details.
+    ///
+    /// [`space::FieldType::is_compatible_with`]: crate::space::FieldType::is_compatible_with
+    #[inline(always)]
+    pub fn is_compatible_with<T>(self) -> bool
+    where
+        T: crate::space::FieldTypeCompat + ?Sized,
+    {
+        crate::space::FieldType::from(self).is_compatible_with::<T>()
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // KeyDef
 ////////////////////////////////////////////////////////////////////////////////
@@ -900,6 +1203,19 @@ impl From<index::FieldType> for FieldType {
 /// let meta = index.meta().unwrap();
 /// let key_def: KeyDef = meta.to_key_def();
 /// ```
+///
+/// Or using a [`KeyDefBuilder`], which is handy for custom sorting or merging
+/// of tuples outside of a space:
+/// ```no_run
+/// # use tarantool::tuple::{FieldType, KeyDef, Tuple};
+/// let key_def = KeyDef::builder()
+///     .part(1, FieldType::String)
+///     .part(0, FieldType::Unsigned)
+///     .build()
+///     .unwrap();
+/// let mut tuples: Vec<Tuple> = vec![];
+/// tuples.sort_by(|a, b| key_def.compare(a, b));
+/// ```
 #[derive(Debug)]
 pub struct KeyDef {
     inner: NonNull<ffi::BoxKeyDef>,
@@ -962,7 +1278,77 @@ impl<'a> KeyDefPart<'a> {
     }
 }
 
+/// A builder for [`KeyDef`]. See [`KeyDef::builder`].
+#[derive(Default, Debug)]
+pub struct KeyDefBuilder<'a> {
+    parts: Vec<KeyDefPart<'a>>,
+}
+
+impl<'a> KeyDefBuilder<'a> {
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a non nullable key part of type `field_type` located at the
+    /// zero-based field index `field_no` of the tuple.
+    #[inline(always)]
+    pub fn part(self, field_no: u32, field_type: FieldType) -> Self {
+        self.part_def(KeyDefPart {
+            field_no,
+            field_type,
+            ..Default::default()
+        })
+    }
+
+    /// Same as [`Self::part`], but the values of the key part are compared
+    /// using the `collation` (e.g. `"unicode_ci"`).
+    ///
+    /// # Panics
+    /// Will panic if `collation` contains a nul byte.
+    #[inline]
+    #[track_caller]
+    pub fn part_with_collation(
+        self,
+        field_no: u32,
+        field_type: FieldType,
+        collation: &str,
+    ) -> Self {
+        let collation = CString::new(collation)
+            .expect("it's your fault if you put '\0' in collation")
+            .into();
+        self.part_def(KeyDefPart {
+            field_no,
+            field_type,
+            collation: Some(collation),
+            ..Default::default()
+        })
+    }
+
+    /// Add an explicitly defined key part.
+    #[inline(always)]
+    pub fn part_def(mut self, part: KeyDefPart<'a>) -> Self {
+        self.parts.push(part);
+        self
+    }
+
+    /// Create the [`KeyDef`].
+    ///
+    /// Returns an error if any of the key parts is invalid, e.g. the collation
+    /// is not found.
+    #[inline(always)]
+    pub fn build(&self) -> Result<KeyDef> {
+        KeyDef::new(&self.parts)
+    }
+}
+
 impl KeyDef {
+    /// Return a key definition builder.
+    #[inline(always)]
+    pub fn builder<'a>() -> KeyDefBuilder<'a> {
+        KeyDefBuilder::new()
+    }
+
     /// Create key definition with key fields with passed typed on passed positions.
     /// May be used for tuple format creation and/or tuple comparison.
     ///
@@ -1095,11 +1481,19 @@ impl KeyDef {
     /// At the moment 32-bit murmur3 hash is used but it may
     /// change in future.
     ///
+    /// **NOTE**: calculating tuple hashes is not supported in all tarantool
+    /// versions. Use [`tarantool::ffi::has_tuple_hash`] to check whether it's
+    /// supported in your case. If `has_tuple_hash` returns `false` this
+    /// function will **panic**.
+    ///
     /// - `tuple` - tuple
     ///
     /// Returns:
     /// - 32-bit murmur3 hash value
-    #[cfg(feature = "picodata")]
+    ///
+    /// [`tarantool::ffi::has_tuple_hash`]: crate::ffi::has_tuple_hash
+    #[inline]
+    #[track_caller]
     pub fn hash(&self, tuple: &Tuple) -> u32 {
         unsafe { ffi::box_tuple_hash(tuple.ptr.as_ptr(), self.inner.as_ptr()) }
     }
@@ -1125,6 +1519,13 @@ unsafe fn field_value_from_ptr<'de, T>(field_ptr: *mut u8) -> Result<Option<T>>
 where
     T: Decode<'de>,
 {
+    match field_slice_from_ptr(field_ptr)? {
+        Some(value_slice) => Ok(Some(T::decode(value_slice)?)),
+        None => Ok(None),
+    }
+}
+
+unsafe fn field_slice_from_ptr<'a>(field_ptr: *const u8) -> Result<Option<&'a [u8]>> {
     if field_ptr.is_null() {
         return Ok(None);
     }
@@ -1141,8 +1542,7 @@ where
     crate::msgpack::skip_value(&mut cursor)?;
     let value_range = start..(cursor.position() as usize);
     let rough_slice = cursor.into_inner();
-    let value_slice = &rough_slice[value_range];
-    Ok(Some(T::decode(value_slice)?))
+    Ok(Some(&rough_slice[value_range]))
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -1928,6 +2328,46 @@ mod test {
     use crate::space::Space;
     use pretty_assertions::assert_eq;
 
+    #[crate::test(tarantool = "crate")]
+    fn to_json() {
+        let uuid = crate::uuid::Uuid::parse_str("6f2ba4c4-0a4c-4d79-86ae-43d4f84b70e1").unwrap();
+        let mut map = std::collections::BTreeMap::new();
+        map.insert(1, "one");
+        let tuple = Tuple::new(&(
+            1,
+            -2,
+            "three",
+            4.5,
+            true,
+            (),
+            uuid,
+            serde_bytes::Bytes::new(b"bin"),
+            [1, 2],
+            map,
+        ))
+        .unwrap();
+        assert_eq!(
+            tuple.to_json(),
+            serde_json::json!([
+                1,
+                -2,
+                "three",
+                4.5,
+                true,
+                null,
+                "6f2ba4c4-0a4c-4d79-86ae-43d4f84b70e1",
+                "Ymlu",
+                [1, 2],
+                { "1": "one" },
+            ])
+        );
+
+        let tuple: Tuple = crate::lua_state()
+            .eval("return box.tuple.new{require('decimal').new('3.14'), 0/0}")
+            .unwrap();
+        assert_eq!(tuple.to_json(), serde_json::json!(["3.14", null]));
+    }
+
     #[crate::test(tarantool = "crate")]
     fn tuple_buffer_from_lua() {
         let svp = unsafe { ffi::box_region_used() };
@@ -5,6 +5,12 @@ use serde::{Deserialize, Serialize};
 
 type Inner = ::uuid::Uuid;
 
+/// A UUID compatible with tarantool's builtin `uuid` type.
+///
+/// When pushed to Lua it becomes the native uuid cdata (the same as returned
+/// by `require('uuid').new()`), so it is stored as a uuid field when inserted
+/// into a space from Lua. Only uuid cdata can be read from Lua, strings
+/// aren't converted implicitly.
 #[derive(Debug, Copy, Clone, Eq, Hash, Ord, PartialEq, PartialOrd, Default)]
 pub struct Uuid {
     inner: Inner,
@@ -50,13 +50,15 @@ pub fn box_check_access_space() {
     let space = Space::create(space_name, &opts).unwrap();
     {
         let _su = session::su(user_id).unwrap();
-        let e = access_control::box_access_check_space(space.id(), PrivType::Read).unwrap_err();
+        let e =
+            access_control::box_access_check_space(space.space_id(), PrivType::Read).unwrap_err();
         assert_eq!(
             e.to_string(),
             format!("box error: AccessDenied: Read access to space '{space_name}' is denied for user '{user_name}'"),
         );
 
-        let e = access_control::box_access_check_space(space.id(), PrivType::Write).unwrap_err();
+        let e =
+            access_control::box_access_check_space(space.space_id(), PrivType::Write).unwrap_err();
         assert_eq!(
             e.to_string(),
             format!("box error: AccessDenied: Write access to space '{space_name}' is denied for user '{user_name}'"),
@@ -68,9 +70,10 @@ pub fn box_check_access_space() {
     {
         let _su = session::su(user_id).unwrap();
 
-        access_control::box_access_check_space(space.id(), PrivType::Read).unwrap();
+        access_control::box_access_check_space(space.space_id(), PrivType::Read).unwrap();
 
-        let e = access_control::box_access_check_space(space.id(), PrivType::Write).unwrap_err();
+        let e =
+            access_control::box_access_check_space(space.space_id(), PrivType::Write).unwrap_err();
         assert_eq!(
             e.to_string(),
             format!("box error: AccessDenied: Write access to space '{space_name}' is denied for user '{user_name}'"),
@@ -82,9 +85,9 @@ pub fn box_check_access_space() {
     {
         let _su = session::su(user_id).unwrap();
 
-        access_control::box_access_check_space(space.id(), PrivType::Read).unwrap();
+        access_control::box_access_check_space(space.space_id(), PrivType::Read).unwrap();
 
-        access_control::box_access_check_space(space.id(), PrivType::Write).unwrap();
+        access_control::box_access_check_space(space.space_id(), PrivType::Write).unwrap();
     }
 }
 
@@ -137,7 +140,7 @@ pub fn box_check_access_ddl_space() {
             let _su = session::su(user_id).unwrap();
             let e = access_control::box_access_check_ddl(
                 space_name,
-                space.id(),
+                space.space_id().0,
                 1,
                 SchemaObjectType::Space,
                 privilege,
@@ -156,7 +159,7 @@ pub fn box_check_access_ddl_space() {
             let _su = session::su(user_id).unwrap();
             access_control::box_access_check_ddl(
                 space_name,
-                space.id(),
+                space.space_id().0,
                 1,
                 SchemaObjectType::Space,
                 privilege,
@@ -174,7 +177,7 @@ pub fn box_check_access_ddl_space() {
             let _su = session::su(user_id).unwrap();
             let e = access_control::box_access_check_ddl(
                 space_name,
-                space.id(),
+                space.space_id().0,
                 1,
                 SchemaObjectType::Space,
                 privilege,
@@ -193,7 +196,7 @@ pub fn box_check_access_ddl_space() {
             let _su = session::su(user_id).unwrap();
             access_control::box_access_check_ddl(
                 space_name,
-                space.id(),
+                space.space_id().0,
                 1,
                 SchemaObjectType::Space,
                 privilege,
@@ -221,7 +224,7 @@ pub fn box_check_access_ddl_space() {
         // first check that we're allowed to grant
         access_control::box_access_check_ddl(
             &space_name_grant,
-            space.id(),
+            space.space_id().0,
             user_id,
             SchemaObjectType::Space,
             PrivType::Grant,
@@ -237,7 +240,7 @@ pub fn box_check_access_ddl_space() {
             // owner himself has permission on an object
             access_control::box_access_check_ddl(
                 &space_name_grant,
-                space_grant.id(),
+                space_grant.space_id().0,
                 user_id,
                 SchemaObjectType::Space,
                 privilege,
@@ -249,7 +252,7 @@ pub fn box_check_access_ddl_space() {
                 let _su = session::su(grantee_user_id).unwrap();
                 let e = access_control::box_access_check_ddl(
                     &space_name_grant,
-                    space_grant.id(),
+                    space_grant.space_id().0,
                     user_id,
                     SchemaObjectType::Space,
                     privilege,
@@ -276,7 +279,7 @@ pub fn box_check_access_ddl_space() {
                 let _su = session::su(grantee_user_id).unwrap();
                 access_control::box_access_check_ddl(
                     &space_name_grant,
-                    space_grant.id(),
+                    space_grant.space_id().0,
                     user_id,
                     SchemaObjectType::Space,
                     privilege,
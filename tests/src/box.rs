@@ -1,11 +1,15 @@
 use rand::Rng;
 use std::borrow::Cow;
+use std::cell::Cell;
 use std::collections::BTreeMap;
+use std::rc::Rc;
 
-use tarantool::index::{self, IndexOptions, IteratorType};
+use tarantool::error::TarantoolErrorCode;
+use tarantool::fiber;
+use tarantool::index::{self, IndexId, IndexOptions, IteratorType};
 use tarantool::sequence::Sequence;
 use tarantool::space::UpdateOps;
-use tarantool::space::{self, Field, Space, SystemSpace};
+use tarantool::space::{self, Field, Space, SpaceId, SystemSpace};
 use tarantool::space::{SpaceCreateOptions, SpaceEngineType, SpaceType};
 use tarantool::test::util::on_scope_exit;
 use tarantool::tuple::Tuple;
@@ -44,6 +48,65 @@ pub fn space_cache_invalidated() {
     assert!(Space::find_cached(SPACE_NAME).is_none());
 }
 
+pub fn space_get_by_id() {
+    let space = Space::find("test_s1").unwrap();
+    let id: SpaceId = space.space_id();
+    // The deprecated getter returns the same id as a plain integer
+    #[allow(deprecated)]
+    let raw_id = space.id();
+    assert_eq!(u32::from(id), raw_id);
+
+    let found = Space::find_by_id(id).unwrap().unwrap();
+    assert_eq!(found, space);
+    // Plain integer ids are accepted too
+    let found = Space::find_by_id(raw_id).unwrap().unwrap();
+    assert_eq!(found, space);
+    let not_found = Space::find_by_id(SpaceId(space::SPACE_ID_MAX)).unwrap();
+    assert!(not_found.is_none());
+
+    let index = space.index_by_id(IndexId(0)).unwrap().unwrap();
+    assert_eq!(index.index_id(), IndexId(0));
+    #[allow(deprecated)]
+    let raw_index_id = index.id();
+    assert_eq!(raw_index_id, 0);
+    let index_space_id: SpaceId = index.parent_space_id();
+    assert_eq!(index_space_id, id);
+    #[allow(deprecated)]
+    let raw_index_space_id = index.space_id();
+    assert_eq!(raw_index_space_id, raw_id);
+    let index = space.index_by_id(0).unwrap().unwrap();
+    assert_eq!(index.index_id(), IndexId(0));
+    assert!(space.index_by_id(IndexId(100)).unwrap().is_none());
+
+    // Ids are encoded as plain integers.
+    assert_eq!(
+        tarantool::msgpack::encode(&id),
+        tarantool::msgpack::encode(&id.0)
+    );
+    assert_eq!(format!("{id}"), id.0.to_string());
+}
+
+pub fn space_find_or_err() {
+    let space = Space::find_or_err("test_s1").unwrap();
+    assert_eq!(Some(space.clone()), Space::find("test_s1"));
+
+    let err = Space::find_or_err("no_such_space").unwrap_err();
+    assert_eq!(err.to_string(), "space `no_such_space` not found");
+    assert_eq!(err.missing_name(), Some("no_such_space"));
+    assert_eq!(err.variant_name(), "SpaceNotFound");
+
+    assert!(space.index_or_err("primary").is_ok());
+    let err = space.index_or_err("no_such_index").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "index `no_such_index` not found in space {}",
+            space.space_id()
+        )
+    );
+    assert_eq!(err.missing_name(), Some("no_such_index"));
+}
+
 pub fn space_get_system() {
     let space: Space = SystemSpace::Space.into();
     assert!(space.len().is_ok());
@@ -204,6 +267,46 @@ pub fn select() {
     );
 }
 
+pub fn space_pairs() {
+    let space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+    space.insert(&(1, "one")).unwrap();
+    space.insert(&(2, "two")).unwrap();
+    space.insert(&(3, "three")).unwrap();
+
+    let pairs: Vec<(u32, String)> = space.pairs().unwrap().collect::<Result<_, _>>().unwrap();
+    assert_eq!(
+        pairs,
+        [(1, "one".into()), (2, "two".into()), (3, "three".into())]
+    );
+
+    // Errors are reported per row
+    let mut iter = space.pairs::<u32, u32>().unwrap();
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
+
+pub fn select_between() {
+    let space = Space::find("test_s2").unwrap();
+    let pk = space.primary_key();
+
+    let ids = |start, start_inclusive, end, end_inclusive| -> Vec<u32> {
+        pk.select_between(&(start,), start_inclusive, &(end,), end_inclusive)
+            .unwrap()
+            .map(|t| t.field(0).unwrap().unwrap())
+            .collect()
+    };
+
+    assert_eq!(ids(5, true, 10, false), [5, 6, 7, 8, 9]);
+    assert_eq!(ids(5, false, 10, true), [6, 7, 8, 9, 10]);
+    assert_eq!(ids(5, true, 5, true), [5]);
+    assert_eq!(ids(5, true, 5, false), Vec::<u32>::new());
+    assert_eq!(ids(10, true, 5, true), Vec::<u32>::new());
+    assert_eq!(ids(19, true, 100, true), [19, 20]);
+}
+
 pub fn select_composite_key() {
     let space = Space::find("test_s2").unwrap();
     let idx = space.index("idx_2").unwrap();
@@ -225,11 +328,57 @@ pub fn select_composite_key() {
     );
 }
 
+pub fn select_prefix_key() {
+    #[derive(serde::Serialize)]
+    struct IdA {
+        id: u32,
+        a: i32,
+    }
+    impl tarantool::tuple::Encode for IdA {}
+
+    let space = Space::find("test_s2").unwrap();
+    let idx = space.index("idx_2").unwrap();
+
+    fn ids(iter: impl Iterator<Item = Tuple>) -> Vec<u32> {
+        iter.map(|t| t.decode::<S2Record>().unwrap().id).collect()
+    }
+
+    // Only the first of the 3 index parts
+    let result = ids(idx.select(IteratorType::Eq, &(3,)).unwrap());
+    assert_eq!(result, [3]);
+
+    let result = ids(idx.select(IteratorType::GE, &(18,)).unwrap());
+    assert_eq!(result, [18, 19, 20]);
+
+    // First 2 of 3 parts as a struct
+    let result = ids(idx.select(IteratorType::Eq, &IdA { id: 3, a: 3 }).unwrap());
+    assert_eq!(result, [3]);
+
+    let result = ids(idx.select(IteratorType::Eq, &IdA { id: 3, a: 4 }).unwrap());
+    assert!(result.is_empty());
+}
+
 pub fn len() {
     let space = Space::find("test_s2").unwrap();
     assert_eq!(space.len().unwrap(), 20_usize);
 }
 
+pub fn bsize() {
+    let space = Space::builder("space_bsize_test").create().unwrap();
+    let _guard = on_scope_exit(|| space.drop().unwrap());
+    space.index_builder("pk").create().unwrap();
+    assert_eq!(space.bsize().unwrap(), 0);
+
+    space.insert(&(1, "one")).unwrap();
+    let bsize = space.bsize().unwrap();
+    assert!(bsize > 0);
+    // Only the tuple data is counted, not the index
+    assert_eq!(bsize, space.get(&(1,)).unwrap().unwrap().bsize());
+
+    space.insert(&(2, "two")).unwrap();
+    assert!(space.bsize().unwrap() > bsize);
+}
+
 pub fn random() {
     let space = Space::find("test_s2").unwrap();
     let idx = space.primary_key();
@@ -288,6 +437,77 @@ pub fn count() {
     );
 }
 
+pub fn space_count() {
+    let space = Space::find("test_s2").unwrap();
+    for (iterator_type, key) in [
+        (IteratorType::GE, 5),
+        (IteratorType::LT, 15),
+        (IteratorType::Eq, 10),
+        (IteratorType::GT, 100),
+    ] {
+        let count = space.count(iterator_type, &(key,)).unwrap();
+        let expected = space.select(iterator_type, &(key,)).unwrap().count();
+        assert_eq!(count, expected, "{:?} {}", iterator_type, key);
+    }
+    assert_eq!(space.count(IteratorType::GE, &(5,)).unwrap(), 16);
+    assert_eq!(
+        space.count(IteratorType::All, &()).unwrap(),
+        space.len().unwrap()
+    );
+}
+
+pub fn space_triggers() {
+    let space = Space::builder("space_triggers_test").create().unwrap();
+    space.index_builder("pk").create().unwrap();
+    assert!(space.on_replace_triggers().unwrap().is_empty());
+
+    let lua = tarantool::lua_state();
+    lua.exec(
+        "space_triggers_test_fired = {}
+        local space = box.space.space_triggers_test
+        space:on_replace(function() table.insert(space_triggers_test_fired, 'a') end)
+        space:on_replace(function() table.insert(space_triggers_test_fired, 'b') end)
+        space:before_replace(function() table.insert(space_triggers_test_fired, 'before') end)",
+    )
+    .unwrap();
+    let fired = || -> Vec<String> { lua.eval("return space_triggers_test_fired").unwrap() };
+
+    let triggers = space.on_replace_triggers().unwrap();
+    assert_eq!(triggers.len(), 2);
+    assert!(triggers.iter().all(|t| t.space_id() == space.space_id()));
+    space.insert(&(1,)).unwrap();
+    let mut fired_once = fired();
+    fired_once.sort();
+    assert_eq!(fired_once, ["a", "b", "before"]);
+
+    // Remove a single trigger
+    let mut triggers = triggers.into_iter();
+    triggers.next().unwrap().remove().unwrap();
+    assert_eq!(space.on_replace_triggers().unwrap().len(), 1);
+    space.insert(&(2,)).unwrap();
+    let fired_twice = fired();
+    assert_eq!(fired_twice.len(), 5);
+
+    // Clear the rest
+    space.clear_on_replace_triggers().unwrap();
+    assert!(space.on_replace_triggers().unwrap().is_empty());
+    space.insert(&(3,)).unwrap();
+    // The before_replace trigger is not removed
+    let n_before_replace: usize = lua
+        .eval("return #box.space.space_triggers_test:before_replace()")
+        .unwrap();
+    assert_eq!(n_before_replace, 1);
+    let mut fired_thrice = fired_twice.clone();
+    fired_thrice.push("before".into());
+    assert_eq!(fired(), fired_thrice);
+
+    // Removing an already removed trigger is a no-op
+    triggers.next().unwrap().remove().unwrap();
+
+    lua.exec("space_triggers_test_fired = nil").unwrap();
+    space.drop().unwrap();
+}
+
 #[allow(deprecated)]
 pub fn extract_key() {
     let space = Space::find("test_s2").unwrap();
@@ -321,6 +541,45 @@ pub fn insert() {
     assert_eq!(output.unwrap().decode::<S1Record>().unwrap(), input);
 }
 
+pub fn insert_or_get() {
+    let space = Space::builder("insert_or_get_test_space").create().unwrap();
+    let _guard = on_scope_exit(|| space.drop().unwrap());
+    space.index_builder("pk").part(1).create().unwrap();
+    space
+        .index_builder("sk")
+        .part(2)
+        .unique(true)
+        .create()
+        .unwrap();
+
+    let res = space.insert_or_get(&(1, 10, "first")).unwrap();
+    let space::InsertOutcome::Inserted(t) = res else {
+        panic!("expected Inserted, got {:?}", res);
+    };
+    assert_eq!(
+        t.decode::<(u32, u32, String)>().unwrap(),
+        (1, 10, "first".into())
+    );
+
+    // Duplicate primary key: the existing tuple is returned
+    let res = space.insert_or_get(&(1, 20, "second")).unwrap();
+    let space::InsertOutcome::Conflict(t) = res else {
+        panic!("expected Conflict, got {:?}", res);
+    };
+    assert_eq!(
+        t.decode::<(u32, u32, String)>().unwrap(),
+        (1, 10, "first".into())
+    );
+    assert_eq!(space.len().unwrap(), 1);
+
+    // Duplicate secondary key: the error is returned
+    let err = space.insert_or_get(&(2, 10, "third")).unwrap_err();
+    let tarantool::error::Error::Tarantool(err) = err else {
+        panic!("expected box error, got {:?}", err);
+    };
+    assert_eq!(err.error_code(), TarantoolErrorCode::TupleFound as u32);
+}
+
 pub fn replace() {
     let space = Space::find("test_s1").unwrap();
     space.truncate().unwrap();
@@ -398,6 +657,191 @@ pub fn update() {
     assert!(res.is_none());
 }
 
+pub fn update_by_typed_key() {
+    #[derive(serde::Serialize)]
+    struct S1Key {
+        id: u32,
+    }
+    impl tarantool::tuple::Encode for S1Key {}
+
+    let space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+
+    space
+        .insert(&S1Record {
+            id: 362,
+            text: "Original".to_string(),
+        })
+        .unwrap();
+
+    // Key passed as a single-field tuple.
+    let updated = space.update(&(362,), [("=", 1, "Tuple")]).unwrap();
+    assert_eq!(updated.unwrap().decode::<S1Record>().unwrap().text, "Tuple");
+
+    // Key passed as a single-field struct, which is encoded as an array.
+    let updated = space
+        .update(&S1Key { id: 362 }, [("=", 1, "Struct")])
+        .unwrap();
+    assert_eq!(
+        updated.unwrap().decode::<S1Record>().unwrap().text,
+        "Struct"
+    );
+
+    let output = space.get(&S1Key { id: 362 }).unwrap().unwrap();
+    assert_eq!(output.decode::<S1Record>().unwrap().text, "Struct");
+}
+
+pub fn replace_returning_old() {
+    let space = Space::builder("replace_returning_old_test")
+        .create()
+        .unwrap();
+    let _guard = on_scope_exit(|| space.drop().unwrap());
+    space.index_builder("pk").part(1).create().unwrap();
+
+    // New key: nothing is replaced
+    let old = space.replace_returning_old(&(1, "first")).unwrap();
+    assert!(old.is_none());
+
+    // Existing key: the previous tuple is returned
+    let old = space
+        .replace_returning_old(&(1, "second"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(old.decode::<(u32, String)>().unwrap(), (1, "first".into()));
+
+    let t = space.get(&(1,)).unwrap().unwrap();
+    assert_eq!(t.decode::<(u32, String)>().unwrap(), (1, "second".into()));
+    assert_eq!(space.len().unwrap(), 1);
+
+    // Errors are returned as is, not wrapped into a transaction error
+    let e = space.replace_returning_old(&("one", "third")).unwrap_err();
+    assert!(
+        matches!(&e, tarantool::error::Error::Tarantool(e) if e.error_code() == TarantoolErrorCode::FieldType as u32),
+        "{}",
+        e
+    );
+    assert!(!tarantool::transaction::is_in_transaction());
+    assert_eq!(space.len().unwrap(), 1);
+}
+
+pub fn space_increment() {
+    let space = Space::builder("space_increment_test")
+        .format([
+            ("name", space::FieldType::String),
+            ("count", space::FieldType::Integer),
+        ])
+        .create()
+        .unwrap();
+    let _guard = on_scope_exit(|| space.drop().unwrap());
+    space.index_builder("pk").part("name").create().unwrap();
+
+    // The counter is created by the first increment
+    assert_eq!(space.increment(&("hits",), 1, 1).unwrap(), 1);
+    assert_eq!(space.increment(&("hits",), 1, 1).unwrap(), 2);
+    assert_eq!(space.increment(&("hits",), 1, 5).unwrap(), 7);
+    assert_eq!(space.increment(&("hits",), 1, -2).unwrap(), 5);
+
+    let (name, count): (String, i64) = space.get(&("hits",)).unwrap().unwrap().decode().unwrap();
+    assert_eq!(name, "hits");
+    assert_eq!(count, 5);
+
+    // Other counters are independent
+    assert_eq!(space.increment(&("misses",), 1, 3).unwrap(), 3);
+    assert_eq!(space.increment(&("hits",), 1, 1).unwrap(), 6);
+
+    // A new counter can only be created if the field follows the key
+    let e = space.increment(&("other",), 2, 1).unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        "box error: IllegalParams: cannot insert a new counter: field 2 doesn't follow the 1 key field(s)"
+    );
+    assert!(space.get(&("other",)).unwrap().is_none());
+}
+
+pub fn space_get_as() {
+    let space = Space::builder("space_get_as_test")
+        .format([
+            ("id", space::FieldType::Unsigned),
+            ("name", space::FieldType::String),
+        ])
+        .create()
+        .unwrap();
+    let _guard = on_scope_exit(|| space.drop().unwrap());
+    space.index_builder("pk").create().unwrap();
+    space.insert(&(1, "one")).unwrap();
+
+    #[derive(serde::Deserialize, PartialEq, Eq, Debug)]
+    struct Row {
+        id: u32,
+        name: String,
+    }
+
+    let row: Option<Row> = space.get_as(&(1,)).unwrap();
+    assert_eq!(
+        row,
+        Some(Row {
+            id: 1,
+            name: "one".into()
+        })
+    );
+
+    // Not found is not an error
+    let row: Option<Row> = space.get_as(&(2,)).unwrap();
+    assert_eq!(row, None);
+
+    // Decode error is an error
+    let res = space.get_as::<_, (u32, u32)>(&(1,));
+    assert!(res.is_err());
+}
+
+pub fn space_select_yielding() {
+    let space = Space::builder("space_select_yielding_test")
+        .format([("id", space::FieldType::Unsigned)])
+        .create()
+        .unwrap();
+    let _guard = on_scope_exit(|| space.drop().unwrap());
+    space.index_builder("pk").create().unwrap();
+    for i in 0..100 {
+        space.insert(&(i,)).unwrap();
+    }
+
+    let counter = Rc::new(Cell::new(0));
+    let done = Rc::new(Cell::new(false));
+    let jh = fiber::start({
+        let (counter, done) = (counter.clone(), done.clone());
+        move || {
+            while !done.get() {
+                counter.set(counter.get() + 1);
+                fiber::reschedule();
+            }
+        }
+    });
+
+    // The other fiber runs every 10 tuples
+    let mut observed = vec![];
+    let iter = space.select_yielding(IteratorType::All, &(), 10).unwrap();
+    for (i, tuple) in iter.enumerate() {
+        assert_eq!(tuple.get::<_, usize>(0), Some(i));
+        observed.push(counter.get());
+    }
+    assert_eq!(observed.len(), 100);
+    assert_eq!(observed[0], observed[9]);
+    assert!(observed[10] > observed[9]);
+    assert!(observed[99] >= observed[0] + 9);
+
+    // Not yielding at all
+    let before = counter.get();
+    let count = space
+        .select_yielding(IteratorType::All, &(), 0)
+        .unwrap()
+        .count();
+    assert_eq!(count, 100);
+    assert_eq!(counter.get(), before);
+
+    done.set(true);
+    jh.join();
+}
+
 pub fn update_macro() {
     let space = Space::find("test_s2").unwrap();
 
@@ -783,11 +1227,11 @@ pub fn space_create_opt_if_not_exists() {
 pub fn space_create_id_increment() {
     let opts = SpaceCreateOptions::default();
     let _result = Space::create("new_space_3", &opts);
-    let mut prev_id = Space::find("new_space_3").unwrap().id();
+    let mut prev_id = Space::find("new_space_3").unwrap().space_id().0;
     for i in 302..306 {
         let name = format!("new_space_{}", i);
         let result = Space::create(name.as_str(), &opts);
-        let curr_id = result.unwrap().id();
+        let curr_id = result.unwrap().space_id().0;
         assert_eq!(prev_id + 1, curr_id);
         prev_id = curr_id;
     }
@@ -823,7 +1267,7 @@ pub fn space_create_opt_id() {
     };
 
     let result_1 = Space::create("new_space_6", &opts);
-    let id = result_1.unwrap().id();
+    let id = result_1.unwrap().space_id().0;
     assert_eq!(id, opts.id.unwrap());
 
     drop_space("new_space_6");
@@ -889,6 +1333,66 @@ pub fn space_create_is_sync() {
     drop_space("new_space_8");
 }
 
+// `is_local`, `temporary` & `is_sync` are deprecated, but still supported
+#[allow(deprecated)]
+pub fn space_create_local_temporary() {
+    let space = Space::builder("new_space_local_temporary")
+        .space_type(SpaceType::DataLocalTemporary)
+        .create()
+        .unwrap();
+
+    let meta = space.meta().unwrap();
+    assert_eq!(meta.flags.get("group_id"), Some(&Value::Num(1)));
+    assert_eq!(meta.flags.get("temporary"), Some(&Value::Bool(true)));
+
+    let (_, opts) = Space::builder("x")
+        .is_local(true)
+        .temporary(true)
+        .into_parts()
+        .unwrap();
+    assert_eq!(opts.space_type, SpaceType::DataLocalTemporary);
+
+    let (_, opts) = Space::builder("x")
+        .temporary(true)
+        .is_local(true)
+        .into_parts()
+        .unwrap();
+    assert_eq!(opts.space_type, SpaceType::DataLocalTemporary);
+
+    let err = Space::builder("x")
+        .is_sync(true)
+        .temporary(true)
+        .into_parts()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "box error: IllegalParams: space option `temporary` is incompatible with `is_sync`"
+    );
+
+    space.drop().unwrap();
+
+    let err = Space::builder("new_space_local_sync")
+        .is_local(true)
+        .is_sync(true)
+        .create()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "box error: IllegalParams: space option `is_sync` is incompatible with `is_local`"
+    );
+    assert!(Space::find("new_space_local_sync").is_none());
+
+    let err = Space::builder("new_space_temporary_local")
+        .space_type(SpaceType::Temporary)
+        .is_local(true)
+        .create()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "box error: IllegalParams: space option `is_local` is incompatible with `type = 'temporary'`"
+    );
+}
+
 pub fn space_meta() {
     fn assert_field(
         field: &BTreeMap<Cow<'_, str>, Value>,
@@ -1055,19 +1559,19 @@ pub fn fully_temporary_space() {
         .space_type(SpaceType::Temporary)
         .create()
         .unwrap();
-    assert!(dbg!(space_1.id()) > 0x3fff_ffff);
+    assert!(dbg!(space_1.space_id().0) > 0x3fff_ffff);
 
     let space_2 = Space::builder("t2")
         .space_type(SpaceType::Temporary)
         .create()
         .unwrap();
-    assert_eq!(space_2.id(), space_1.id() + 1);
+    assert_eq!(space_2.space_id().0, space_1.space_id().0 + 1);
 
     let space_3 = Space::builder("t3")
         .space_type(SpaceType::Temporary)
         .create()
         .unwrap();
-    assert_eq!(space_3.id(), space_1.id() + 2);
+    assert_eq!(space_3.space_id().0, space_1.space_id().0 + 2);
 
     // Id of space "t2" is now free but we don't start filling the holes until ids overflow
     space_2.drop().unwrap();
@@ -1075,7 +1579,7 @@ pub fn fully_temporary_space() {
         .space_type(SpaceType::Temporary)
         .create()
         .unwrap();
-    assert_eq!(space_4.id(), space_1.id() + 3);
+    assert_eq!(space_4.space_id().0, space_1.space_id().0 + 3);
 
     // Take the maximum space id to force ids to start filling the holes
     let space_5 = Space::builder("t5")
@@ -1083,14 +1587,14 @@ pub fn fully_temporary_space() {
         .space_type(SpaceType::Temporary)
         .create()
         .unwrap();
-    assert_eq!(space_5.id(), tarantool::space::SPACE_ID_MAX);
+    assert_eq!(space_5.space_id().0, tarantool::space::SPACE_ID_MAX);
 
     // Now the id of space "t2" is taken
     let space_6 = Space::builder("t6")
         .space_type(SpaceType::Temporary)
         .create()
         .unwrap();
-    assert_eq!(space_6.id(), space_1.id() + 1);
+    assert_eq!(space_6.space_id().0, space_1.space_id().0 + 1);
 
     space_1.drop().unwrap();
     space_3.drop().unwrap();
@@ -1,11 +1,11 @@
 use std::convert::TryInto;
-use std::io::{Read, Write};
+use std::io::{ErrorKind, Read, Write};
 use std::net::{TcpListener, TcpStream};
-use std::os::fd::OwnedFd;
+use std::os::fd::{AsRawFd, OwnedFd};
 use std::os::unix::net::UnixStream;
 use std::time::Duration;
 
-use tarantool::coio::{self, channel, CoIOListener, CoIOStream};
+use tarantool::coio::{self, channel, CoIOListener, CoIOStream, CoIOUdpSocket};
 use tarantool::fiber;
 
 pub fn coio_accept() {
@@ -41,6 +41,68 @@ pub fn coio_read_write() {
     writer_fiber.join();
 }
 
+pub fn coio_read_write_with_deadline() {
+    let (reader_soc, writer_soc) = UnixStream::pair().unwrap();
+    reader_soc.set_nonblocking(true).unwrap();
+    writer_soc.set_nonblocking(true).unwrap();
+    let mut buf = [0; 4];
+
+    // Nobody writes, so the read times out
+    let t0 = fiber::clock();
+    let deadline = t0.saturating_add(Duration::from_millis(10));
+    let err = coio::read_with_deadline(reader_soc.as_raw_fd(), &mut buf, deadline).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+    assert!(fiber::clock() >= deadline);
+
+    // Deadline in the past is checked without yielding
+    let err = coio::read_with_deadline(reader_soc.as_raw_fd(), &mut buf, t0).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+
+    let deadline = fiber::clock().saturating_add(Duration::from_secs(10));
+    let n = coio::write_with_deadline(writer_soc.as_raw_fd(), &[1, 2, 3, 4], deadline).unwrap();
+    assert_eq!(n, 4);
+    let n = coio::read_with_deadline(reader_soc.as_raw_fd(), &mut buf, deadline).unwrap();
+    assert_eq!(n, 4);
+    assert_eq!(buf, [1, 2, 3, 4]);
+}
+
+pub fn coio_udp() {
+    let receiver = CoIOUdpSocket::bind("127.0.0.1:0").unwrap();
+    let receiver_addr = receiver.local_addr().unwrap();
+    let sender = CoIOUdpSocket::bind("127.0.0.1:0").unwrap();
+    let sender_addr = sender.local_addr().unwrap();
+
+    // Nothing was sent yet, so the receive times out
+    let mut buf = [0; 16];
+    let err = receiver
+        .recv_from_with_timeout(&mut buf, Some(Duration::from_millis(10)))
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+
+    let receiver_fiber = fiber::start(move || {
+        let mut buf = [0; 16];
+        let (n, from) = receiver.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"hello");
+        assert_eq!(from, sender_addr);
+        receiver.send_to(b"world", from).unwrap();
+    });
+
+    let sender_fiber = fiber::start(move || {
+        fiber::sleep(Duration::from_millis(10));
+        let n = sender.send_to(b"hello", receiver_addr).unwrap();
+        assert_eq!(n, 5);
+        let mut buf = [0; 16];
+        let (n, from) = sender
+            .recv_from_with_timeout(&mut buf, Some(Duration::from_secs(3)))
+            .unwrap();
+        assert_eq!(&buf[..n], b"world");
+        assert_eq!(from, receiver_addr);
+    });
+
+    receiver_fiber.join();
+    sender_fiber.join();
+}
+
 pub fn coio_call() {
     let res = coio::coio_call(
         &mut |x| {
@@ -85,3 +147,72 @@ pub fn channel_tx_closed() {
     });
     fiber.join();
 }
+
+pub fn pipe() {
+    let (mut reader, mut writer) = coio::pipe_with_capacity(4);
+    let data: Vec<u8> = (0..100).collect();
+
+    let writer_fiber = fiber::start({
+        let data = data.clone();
+        move || {
+            // The buffer is smaller than the data, so the fiber blocks until
+            // the reader consumes some of it
+            writer.write_all(&data).unwrap();
+        }
+    });
+
+    let mut received = vec![];
+    reader.read_to_end(&mut received).unwrap();
+    assert_eq!(received, data);
+    writer_fiber.join();
+}
+
+pub fn pipe_async() {
+    use tarantool::fiber::r#async::futures::{AsyncReadExt, AsyncWriteExt};
+
+    let (mut reader, mut writer) = coio::pipe_with_capacity(4);
+    let writer_fiber = fiber::start_async(async move {
+        AsyncWriteExt::write_all(&mut writer, b"hello, pipe")
+            .await
+            .unwrap();
+        writer.close().await.unwrap();
+    });
+
+    let received = fiber::block_on(async move {
+        let mut buf = vec![];
+        AsyncReadExt::read_to_end(&mut reader, &mut buf)
+            .await
+            .unwrap();
+        buf
+    });
+    assert_eq!(received, b"hello, pipe");
+    writer_fiber.join();
+}
+
+pub fn pipe_fiber_cancelled() {
+    // The writer is kept alive, so the reader blocks forever
+    let (mut reader, _writer) = coio::pipe();
+    let reader_fiber = fiber::start(move || {
+        let mut buf = vec![];
+        reader.read_to_end(&mut buf)
+    });
+    reader_fiber.cancel();
+    let e = reader_fiber.join().unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::Other);
+    assert_eq!(e.to_string(), "fiber is cancelled");
+
+    // The reader is kept alive, so the writer blocks once the buffer is full
+    let (_reader, mut writer) = coio::pipe_with_capacity(4);
+    let writer_fiber = fiber::start(move || writer.write_all(b"more than 4 bytes"));
+    writer_fiber.cancel();
+    let e = writer_fiber.join().unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::Other);
+    assert_eq!(e.to_string(), "fiber is cancelled");
+}
+
+pub fn pipe_reader_closed() {
+    let (reader, mut writer) = coio::pipe();
+    drop(reader);
+    let e = writer.write(b"data").unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::BrokenPipe);
+}
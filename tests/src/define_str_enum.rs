@@ -24,7 +24,8 @@ pub fn basic() {
 
     assert_eq!(Color::MIN, Color::Black);
     assert_eq!(Color::MAX, Color::White);
-    assert_eq!(Color::VARIANTS.len(), 2);
+    assert_eq!(Color::VARIANTS, [Color::Black, Color::White]);
+    assert_eq!(Color::values(), ["#000000", "#FFFFFF"]);
     assert_eq!(Color::default(), Color::Black);
     assert_eq!(Color::Black.as_ref(), "#000000");
     assert_eq!(Color::White.as_str(), "#FFFFFF");
@@ -149,3 +150,53 @@ pub fn deserialize_from_owned() {
         rmpv::ext::from_value::<Season>(value).expect("fails to deserialize from owned string");
     assert_eq!(season, Season::Summer);
 }
+
+pub fn numeric_codes() {
+    use std::convert::TryFrom;
+
+    define_str_enum! {
+        enum FieldKind {
+            Unsigned = "unsigned" = 1,
+            String = "string" = 2,
+            Double = "double" = 5,
+        }
+    }
+    assert_eq!(FieldKind::HAS_NUMERIC_CODES, true);
+
+    // integer conversions
+    assert_eq!(u32::from(FieldKind::Double), 5);
+    assert_eq!(i64::from(FieldKind::String), 2);
+    assert_eq!(FieldKind::try_from(1_u32), Ok(FieldKind::Unsigned));
+    assert_eq!(FieldKind::try_from(5_u32), Ok(FieldKind::Double));
+    assert_eq!(FieldKind::try_from(3_u32), Err(3));
+
+    // decode from integer
+    let double_mp = msgpack::encode(&5);
+    assert_eq!(
+        msgpack::decode::<FieldKind>(&double_mp).unwrap(),
+        FieldKind::Double
+    );
+    let unknown_mp = msgpack::encode(&3);
+    assert_eq!(
+        msgpack::decode::<FieldKind>(&unknown_mp)
+            .unwrap_err()
+            .to_string(),
+        "failed decoding tarantool_module_test_runner::define_str_enum::numeric_codes::FieldKind: unknown enum variant code `3`"
+    );
+
+    // decode from string still works
+    let string_mp = msgpack::encode(&"string");
+    assert_eq!(
+        msgpack::decode::<FieldKind>(&string_mp).unwrap(),
+        FieldKind::String
+    );
+
+    // enums without numeric codes are only decoded from strings
+    define_str_enum! {
+        enum NoCodes {
+            A = "a",
+        }
+    }
+    assert_eq!(NoCodes::HAS_NUMERIC_CODES, false);
+    assert!(msgpack::decode::<NoCodes>(&msgpack::encode(&0)).is_err());
+}
@@ -30,6 +30,33 @@ pub fn send_full() {
     );
 }
 
+pub fn is_full() {
+    let ch = fiber::Channel::new(2);
+    assert_eq!(ch.size(), 2);
+    assert!(ch.is_empty());
+    assert!(!ch.is_full());
+
+    ch.send(1).unwrap();
+    assert!(!ch.is_empty());
+    assert!(!ch.is_full());
+
+    ch.send(2).unwrap();
+    assert!(ch.is_full());
+    assert_eq!(ch.count(), 2);
+    assert_eq!(ch.try_send(3).unwrap_err(), fiber::TrySendError::Full(3));
+
+    assert_eq!(ch.recv(), Some(1));
+    assert!(!ch.is_full());
+    assert_eq!(ch.count(), 1);
+
+    assert_eq!(ch.recv(), Some(2));
+    assert!(ch.is_empty());
+
+    let ch = fiber::Channel::<()>::new(0);
+    assert!(ch.is_empty());
+    assert!(ch.is_full());
+}
+
 pub fn recv_empty() {
     let ch = fiber::Channel::<()>::new(0);
 
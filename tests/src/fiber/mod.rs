@@ -2,6 +2,7 @@
 #![allow(deprecated)]
 use std::{
     cell::{Cell, RefCell},
+    panic::AssertUnwindSafe,
     rc::Rc,
     time::Duration,
 };
@@ -456,6 +457,32 @@ pub fn lifetime() {
     // }.join();
 }
 
+pub fn catch_unwind() {
+    let reached = Rc::new(Cell::new(false));
+    let jh = fiber::Builder::new()
+        .func(AssertUnwindSafe({
+            let reached = reached.clone();
+            move || {
+                reached.set(true);
+                panic!("this panic must be caught");
+            }
+        }))
+        .catch_unwind()
+        .start()
+        .unwrap();
+    jh.join();
+    assert!(reached.get());
+
+    // Non-string payloads are handled too. The fiber runs to completion
+    // before `start_non_joinable` returns, so getting here means the process
+    // didn't abort.
+    fiber::Builder::new()
+        .func(|| std::panic::panic_any(42_i32))
+        .catch_unwind()
+        .start_non_joinable()
+        .unwrap();
+}
+
 pub fn r#yield() {
     //if fiber doesnt yield than test will be running forever
     let mut fiber = Fiber::new("test_fiber", &mut |_| {
@@ -32,6 +32,7 @@ mod enums;
 mod fiber;
 mod latch;
 mod net_box;
+mod network_client;
 mod proc;
 mod session;
 mod sql;
@@ -187,6 +188,7 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 define_str_enum::basic,
                 define_str_enum::coerce_from_str,
                 define_str_enum::deserialize_from_owned,
+                define_str_enum::numeric_codes,
                 tlua::lua_functions::basic,
                 tlua::lua_functions::two_functions_at_the_same_time,
                 tlua::lua_functions::args,
@@ -202,12 +204,14 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 tlua::lua_functions::either_or,
                 tlua::lua_functions::multiple_return_values,
                 tlua::lua_functions::multiple_return_values_fail,
+                tlua::lua_functions::many_values,
                 tlua::lua_functions::execute_from_reader_errors_if_cant_read,
                 tlua::lua_functions::from_function_call_error,
                 tlua::lua_functions::non_string_error,
                 tlua::lua_functions::push_function,
                 tlua::lua_functions::push_iter_no_err,
                 tlua::lua_functions::eval_with,
+                tlua::lua_functions::into_iter,
                 tlua::lua_tables::iterable,
                 tlua::lua_tables::iterable_multipletimes,
                 tlua::lua_tables::get_set,
@@ -215,7 +219,10 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 tlua::lua_tables::table_over_table,
                 tlua::lua_tables::get_or_create_metatable,
                 tlua::lua_tables::complex_anonymous_table_metatable,
+                tlua::lua_tables::set_metatable,
                 tlua::lua_tables::empty_array,
+                tlua::lua_tables::len,
+                tlua::lua_tables::insert_remove,
                 tlua::lua_tables::by_value,
                 tlua::lua_tables::registry,
                 tlua::lua_tables::registry_metatable,
@@ -225,6 +232,7 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 tlua::functions_write::simple_function,
                 tlua::functions_write::one_argument,
                 tlua::functions_write::two_arguments,
+                tlua::functions_write::multiple_return_values,
                 tlua::functions_write::wrong_arguments_types,
                 tlua::functions_write::return_result,
                 tlua::functions_write::closures,
@@ -236,8 +244,12 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 tlua::functions_write::closures_must_be_static,
                 tlua::functions_write::pcall,
                 tlua::functions_write::error,
+                tlua::functions_write::error_with_code,
                 tlua::functions_write::optional_params,
                 tlua::functions_write::lua_function_as_argument,
+                tlua::functions_write::variadic,
+                tlua::functions_write::push_generator,
+                tlua::functions_write::catch_panic,
                 tlua::any::read_numbers,
                 tlua::any::read_hashable_numbers,
                 tlua::any::read_strings,
@@ -259,8 +271,13 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 tlua::misc::json,
                 tlua::misc::dump_stack,
                 tlua::misc::dump_stack_raw,
+                tlua::misc::dump_stack_entries,
                 tlua::misc::error_during_push_tuple,
+                tlua::misc::eval_protected,
+                tlua::misc::eval_in_env,
+                tlua::misc::eval_capturing_print,
                 tlua::misc::hash,
+                tlua::misc::register_module,
                 tlua::object::callable_builtin,
                 tlua::object::callable_ffi,
                 tlua::object::callable_meta,
@@ -293,6 +310,7 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 tlua::rust_tables::derive_enum_lua_read,
                 tlua::rust_tables::derive_generic_struct_push,
                 tlua::rust_tables::derive_generic_struct_lua_read,
+                tlua::rust_tables::derive_struct_lua_read_option,
                 tlua::rust_tables::derive_generic_enum_push,
                 tlua::rust_tables::derive_generic_enum_lua_read,
                 tlua::rust_tables::derive_generic_push_into,
@@ -365,6 +383,7 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 fiber::lifetime,
                 fiber::r#yield,
                 fiber::yield_canceled,
+                fiber::catch_unwind,
             ]);
 
             tests.append(&mut tests![
@@ -378,6 +397,7 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
 
                 fiber::channel::send_self,
                 fiber::channel::send_full,
+                fiber::channel::is_full,
                 fiber::channel::recv_empty,
                 fiber::channel::drop_sender,
                 fiber::channel::dont_drop_msg,
@@ -405,14 +425,22 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 r#box::space_get_by_name,
                 r#box::space_get_by_name_cached,
                 r#box::space_cache_invalidated,
+                r#box::space_get_by_id,
+                r#box::space_find_or_err,
                 r#box::space_get_system,
                 r#box::index_get_by_name,
                 r#box::index_get_by_name_cached,
                 r#box::index_cache_invalidated,
                 r#box::insert,
+                r#box::insert_or_get,
                 r#box::replace,
                 r#box::delete,
                 r#box::update,
+                r#box::update_by_typed_key,
+                r#box::replace_returning_old,
+                r#box::space_increment,
+                r#box::space_select_yielding,
+                r#box::space_get_as,
                 r#box::update_macro,
                 r#box::update_index_macro,
                 r#box::update_ops,
@@ -421,11 +449,17 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 r#box::truncate,
                 r#box::get,
                 r#box::select,
+                r#box::space_pairs,
+                r#box::select_between,
                 r#box::select_composite_key,
+                r#box::select_prefix_key,
                 r#box::len,
+                r#box::bsize,
                 r#box::random,
                 r#box::min_max,
                 r#box::count,
+                r#box::space_count,
+                r#box::space_triggers,
                 r#box::extract_key,
                 r#box::sequence_get_by_name,
                 r#box::sequence_iterate,
@@ -436,6 +470,7 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 r#box::space_create_opt_user,
                 r#box::space_create_opt_id,
                 r#box::space_create_is_sync,
+                r#box::space_create_local_temporary,
                 r#box::space_meta,
                 r#box::space_drop,
                 r#box::index_create_drop,
@@ -447,6 +482,8 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 tuple::tuple_decode,
                 tuple::tuple_clone,
                 tuple::tuple_iterator,
+                tuple::tuple_iter_as,
+                tuple::tuple_fields,
                 tuple::tuple_iterator_seek_rewind,
                 tuple::tuple_get_format,
                 tuple::tuple_get_field,
@@ -460,20 +497,34 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 [should_panic_if: !tarantool::ffi::has_tuple_field_by_path()]
                 tuple::tuple_get_field_path,
             ]);
+            tests.append(&mut tests![
+                [should_panic_if: !tarantool::ffi::has_tuple_hash()]
+                tuple::tuple_hash,
+            ]);
             tests.append(&mut tests![
                 tuple::tuple_compare,
                 tuple::tuple_compare_with_key,
+                tuple::key_def_builder_sort,
                 tuple::to_and_from_lua,
                 tuple::tuple_debug_fmt,
                 tuple::tuple_buffer_from_vec_fail,
                 coio::coio_accept,
                 coio::coio_read_write,
+                coio::coio_read_write_with_deadline,
+                coio::coio_udp,
                 coio::coio_call,
                 coio::coio_channel,
                 coio::channel_rx_closed,
                 coio::channel_tx_closed,
+                coio::pipe,
+                coio::pipe_async,
+                coio::pipe_fiber_cancelled,
+                coio::pipe_reader_closed,
                 transaction::transaction_commit,
                 transaction::transaction_rollback,
+                transaction::transaction_yielded,
+                transaction::transaction_commit_conflict,
+                transaction::transaction_async_commit,
                 latch::latch_lock,
                 latch::latch_try_lock,
                 net_box::immediate_close,
@@ -485,6 +536,7 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 net_box::call_async_error,
                 net_box::call_async_disconnected,
                 net_box::call_timeout,
+                net_box::call_builder,
                 net_box::call_async_timeout,
                 net_box::call_async_wait_disconnected,
                 net_box::eval,
@@ -493,6 +545,7 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 net_box::connection_error,
                 net_box::is_connected,
                 net_box::schema_sync,
+                net_box::schema_version,
                 net_box::select,
                 net_box::get,
                 net_box::insert,
@@ -504,7 +557,9 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 net_box::triggers_connect,
                 net_box::triggers_reject,
                 net_box::triggers_schema_sync,
+                net_box::reconnect_jitter,
                 net_box::execute,
+                network_client::timeout_methods,
                 proc::simple,
                 proc::return_tuple,
                 proc::return_raw_bytes,
@@ -513,12 +568,16 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 proc::debug,
                 proc::tarantool_reimport,
                 proc::custom_ret,
+                proc::custom_ret_borrowed,
+                proc::raw_or_set_error,
                 proc::inject,
                 proc::inject_with_packed,
                 uuid::to_tuple,
                 uuid::from_tuple,
                 uuid::to_lua,
                 uuid::from_lua,
+                uuid::lua_insert_into_space,
+                uuid::primary_key,
                 enums::space_engine_type,
                 enums::space_field_type,
                 enums::index_type,
@@ -544,6 +603,10 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                     sql::prepared_with_unnamed_params,
                     sql::prepared_with_named_params,
                     sql::prepared_invalid_params,
+                    sql::execute_cached,
+                    sql::execute_cached_other_session,
+                    sql::execute_cached_eviction_error,
+                    sql::execute_returns_info,
                     tuple_picodata::tuple_format_get_names,
                     tuple_picodata::tuple_as_named_buffer,
                     tuple_picodata::tuple_hash,
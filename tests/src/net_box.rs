@@ -188,6 +188,30 @@ pub fn call_timeout() {
     assert!(matches!(result, Err(Error::IO(ref e)) if e.kind() == io::ErrorKind::TimedOut));
 }
 
+pub fn call_builder() {
+    let conn = test_user_conn();
+    let result = conn
+        .call_builder("test_stored_proc")
+        .args(&(1, 2))
+        .timeout(Duration::from_secs(3))
+        .send()
+        .unwrap();
+    assert_eq!(result.decode::<(i32,)>().unwrap(), (3,));
+
+    let (sum,): (i32,) = conn
+        .call_builder("test_stored_proc")
+        .args(&(2, 3))
+        .send_and_decode()
+        .unwrap();
+    assert_eq!(sum, 5);
+
+    let result = conn
+        .call_builder("test_timeout")
+        .timeout(Duration::from_millis(1))
+        .send();
+    assert!(matches!(result, Err(Error::IO(ref e)) if e.kind() == io::ErrorKind::TimedOut));
+}
+
 pub fn call_async_timeout() {
     let conn = test_user_conn();
     let p = conn.call_async::<_, ()>("test_timeout", ()).unwrap();
@@ -318,6 +342,23 @@ pub fn schema_sync() {
     .unwrap();
 }
 
+pub fn schema_version() {
+    let conn = default_conn();
+    assert_eq!(conn.schema_version(), None);
+
+    conn.ping(&Options::default()).unwrap();
+    let before = conn.schema_version().unwrap();
+
+    let space = Space::builder("net_box_schema_version_test")
+        .create()
+        .unwrap();
+    conn.ping(&Options::default()).unwrap();
+    let after = conn.schema_version().unwrap();
+    assert!(after > before, "{} <= {}", after, before);
+
+    space.drop().unwrap();
+}
+
 pub fn get() {
     let conn = test_user_conn();
     let space = conn.space("test_s2").unwrap().unwrap();
@@ -611,6 +652,41 @@ pub fn triggers_reject() {
     assert!(matches!(res, Err(Error::IO(err)) if err.kind() == io::ErrorKind::Interrupted));
 }
 
+pub fn reconnect_jitter() {
+    let base = Duration::from_secs(1);
+
+    let options = ConnOptions {
+        reconnect_after: base,
+        ..ConnOptions::default()
+    };
+    assert_eq!(options.reconnect_delay(), base);
+
+    let options = ConnOptions {
+        reconnect_after: base,
+        reconnect_jitter: 0.25,
+        ..ConnOptions::default()
+    };
+    let delays: Vec<_> = (0..100).map(|_| options.reconnect_delay()).collect();
+    for delay in &delays {
+        assert!(
+            Duration::from_millis(750) <= *delay && *delay <= Duration::from_millis(1250),
+            "{:?}",
+            delay
+        );
+    }
+    assert!(delays.iter().any(|d| *d != delays[0]));
+
+    // Jitter is clamped, so the delay never becomes negative
+    let options = ConnOptions {
+        reconnect_after: base,
+        reconnect_jitter: 5.0,
+        ..ConnOptions::default()
+    };
+    for _ in 0..100 {
+        assert!(options.reconnect_delay() <= 2 * base);
+    }
+}
+
 pub fn triggers_schema_sync() {
     let port = listen_port();
     struct TriggersMock {
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use tarantool::fiber;
+use tarantool::fiber::r#async::timeout;
+use tarantool::network::client::{AsClient as _, Client};
+use tarantool::network::protocol;
+use tarantool::test::util::listen_port;
+
+/// The `*_timeout` methods of `AsClient` can be used without importing
+/// `IntoTimeout`.
+pub fn timeout_methods() {
+    fiber::block_on(async {
+        let mut config = protocol::Config::default();
+        config.creds = Some(("test_user".into(), "password".into()));
+        let client = Client::connect_with_config("localhost", listen_port(), config)
+            .await
+            .unwrap();
+        let timeout = Duration::from_secs(3);
+
+        client.ping_timeout(timeout).await.unwrap();
+
+        let tuple = client
+            .call_timeout("test_stored_proc", &(1, 2), timeout)
+            .await
+            .unwrap();
+        assert_eq!(tuple.decode::<(i32,)>().unwrap(), (3,));
+
+        let tuple = client
+            .eval_timeout("return ...", &(1, 2), timeout)
+            .await
+            .unwrap();
+        assert_eq!(tuple.decode::<(i32, i32)>().unwrap(), (1, 2));
+
+        let rows = client
+            .execute_timeout("SELECT 1, 2", &(), timeout)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].decode::<(i32, i32)>().unwrap(), (1, 2));
+
+        let err = client
+            .eval_timeout("require('fiber').sleep(1)", &(), Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, timeout::Error::Expired), "{}", err);
+
+        let err = client
+            .call_timeout("unexistent_proc", &(), timeout)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, timeout::Error::Failed(_)), "{}", err);
+    })
+}
@@ -211,6 +211,69 @@ pub fn custom_ret() {
     );
 }
 
+pub fn custom_ret_borrowed() {
+    fn big_vec() -> Vec<u64> {
+        (0..10_000).collect()
+    }
+
+    #[tarantool::proc]
+    fn proc_custom_ret_borrowed<'a>(
+        #[inject(&big_vec())] big_vec: &'a Vec<u64>,
+    ) -> ReturnMsgpack<&'a Vec<u64>> {
+        ReturnMsgpack(big_vec)
+    }
+
+    #[tarantool::proc(custom_ret)]
+    fn proc_custom_ret_attr_borrowed<'a>(
+        #[inject(&big_vec())] big_vec: &'a Vec<u64>,
+    ) -> &'a Vec<u64> {
+        big_vec
+    }
+
+    assert_eq!(
+        call_proc::<_, Vec<u64>>("proc_custom_ret_borrowed", ()).unwrap(),
+        big_vec()
+    );
+    assert_eq!(
+        call_proc::<_, Vec<u64>>("proc_custom_ret_attr_borrowed", ()).unwrap(),
+        big_vec()
+    );
+}
+
+pub fn raw_or_set_error() {
+    use std::os::raw::c_int;
+    use tarantool::error::{ResultExt, TarantoolErrorCode};
+    use tarantool::tuple::{FunctionArgs, FunctionCtx};
+
+    #[no_mangle]
+    unsafe extern "C" fn proc_raw_or_set_error(ctx: FunctionCtx, args: FunctionArgs) -> c_int {
+        let f = || {
+            let (s,): (String,) = args.decode().or_set_error(TarantoolErrorCode::ProcC)?;
+            let x: i32 = s.parse().or_set_error(TarantoolErrorCode::IllegalParams)?;
+            ctx.return_mp(&(x + 1))
+                .or_set_error(TarantoolErrorCode::ProcC)
+        };
+        f().unwrap_or(-1)
+    }
+
+    assert_eq!(
+        call_proc::<_, i32>("proc_raw_or_set_error", "68").unwrap(),
+        69
+    );
+
+    let lua = tarantool::lua_state();
+    let (code, message): (u32, String) = lua
+        .eval_with(
+            "local ok, e = pcall(box.func[...].call, box.func[...], {'nope'})
+            assert(not ok)
+            return e.code, e.message",
+            format!("{}.proc_raw_or_set_error", lib_name()),
+        )
+        .unwrap();
+    assert_eq!(code, TarantoolErrorCode::IllegalParams as u32);
+    assert_eq!(message, "invalid digit found in string");
+}
+
 pub fn inject() {
     #[tarantool::proc]
     fn proc_inject<'a>(
@@ -379,3 +442,109 @@ fn proc_public_attribute() {
     let proc = procs.iter().find(|p| p.name() == "test_proc_pub_visibility_public_false").unwrap();
     assert!(!proc.is_public());
 }
+
+#[tarantool::test]
+fn proc_create_ddl() {
+    #[tarantool::proc]
+    fn test_proc_create_ddl(x: i32) -> i32 {
+        x * 2
+    }
+
+    let procs = tarantool::proc::all_procs();
+    let proc = procs
+        .iter()
+        .find(|p| p.name() == "test_proc_create_ddl")
+        .unwrap();
+
+    let lib_name = lib_name();
+    let ddl = proc.create_ddl(&lib_name);
+    assert_eq!(
+        ddl,
+        format!(
+            "box.schema.func.create('{lib_name}.test_proc_create_ddl', \
+            {{language = 'C', if_not_exists = true}})"
+        )
+    );
+
+    // Executing the code registers the proc, doing it again is fine
+    let lua = tarantool::lua_state();
+    lua.exec(&ddl).unwrap();
+    lua.exec(&ddl).unwrap();
+    let res: i32 = lua
+        .eval_with(
+            "return box.func[...]:call{21}",
+            format!("{lib_name}.test_proc_create_ddl"),
+        )
+        .unwrap();
+    assert_eq!(res, 42);
+    lua.exec_with(
+        "box.func[...]:drop()",
+        format!("{lib_name}.test_proc_create_ddl"),
+    )
+    .unwrap();
+
+    assert_eq!(
+        proc.create_ddl("it's"),
+        "box.schema.func.create('it\\'s.test_proc_create_ddl', \
+        {language = 'C', if_not_exists = true})"
+    );
+
+    // The options are added to the options table
+    let ddl = proc.create_ddl_with_options(&lib_name, "returns = 'integer'");
+    assert_eq!(
+        ddl,
+        format!(
+            "box.schema.func.create('{lib_name}.test_proc_create_ddl', \
+            {{language = 'C', if_not_exists = true, returns = 'integer'}})"
+        )
+    );
+    lua.exec(&ddl).unwrap();
+    let returns: String = lua
+        .eval_with(
+            "return box.func[...].returns",
+            format!("{lib_name}.test_proc_create_ddl"),
+        )
+        .unwrap();
+    assert_eq!(returns, "integer");
+    lua.exec_with(
+        "box.func[...]:drop()",
+        format!("{lib_name}.test_proc_create_ddl"),
+    )
+    .unwrap();
+}
+
+#[tarantool::test]
+fn register_dynamic_proc() {
+    unsafe extern "C" fn dynamic_proc(
+        _: tarantool::tuple::FunctionCtx,
+        _: tarantool::tuple::FunctionArgs,
+    ) -> std::os::raw::c_int {
+        0
+    }
+
+    tarantool::proc::register("test_dynamic_proc", dynamic_proc, true).unwrap();
+
+    let procs = tarantool::proc::all_procs();
+    let proc = procs
+        .iter()
+        .find(|p| p.name() == "test_dynamic_proc")
+        .unwrap();
+    assert!(proc.is_public());
+    let expected: tarantool::ffi::tarantool::Proc = dynamic_proc;
+    assert_eq!(proc.proc() as usize, expected as usize);
+    // Statically defined procs are still there
+    assert!(procs.iter().any(|p| p.name() == "test_proc_pub_visibility"));
+
+    let err = tarantool::proc::register("test_dynamic_proc", dynamic_proc, false).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "stored procedure `test_dynamic_proc` is already registered"
+    );
+
+    let err =
+        tarantool::proc::register("test_proc_pub_visibility", dynamic_proc, false).unwrap_err();
+    assert_eq!(
+        err,
+        tarantool::proc::AlreadyRegistered("test_proc_pub_visibility")
+    );
+}
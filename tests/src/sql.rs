@@ -252,3 +252,153 @@ pub fn prepared_with_named_params() {
 
     drop_sql_test_space(sp).unwrap();
 }
+
+pub fn execute_cached() {
+    let sp = create_sql_test_space("SQL_TEST").unwrap();
+    sp.insert(&(1, "one")).unwrap();
+    sp.insert(&(2, "two")).unwrap();
+
+    let sql = "SELECT * FROM SQL_TEST WHERE ID = ?";
+    let stats = tarantool::sql::statement_cache_stats();
+
+    let mut stream = tarantool::sql::execute_cached(sql, &(1,), 100).unwrap();
+    let result = decode_dql_result::<Vec<(u64, String)>>(&mut stream);
+    assert_eq!(result, [(1, "one".to_string())]);
+    let new_stats = tarantool::sql::statement_cache_stats();
+    assert_eq!(new_stats.misses, stats.misses + 1);
+    assert_eq!(new_stats.hits, stats.hits);
+
+    // The second execution reuses the prepared statement
+    let mut stream = tarantool::sql::execute_cached(sql, &(2,), 100).unwrap();
+    let result = decode_dql_result::<Vec<(u64, String)>>(&mut stream);
+    assert_eq!(result, [(2, "two".to_string())]);
+    let stats = tarantool::sql::statement_cache_stats();
+    assert_eq!(stats.misses, new_stats.misses);
+    assert_eq!(stats.hits, new_stats.hits + 1);
+
+    // Schema change invalidates the cache
+    drop_sql_test_space(sp).unwrap();
+    let sp = create_sql_test_space("SQL_TEST").unwrap();
+    sp.insert(&(1, "uno")).unwrap();
+
+    let mut stream = tarantool::sql::execute_cached(sql, &(1,), 100).unwrap();
+    let result = decode_dql_result::<Vec<(u64, String)>>(&mut stream);
+    assert_eq!(result, [(1, "uno".to_string())]);
+    let new_stats = tarantool::sql::statement_cache_stats();
+    assert_eq!(new_stats.misses, stats.misses + 1);
+    assert_eq!(new_stats.hits, stats.hits);
+
+    // With zero capacity nothing is cached
+    tarantool::sql::set_statement_cache_capacity(0).unwrap();
+    let mut stream = tarantool::sql::execute_cached(sql, &(1,), 100).unwrap();
+    let result = decode_dql_result::<Vec<(u64, String)>>(&mut stream);
+    assert_eq!(result, [(1, "uno".to_string())]);
+    assert_eq!(tarantool::sql::statement_cache_stats(), new_stats);
+    tarantool::sql::set_statement_cache_capacity(tarantool::sql::DEFAULT_STATEMENT_CACHE_CAPACITY)
+        .unwrap();
+
+    drop_sql_test_space(sp).unwrap();
+}
+
+pub fn execute_cached_other_session() {
+    let sp = create_sql_test_space("SQL_TEST").unwrap();
+    sp.insert(&(1, "one")).unwrap();
+    let sql = "SELECT * FROM SQL_TEST WHERE ID = ?";
+    let session_id = || unsafe { tarantool::ffi::tarantool::box_session_id() };
+    tarantool::sql::clear_statement_cache().unwrap();
+
+    // A fiber started from rust gets its own session, which is closed along
+    // with the fiber
+    let other_session_id = tarantool::fiber::start(move || {
+        tarantool::sql::execute_cached(sql, &(1,), 100).unwrap();
+        session_id()
+    })
+    .join();
+    assert_ne!(other_session_id, session_id());
+
+    // The statement of the closed session is evicted without an error, it's
+    // already been unprepared along with the session
+    tarantool::sql::set_statement_cache_capacity(1).unwrap();
+    let stats = tarantool::sql::statement_cache_stats();
+    let mut stream = tarantool::sql::execute_cached(sql, &(1,), 100).unwrap();
+    let result = decode_dql_result::<Vec<(u64, String)>>(&mut stream);
+    assert_eq!(result, [(1, "one".to_string())]);
+    assert_eq!(
+        tarantool::sql::statement_cache_stats().misses,
+        stats.misses + 1
+    );
+
+    tarantool::sql::clear_statement_cache().unwrap();
+    tarantool::sql::set_statement_cache_capacity(tarantool::sql::DEFAULT_STATEMENT_CACHE_CAPACITY)
+        .unwrap();
+    drop_sql_test_space(sp).unwrap();
+}
+
+pub fn execute_cached_eviction_error() {
+    let sp = create_sql_test_space("SQL_TEST").unwrap();
+    sp.insert(&(1, "one")).unwrap();
+    let sql = "SELECT * FROM SQL_TEST WHERE ID = ?";
+    let other_sql = "SELECT * FROM SQL_TEST WHERE ID > ?";
+    tarantool::sql::clear_statement_cache().unwrap();
+    tarantool::sql::set_statement_cache_capacity(1).unwrap();
+
+    tarantool::sql::execute_cached(sql, &(1,), 100).unwrap();
+    // Unprepare the cached statement behind the cache's back, so that
+    // unpreparing it on eviction fails
+    let stmt = tarantool::sql::prepare(sql.into()).unwrap();
+    tarantool::sql::unprepare(stmt).unwrap();
+
+    // The failure to unprepare the evicted statement doesn't affect the query
+    let mut stream = tarantool::sql::execute_cached(other_sql, &(0,), 100).unwrap();
+    let result = decode_dql_result::<Vec<(u64, String)>>(&mut stream);
+    assert_eq!(result, [(1, "one".to_string())]);
+
+    tarantool::sql::clear_statement_cache().unwrap();
+    tarantool::sql::set_statement_cache_capacity(tarantool::sql::DEFAULT_STATEMENT_CACHE_CAPACITY)
+        .unwrap();
+    drop_sql_test_space(sp).unwrap();
+}
+
+pub fn execute_returns_info() {
+    use tarantool::sql::SqlResult;
+
+    tarantool::sql::execute(
+        "CREATE TABLE SQL_AUTOINC_TEST (ID INTEGER PRIMARY KEY AUTOINCREMENT, VALUE STRING)",
+        &(),
+        100,
+    )
+    .unwrap();
+
+    let res = tarantool::sql::execute(
+        "INSERT INTO SQL_AUTOINC_TEST (VALUE) VALUES (?)",
+        &("one",),
+        100,
+    )
+    .unwrap();
+    let SqlResult::Info(info) = res else {
+        panic!("expected Info, got {:?}", res);
+    };
+    assert_eq!(info.row_count, 1);
+    assert_eq!(info.autoincrement_ids.len(), 1);
+    let id = info.autoincrement_ids[0];
+
+    // The rows path still works
+    let res = tarantool::sql::execute("SELECT * FROM SQL_AUTOINC_TEST", &(), 100).unwrap();
+    let SqlResult::Rows(rows) = res else {
+        panic!("expected Rows, got {:?}", res);
+    };
+    assert_eq!(rows.len(), 1);
+    assert_eq!(
+        rows[0].decode::<(i64, String)>().unwrap(),
+        (id, "one".to_string())
+    );
+
+    let res = tarantool::sql::execute("DELETE FROM SQL_AUTOINC_TEST", &(), 100).unwrap();
+    let SqlResult::Info(info) = res else {
+        panic!("expected Info, got {:?}", res);
+    };
+    assert_eq!(info.row_count, 1);
+    assert!(info.autoincrement_ids.is_empty());
+
+    tarantool::sql::execute("DROP TABLE SQL_AUTOINC_TEST", &(), 100).unwrap();
+}
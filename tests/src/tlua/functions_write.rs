@@ -1,6 +1,6 @@
 use std::sync::Arc;
 use tarantool::tlua::{
-    self, function, function0, function1, function2, AsLua, Function, Lua, LuaFunction,
+    self, function, function0, function1, function2, AnyLuaValue, AsLua, Function, Lua, LuaFunction,
 };
 
 pub fn simple_function() {
@@ -42,6 +42,31 @@ pub fn two_arguments() {
     assert_eq!(val, 10);
 }
 
+pub fn multiple_return_values() {
+    let lua = Lua::new();
+    lua.openlibs();
+
+    let f: function![() -> (i32, &'static str, f64)] = function0(|| (1, "two", 3.5));
+    lua.set("three", f);
+    lua.set(
+        "five",
+        function1(|x: i32| (x, x + 1, x + 2, Some(x + 3), format!("{}", x + 4))),
+    );
+
+    // Values are returned as separate lua values, not as a table
+    let n: i32 = lua.eval("return select('#', three())").unwrap();
+    assert_eq!(n, 3);
+    let ty: String = lua.eval("return type((three()))").unwrap();
+    assert_eq!(ty, "number");
+    let res: (i32, String, f64) = lua.eval("return three()").unwrap();
+    assert_eq!(res, (1, "two".into(), 3.5));
+
+    let n: i32 = lua.eval("return select('#', five(1))").unwrap();
+    assert_eq!(n, 5);
+    let res: (i32, i32, i32, Option<i32>, String) = lua.eval("return five(1)").unwrap();
+    assert_eq!(res, (1, 2, 3, Some(4), "5".into()));
+}
+
 pub fn wrong_arguments_types() {
     let lua = Lua::new();
 
@@ -299,6 +324,91 @@ pub fn error() {
     assert_eq!(msg, "but this way is the best");
 }
 
+pub fn catch_panic() {
+    let lua = tarantool::lua_state();
+    lua.set(
+        "panicking_callback",
+        tlua::Function::new(|x: i32| {
+            tlua::catch_panic(|| {
+                if x < 0 {
+                    panic!("negative value: {}", x);
+                }
+                x * 2
+            })
+        }),
+    );
+
+    let v: i32 = lua.eval("return panicking_callback(21)").unwrap();
+    assert_eq!(v, 42);
+
+    let (ok, err): (bool, String) = lua
+        .eval(
+            "local ok, err = pcall(panicking_callback, -1)
+            return ok, tostring(err)",
+        )
+        .unwrap();
+    assert!(!ok);
+    assert_eq!(err, "rust callback panicked: negative value: -1");
+
+    let err = lua
+        .eval::<()>("return panicking_callback(-2)")
+        .unwrap_err()
+        .to_string();
+    assert_eq!(err, "rust callback panicked: negative value: -2");
+}
+
+pub fn error_with_code() {
+    let lua = tarantool::lua_state();
+    lua.set(
+        "coded_error_callback",
+        tlua::Function::new(|code: u32| -> Result<(), tlua::ThrowWithCode<String>> {
+            Err(tlua::Throw(format!("failed with code {}", code)).with_code(code))
+        }),
+    );
+    let (code, message, as_string, is_box_error): (u32, String, String, bool) = lua
+        .eval(
+            "
+        local ok, err = pcall(coded_error_callback, 69)
+        assert(not ok)
+        return err.code, err.message, tostring(err), type(err) == 'cdata'
+    ",
+        )
+        .unwrap();
+    assert_eq!(code, 69);
+    assert_eq!(message, "failed with code 69");
+    assert_eq!(as_string, "failed with code 69");
+    // box.error objects are cdata
+    assert!(is_box_error);
+
+    let msg = lua
+        .exec("coded_error_callback(13)")
+        .unwrap_err()
+        .to_string();
+    assert_eq!(msg, "failed with code 13");
+
+    // Without `box` the error is a lua table with the same fields
+    let lua = Lua::new();
+    lua.openlibs();
+    lua.set(
+        "coded_error_callback",
+        tlua::Function::new(|code: u32| -> Result<(), tlua::ThrowWithCode<String>> {
+            Err(tlua::Throw(format!("failed with code {}", code)).with_code(code))
+        }),
+    );
+    let (code, message, as_string): (u32, String, String) = lua
+        .eval(
+            "
+        local ok, err = pcall(coded_error_callback, 69)
+        assert(not ok)
+        return err.code, err.message, tostring(err)
+    ",
+        )
+        .unwrap();
+    assert_eq!(code, 69);
+    assert_eq!(message, "failed with code 69");
+    assert_eq!(as_string, "failed with code 69");
+}
+
 pub fn optional_params() {
     let lua = Lua::new();
     #[derive(tlua::LuaRead)]
@@ -376,3 +486,102 @@ pub fn lua_function_as_argument() {
         .unwrap();
     assert_eq!(my_data.get(), 69);
 }
+
+pub fn variadic() {
+    let lua = Lua::new();
+    let seen = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+    lua.set(
+        "variadic",
+        Function::variadic({
+            let seen = seen.clone();
+            move |args: Vec<AnyLuaValue>| {
+                let n = args.len();
+                seen.borrow_mut().push(args);
+                n
+            }
+        }),
+    );
+
+    let n: usize = lua.eval("return variadic()").unwrap();
+    assert_eq!(n, 0);
+    let n: usize = lua.eval("return variadic('one')").unwrap();
+    assert_eq!(n, 1);
+    let n: usize = lua
+        .eval("return variadic(1, 'two', true, 4.5, {})")
+        .unwrap();
+    assert_eq!(n, 5);
+
+    let seen = seen.borrow();
+    assert_eq!(seen.len(), 3);
+    assert!(seen[0].is_empty());
+    assert_eq!(seen[1], [AnyLuaValue::LuaString("one".into())]);
+    assert_eq!(seen[2][1], AnyLuaValue::LuaString("two".into()));
+    assert_eq!(seen[2][2], AnyLuaValue::LuaBoolean(true));
+    assert_eq!(seen[2][4], AnyLuaValue::LuaArray(vec![]));
+
+    // Fixed parameters followed by the rest of them
+    lua.set(
+        "sum",
+        Function::new(|first: i32, tlua::Variadic(rest): tlua::Variadic<i32>| {
+            first + rest.into_iter().sum::<i32>()
+        }),
+    );
+    assert_eq!(lua.eval::<i32>("return sum(1)").unwrap(), 1);
+    assert_eq!(lua.eval::<i32>("return sum(1, 2, 3, 4, 5)").unwrap(), 15);
+    let err = lua.eval::<i32>("return sum(1, 2, 'three')").unwrap_err();
+    assert!(
+        err.to_string().contains("reading variadic arguments"),
+        "{}",
+        err
+    );
+    assert!(
+        err.to_string().contains("argument #3 of wrong type"),
+        "{}",
+        err
+    );
+}
+
+pub fn push_generator() {
+    let lua = Lua::new();
+    let next_calls = std::rc::Rc::new(std::cell::Cell::new(0));
+    let iter = (1..=1000).inspect({
+        let next_calls = next_calls.clone();
+        move |_| next_calls.set(next_calls.get() + 1)
+    });
+
+    let sum: LuaFunction<_> = lua
+        .eval(
+            "return function(gen, limit)
+                local sum = 0
+                for i in gen do
+                    if i > limit then break end
+                    sum = sum + i
+                end
+                return sum
+            end",
+        )
+        .unwrap();
+
+    let gen = (&lua).push_generator(iter);
+    let gen: LuaFunction<_> = gen.read().unwrap();
+    // The elements are produced lazily
+    assert_eq!(next_calls.get(), 0);
+
+    let res: i32 = sum.call_with_args((&gen, 10)).unwrap();
+    assert_eq!(res, 55);
+    assert_eq!(next_calls.get(), 11);
+
+    // The generator continues where it stopped
+    let res: i32 = sum.call_with_args((&gen, 1000)).unwrap();
+    assert_eq!(res, (12..=1000).sum::<i32>());
+    assert_eq!(next_calls.get(), 1000);
+
+    // Exhausted generator returns nil
+    let res: Option<i32> = gen.call().unwrap();
+    assert_eq!(res, None);
+
+    // The iterator is dropped with the lua function
+    drop(gen);
+    lua.exec("collectgarbage()").unwrap();
+    assert_eq!(std::rc::Rc::strong_count(&next_calls), 1);
+}
@@ -261,6 +261,52 @@ pub fn multiple_return_values() {
     );
 }
 
+pub fn many_values() {
+    let lua = Lua::new();
+
+    // Tuples longer than 12 don't implement PartialEq, so compare the parts
+    type T14 = (
+        i32,
+        i32,
+        i32,
+        i32,
+        i32,
+        i32,
+        i32,
+        i32,
+        i32,
+        i32,
+        i32,
+        i32,
+        i32,
+        String,
+    );
+    let f = LuaFunction::load(&lua, "return ...").unwrap();
+    let res: T14 = f
+        .call_with_args((1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, "fourteen"))
+        .unwrap();
+    let (a, b, c, d, e, f_, g, h, i, j, k, l, m, n) = res;
+    assert_eq!(
+        (a, b, c, d, e, f_, g, h, i, j, k, l),
+        (1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12)
+    );
+    assert_eq!((m, n), (13, "fourteen".to_string()));
+
+    // Rust callback returning 16 values
+    lua.set(
+        "sixteen",
+        tlua::Function::new(|| (1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16)),
+    );
+    let sum: i32 = lua
+        .eval(
+            "local sum = 0
+            for _, v in ipairs({sixteen()}) do sum = sum + v end
+            return sum",
+        )
+        .unwrap();
+    assert_eq!(sum, 136);
+}
+
 pub fn multiple_return_values_fail() {
     let lua = Lua::new();
     let f = LuaFunction::load(&lua, "return 1, 2, 3;").unwrap();
@@ -401,3 +447,52 @@ pub fn eval_with() {
     let res: i32 = f.call_with_args(400).unwrap();
     assert_eq!(res, 420);
 }
+
+pub fn into_iter() {
+    let lua = Lua::new();
+    lua.exec(
+        "function make_counter(n)
+            local i = 0
+            return function()
+                i = i + 1
+                if i <= n then return i end
+            end
+        end",
+    )
+    .unwrap();
+    let make_counter: LuaFunction<_> = lua.get("make_counter").unwrap();
+
+    let counter: LuaFunction<_> = make_counter.call_with_args(3).unwrap();
+    let values: Vec<i32> = counter.into_iter().collect::<Result<_, _>>().unwrap();
+    assert_eq!(values, [1, 2, 3]);
+
+    // Multiple return values
+    let f: LuaFunction<_> = lua
+        .eval(
+            "local t = {'a', 'b'}
+            local i = 0
+            return function()
+                i = i + 1
+                if t[i] then return i, t[i] end
+            end",
+        )
+        .unwrap();
+    let values: Vec<(i32, String)> = f.into_iter().collect::<Result<_, _>>().unwrap();
+    assert_eq!(values, [(1, "a".into()), (2, "b".into())]);
+
+    // Errors stop the iteration
+    let f: LuaFunction<_> = lua.eval("return function() error('oops') end").unwrap();
+    let mut iter = f.into_iter::<i32>();
+    assert!(matches!(
+        iter.next(),
+        Some(Err(LuaError::ExecutionError(_)))
+    ));
+    assert!(iter.next().is_none());
+
+    let f: LuaFunction<_> = lua
+        .eval("return function() return 'not a number' end")
+        .unwrap();
+    let mut iter = f.into_iter::<i32>();
+    assert!(matches!(iter.next(), Some(Err(LuaError::WrongType(_)))));
+    assert!(iter.next().is_none());
+}
@@ -1,3 +1,4 @@
+use crate::common::LuaStackIntegrityGuard;
 use tarantool::tlua::{function0, Lua, LuaTable, PushGuard};
 
 pub fn iterable() {
@@ -63,6 +64,79 @@ pub fn get_nil() {
     assert_eq!(t.get::<Option<Option<i32>>, _>(1), Some(None));
 }
 
+pub fn len() {
+    let lua = Lua::new();
+
+    let t: LuaTable<_> = lua.eval("return { 10, 20, 30, 40, 50 }").unwrap();
+    {
+        let _guard = LuaStackIntegrityGuard::new("len", &lua);
+        assert_eq!(t.len(), 5);
+        assert!(!t.is_empty());
+    }
+    // Iterating the sequence by index
+    let sum: i32 = (1..=t.len()).map(|i| t.get::<i32, _>(i).unwrap()).sum();
+    assert_eq!(sum, 150);
+
+    // Non integer keys are not counted
+    let t: LuaTable<_> = lua.eval("return { x = 1, y = 2 }").unwrap();
+    assert_eq!(t.len(), 0);
+    assert!(t.is_empty());
+
+    // Appending to the sequence updates the length
+    let t: LuaTable<_> = lua.eval("return { 1, 2, 3 }").unwrap();
+    t.set(4, 4);
+    assert_eq!(t.len(), 4);
+
+    // For tables with holes the result is any border
+    let t: LuaTable<_> = lua.eval("return { 1, 2, nil, 4 }").unwrap();
+    let len = t.len();
+    assert!(len == 2 || len == 4, "{}", len);
+}
+
+pub fn insert_remove() {
+    let lua = Lua::new();
+    lua.openlibs();
+
+    let t: LuaTable<_> = lua.eval("return { 'a', 'b', 'c' }").unwrap();
+    let contents =
+        |t: &LuaTable<_>| -> Vec<String> { (1..=t.len()).map(|i| t.get(i).unwrap()).collect() };
+    {
+        let _guard = LuaStackIntegrityGuard::new("insert_remove", &lua);
+
+        // Insert in the middle
+        t.insert(2, "x");
+        assert_eq!(contents(&t), ["a", "x", "b", "c"]);
+
+        // Remove from the front
+        assert_eq!(t.remove::<String>(1).as_deref(), Some("a"));
+        assert_eq!(contents(&t), ["x", "b", "c"]);
+
+        // Append and remove from the back
+        t.insert(4, "d");
+        assert_eq!(t.remove::<String>(4).as_deref(), Some("d"));
+
+        // Out of range and wrong type
+        assert_eq!(t.remove::<String>(0), None);
+        assert_eq!(t.remove::<String>(4), None);
+        assert_eq!(t.remove::<i32>(1), None);
+        assert_eq!(contents(&t), ["x", "b", "c"]);
+    }
+
+    // Same as lua table library
+    let ok: bool = lua
+        .eval_with(
+            "local t = ...
+            table.insert(t, 1, 'y')
+            return table.remove(t) == 'c' and table.concat(t) == 'yxb'",
+            &t,
+        )
+        .unwrap();
+    assert!(ok);
+
+    let res = std::panic::catch_unwind(|| t.insert(5, "z"));
+    assert!(res.is_err());
+}
+
 pub fn table_over_table() {
     let lua = Lua::new();
 
@@ -133,6 +207,46 @@ pub fn complex_anonymous_table_metatable() {
     assert_eq!(&r, return_value);
 }
 
+pub fn set_metatable() {
+    let lua = Lua::new();
+    lua.openlibs();
+
+    let defaults = LuaTable::empty(&lua);
+    defaults.set("x", 1);
+    defaults.set("y", 2);
+
+    let mt = LuaTable::empty(&lua);
+    mt.set("__index", &defaults);
+
+    let table = LuaTable::empty(&lua);
+    table.set("y", 20);
+    {
+        let _guard = LuaStackIntegrityGuard::new("set_metatable", &lua);
+        table.set_metatable(&mt);
+    }
+
+    // Missing fields fall through to the defaults table
+    assert_eq!(table.get::<i32, _>("x"), Some(1));
+    assert_eq!(table.get::<i32, _>("y"), Some(20));
+    assert_eq!(table.get::<i32, _>("z"), None);
+
+    let (x, y, same_mt): (i32, i32, bool) = lua
+        .eval_with(
+            "local t, mt = ...; return t.x, t.y, getmetatable(t) == mt",
+            (&table, &mt),
+        )
+        .unwrap();
+    assert_eq!((x, y, same_mt), (1, 20, true));
+
+    // Replaces the existing metatable
+    let other_defaults = LuaTable::empty(&lua);
+    other_defaults.set("x", 100);
+    let other_mt = LuaTable::empty(&lua);
+    other_mt.set("__index", &other_defaults);
+    table.set_metatable(&other_mt);
+    assert_eq!(table.get::<i32, _>("x"), Some(100));
+}
+
 pub fn empty_array() {
     let lua = Lua::new();
 
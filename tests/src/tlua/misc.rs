@@ -1,6 +1,6 @@
 use crate::common::LuaStackIntegrityGuard;
 use tarantool::tlua::{
-    self, AsLua, Lua, LuaFunction, LuaTable, PushGuard,
+    self, AnyLuaString, AsLua, Lua, LuaError, LuaFunction, LuaTable, PushGuard,
     TuplePushError::{First, Other},
 };
 
@@ -71,6 +71,31 @@ pub fn dump_stack_raw() {
     )
 }
 
+pub fn dump_stack_entries() {
+    let lua = Lua::new();
+    let _guard = (&lua)
+        .push("hello")
+        .push(3.14)
+        .push(false)
+        .push(tlua::Nil)
+        .push(vec![1, 2]);
+    let stack = lua.dump_stack();
+    let types: Vec<_> = stack.iter().map(|e| e.type_name).collect();
+    assert_eq!(types, ["string", "number", "boolean", "nil", "table"]);
+    let indexes: Vec<_> = stack.iter().map(|e| e.index).collect();
+    assert_eq!(indexes, [1, 2, 3, 4, 5]);
+    assert_eq!(stack[0].to_string(), "1: string(hello)");
+    assert_eq!(stack[1].value, "3.14");
+    assert_eq!(stack[2].value, "false");
+    assert_eq!(stack[3].value, "nil");
+    assert!(stack[4].value.starts_with("table: 0x"));
+
+    // The stack is unchanged, numbers aren't converted to strings
+    assert_eq!(lua.dump_stack(), stack);
+    let n: f64 = (&lua).read_at(2).ok().unwrap();
+    assert_eq!(n, 3.14);
+}
+
 pub fn error_during_push_tuple() {
     #[derive(Debug, PartialEq, Eq)]
     struct CustomError;
@@ -110,6 +135,150 @@ pub fn error_during_push_tuple() {
     drop(lua);
 }
 
+pub fn eval_protected() {
+    let lua = Lua::new();
+    lua.openlibs();
+    let _guard = LuaStackIntegrityGuard::new("eval_protected", &lua);
+
+    let v: i32 = lua.eval_protected("return 6 * 7").unwrap();
+    assert_eq!(v, 42);
+
+    match lua.eval_protected::<i32>("error('oops', 0)").unwrap_err() {
+        LuaError::ExecutionError(msg) => assert_eq!(msg, "oops"),
+        e => panic!("unexpected error: {}", e),
+    }
+
+    match lua.exec_protected("local t = nil; t.x = 1").unwrap_err() {
+        LuaError::ExecutionError(msg) => assert!(msg.contains("attempt to index")),
+        e => panic!("unexpected error: {}", e),
+    }
+
+    // Type mismatch is still reported as such
+    let e = lua
+        .eval_protected::<i32>("return 'not a number'")
+        .unwrap_err();
+    assert!(matches!(e, LuaError::WrongType(_)));
+
+    // An error raised by a metamethod while the result is being read
+    let res = lua.eval_protected::<tlua::ToString>(
+        "return setmetatable({}, { __tostring = function() error('bad tostring', 0) end })",
+    );
+    match res.unwrap_err() {
+        LuaError::ExecutionError(msg) => assert_eq!(msg, "bad tostring"),
+        e => panic!("unexpected error: {}", e),
+    }
+
+    // The lua state is still usable
+    lua.exec_protected("x = 1").unwrap();
+    assert_eq!(lua.get::<i32, _>("x"), Some(1));
+}
+
+pub fn eval_in_env() {
+    let lua = Lua::new();
+    lua.openlibs();
+    let _guard = LuaStackIntegrityGuard::new("eval_in_env", &lua);
+
+    let env = LuaTable::empty(&lua);
+    env.set("double", tlua::function1(|x: i32| x * 2));
+
+    // Whitelisted function is available
+    let v: i32 = lua.eval_in_env("return double(21)", &env).unwrap();
+    assert_eq!(v, 42);
+
+    // Builtin modules are not
+    match lua.exec_in_env("os.exit(1)", &env).unwrap_err() {
+        LuaError::ExecutionError(msg) => {
+            assert!(msg.contains("attempt to index global 'os'"), "{}", msg)
+        }
+        e => panic!("unexpected error: {}", e),
+    }
+    let v: bool = lua
+        .eval_in_env("return io == nil and _G == nil", &env)
+        .unwrap();
+    assert!(v);
+
+    // Globals are assigned in the env, not in the global table
+    lua.exec_in_env("x = double(2)", &env).unwrap();
+    assert_eq!(env.get::<i32, _>("x"), Some(4));
+    assert_eq!(lua.get::<i32, _>("x"), None);
+
+    // Env must be a table
+    let e = lua.exec_in_env("return", 1).unwrap_err();
+    assert!(matches!(e, LuaError::WrongType(_)));
+
+    // Precompiled bytecode is refused
+    let bytecode: AnyLuaString = lua
+        .eval("return string.dump(function() return 42 end)")
+        .unwrap();
+    // The bytecode may not be valid utf-8, but the leading `\x1b` is kept
+    let bytecode = String::from_utf8_lossy(&bytecode.0);
+    assert!(bytecode.starts_with('\x1b'));
+    match lua.eval_in_env::<_, i32>(&bytecode, &env).unwrap_err() {
+        LuaError::SyntaxError(msg) => {
+            assert!(msg.contains("attempt to load a binary chunk"), "{}", msg)
+        }
+        e => panic!("unexpected error: {}", e),
+    }
+}
+
+pub fn eval_capturing_print() {
+    let lua = Lua::new();
+    lua.openlibs();
+    let _guard = LuaStackIntegrityGuard::new("eval_capturing_print", &lua);
+
+    let (res, output) = lua.eval_capturing_print::<i32>("print('hi'); return 5");
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(output, "hi\n");
+
+    // Arguments are converted via tostring and separated by tabs
+    let (res, output) = lua.eval_capturing_print::<()>(
+        "print(1, nil, true, setmetatable({}, {__tostring = function() return 'x' end})) print()",
+    );
+    res.unwrap();
+    assert_eq!(output, "1\tnil\ttrue\tx\n\n");
+
+    // The builtin print is restored even if the code fails
+    let print_is_builtin = "return print == builtin_print";
+    lua.exec("builtin_print = print").unwrap();
+    let (res, output) = lua.eval_capturing_print::<()>("print('before'); error('oops')");
+    assert!(matches!(res, Err(LuaError::ExecutionError(_))));
+    assert_eq!(output, "before\n");
+    assert!(lua.eval::<bool>(print_is_builtin).unwrap());
+
+    // Errors in tostring are propagated
+    let (res, output) = lua.eval_capturing_print::<()>(
+        "print(setmetatable({}, {__tostring = function() error('bad tostring') end}))",
+    );
+    match res.unwrap_err() {
+        LuaError::ExecutionError(msg) => assert!(msg.contains("bad tostring"), "{}", msg),
+        e => panic!("unexpected error: {}", e),
+    }
+    assert_eq!(output, "");
+    assert!(lua.eval::<bool>(print_is_builtin).unwrap());
+}
+
+pub fn register_module() {
+    let lua = Lua::new();
+    lua.openlibs();
+
+    {
+        let _guard = LuaStackIntegrityGuard::new("register_module", &lua);
+        let module: LuaTable<_> = lua.eval("return {}").unwrap();
+        module.set("add", tlua::function2(|a: i32, b: i32| a + b));
+        lua.register_module("rust_test_module", &module);
+    }
+
+    let res: i32 = lua
+        .eval("return require('rust_test_module').add(1, 2)")
+        .unwrap();
+    assert_eq!(res, 3);
+
+    let is_same: bool = lua
+        .eval("return require('rust_test_module') == package.loaded.rust_test_module")
+        .unwrap();
+    assert!(is_same);
+}
+
 pub fn hash() {
     assert_eq!(tlua::util::hash(""), 0);
     assert_eq!(tlua::util::hash("a"), 0x20e3223e);
@@ -681,6 +681,70 @@ pub fn derive_generic_struct_lua_read() {
     type S1 = S<String, f32, u32, u64, bool>;
 }
 
+pub fn derive_struct_lua_read_option() {
+    #[derive(Debug, PartialEq, Eq, LuaRead)]
+    struct Inner {
+        x: i32,
+        y: String,
+    }
+
+    #[derive(Debug, PartialEq, Eq, LuaRead)]
+    struct Outer {
+        id: i32,
+        inner: Option<Inner>,
+    }
+
+    let lua = Lua::new();
+
+    // Top level
+    let v: Option<Inner> = lua.eval("return nil").unwrap();
+    assert_eq!(v, None);
+    let v: Option<Inner> = lua.eval("return { x = 1, y = 'one' }").unwrap();
+    assert_eq!(
+        v,
+        Some(Inner {
+            x: 1,
+            y: "one".into()
+        })
+    );
+
+    // Field present
+    let v: Outer = lua
+        .eval("return { id = 2, inner = { x = 3, y = 'three' } }")
+        .unwrap();
+    assert_eq!(
+        v,
+        Outer {
+            id: 2,
+            inner: Some(Inner {
+                x: 3,
+                y: "three".into()
+            }),
+        }
+    );
+
+    // Field absent
+    let v: Outer = lua.eval("return { id = 4 }").unwrap();
+    assert_eq!(v, Outer { id: 4, inner: None });
+
+    // Read from a table field
+    let t: LuaTable<_> = lua
+        .eval("return { present = { x = 5, y = 'five' } }")
+        .unwrap();
+    assert_eq!(
+        t.get::<Option<Inner>, _>("present"),
+        Some(Some(Inner {
+            x: 5,
+            y: "five".into()
+        }))
+    );
+    assert_eq!(t.get::<Option<Inner>, _>("absent"), Some(None));
+
+    // Wrong type is still an error
+    let res = lua.eval::<Option<Inner>>("return 'not a table'");
+    assert!(res.is_err());
+}
+
 pub fn derive_enum_push() {
     #[derive(Push)]
     enum E {
@@ -1,8 +1,14 @@
 use std::io;
+use std::time::Duration;
 
-use tarantool::error::Error;
-use tarantool::space::Space;
-use tarantool::transaction::transaction;
+use tarantool::error::{Error, TarantoolErrorCode};
+use tarantool::fiber;
+use tarantool::fiber::r#async::timeout::IntoTimeout as _;
+use tarantool::network::client::{AsClient as _, Client};
+use tarantool::network::protocol;
+use tarantool::space::{Space, SpaceEngineType};
+use tarantool::test::util::{listen_port, on_scope_exit};
+use tarantool::transaction::{transaction, transaction_async, TransactionError};
 
 use crate::common::S1Record;
 
@@ -45,3 +51,97 @@ pub fn transaction_rollback() {
     let output = space.get(&(1,)).unwrap();
     assert!(output.is_none());
 }
+
+pub fn transaction_yielded() {
+    let space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+
+    let result = transaction(|| -> Result<(), Error> {
+        space.insert(&S1Record {
+            id: 1,
+            text: "test".to_string(),
+        })?;
+        // Memtx transactions are aborted if the fiber yields after a write
+        fiber::sleep(Duration::from_millis(1));
+        Ok(())
+    });
+    let e = result.unwrap_err();
+    assert!(matches!(e, TransactionError::Yielded(_)), "{}", e);
+    let e = Error::from(e);
+    assert!(matches!(e, Error::TransactionYielded(_)), "{}", e);
+    assert!(e.to_string().starts_with(
+        "transaction aborted, because the fiber yielded inside it (e.g. in fiber::sleep"
+    ));
+
+    assert!(space.get(&(1,)).unwrap().is_none());
+    assert!(!tarantool::transaction::is_in_transaction());
+}
+
+pub fn transaction_commit_conflict() {
+    // Vinyl transactions can yield, but are aborted by conflicting writes
+    let space = Space::builder("transaction_commit_conflict")
+        .engine(SpaceEngineType::Vinyl)
+        .create()
+        .unwrap();
+    let _guard = on_scope_exit(|| space.drop().unwrap());
+    space.index_builder("pk").create().unwrap();
+
+    let result = transaction(|| -> Result<(), Error> {
+        assert!(space.get(&(1,))?.is_none());
+        space.replace(&(2, "mine"))?;
+        // Meanwhile another fiber overwrites the tuple we've read
+        let space = space.clone();
+        fiber::start(move || space.replace(&(1, "theirs")).unwrap()).join();
+        Ok(())
+    });
+    let e = result.unwrap_err();
+    let TransactionError::FailedToCommit(e) = e else {
+        panic!("{}", e);
+    };
+    assert_eq!(
+        e.error_code(),
+        TarantoolErrorCode::TransactionConflict as u32
+    );
+
+    assert!(space.get(&(2,)).unwrap().is_none());
+    assert!(!tarantool::transaction::is_in_transaction());
+}
+
+pub fn transaction_async_commit() {
+    let space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+
+    fiber::block_on(async {
+        let mut config = protocol::Config::default();
+        config.creds = Some(("test_user".into(), "password".into()));
+        let client = Client::connect_with_config("localhost", listen_port(), config)
+            .timeout(Duration::from_secs(3))
+            .await
+            .unwrap();
+
+        let result = transaction_async(|| async {
+            // Memtx transactions can only yield before the first write
+            let sum = client.call("test_stored_proc", &(1, 2)).await?;
+            let (id,): (u32,) = sum.decode()?;
+            space.insert(&(id, "async"))?;
+            Ok::<_, Error>(id)
+        })
+        .await;
+        assert_eq!(result.unwrap(), 3);
+
+        // Nested transactions are not allowed
+        let result = transaction_async(|| async {
+            let res = transaction_async(|| async { Ok::<_, Error>(()) }).await;
+            assert!(matches!(res, Err(TransactionError::AlreadyStarted)));
+            space.insert(&(4, "rolled back"))?;
+            Err::<(), _>(Error::IO(io::ErrorKind::Interrupted.into()))
+        })
+        .await;
+        assert!(matches!(result, Err(TransactionError::RolledBack(_))));
+    });
+
+    let output = space.get(&(3,)).unwrap().unwrap();
+    assert_eq!(output.decode::<S1Record>().unwrap().text, "async");
+    assert!(space.get(&(4,)).unwrap().is_none());
+    assert!(!tarantool::transaction::is_in_transaction());
+}
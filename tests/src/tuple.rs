@@ -138,6 +138,59 @@ pub fn tuple_iterator() {
     assert_eq!(iterator.next::<()>().unwrap(), None);
 }
 
+pub fn tuple_iter_as() {
+    let tuple = Tuple::new(&[1, -2, 3]).unwrap();
+    let values: Vec<i64> = tuple
+        .iter_as::<i64>()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(values, [1, -2, 3]);
+
+    let tuple = Tuple::new(&(1, "two")).unwrap();
+    let mut iter = tuple.iter_as::<i64>().unwrap();
+    assert_eq!(iter.next().unwrap().unwrap(), 1);
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
+
+pub fn tuple_fields() {
+    use tarantool::tuple::FieldType;
+    use tarantool::uuid::Uuid;
+
+    let uuid = Uuid::random();
+    let tuple = Tuple::new(&(1, -2, "three", 4.5, true, (), vec![6, 7], uuid)).unwrap();
+    let fields: Vec<_> = tuple.fields().collect();
+    assert_eq!(fields.len(), 8);
+
+    let types: Vec<_> = fields.iter().map(|f| f.field_type()).collect();
+    assert_eq!(
+        types,
+        [
+            FieldType::Unsigned,
+            FieldType::Integer,
+            FieldType::String,
+            FieldType::Double,
+            FieldType::Boolean,
+            FieldType::Any,
+            FieldType::Array,
+            FieldType::Uuid,
+        ]
+    );
+
+    assert_eq!(fields[0].decode::<u32>().unwrap(), 1);
+    assert_eq!(fields[1].decode::<i32>().unwrap(), -2);
+    assert_eq!(fields[2].decode::<&str>().unwrap(), "three");
+    assert_eq!(fields[3].decode::<f64>().unwrap(), 4.5);
+    assert!(fields[4].decode::<bool>().unwrap());
+    assert_eq!(fields[6].decode::<Vec<i32>>().unwrap(), [6, 7]);
+    assert_eq!(fields[7].decode::<Uuid>().unwrap(), uuid);
+    assert_eq!(fields[5].data(), b"\xc0");
+
+    let tuple = Tuple::new(&()).unwrap();
+    assert_eq!(tuple.fields().count(), 0);
+}
+
 pub fn tuple_iterator_seek_rewind() {
     let tuple = Tuple::new(&S2Record {
         id: 1,
@@ -336,6 +389,81 @@ pub fn tuple_compare_with_key() {
     assert_eq!(key_def.compare_with_key(&key, &key), Ordering::Equal);
 }
 
+pub fn key_def_builder_sort() {
+    let key_def = KeyDef::builder()
+        .part(1, FieldType::String)
+        .part(0, FieldType::Unsigned)
+        .build()
+        .unwrap();
+
+    let mut tuples: Vec<_> = [(3, "b"), (1, "b"), (2, "a"), (4, "a")]
+        .iter()
+        .map(|t| Tuple::new(t).unwrap())
+        .collect();
+    tuples.sort_by(|a, b| key_def.compare(a, b));
+
+    let sorted: Vec<(u32, String)> = tuples.iter().map(|t| t.decode().unwrap()).collect();
+    assert_eq!(
+        sorted,
+        [
+            (2, "a".into()),
+            (4, "a".into()),
+            (1, "b".into()),
+            (3, "b".into()),
+        ]
+    );
+
+    assert_eq!(
+        key_def.compare_with_key(&tuples[0], &("a", 2)),
+        Ordering::Equal
+    );
+    let key = key_def.extract_key(&tuples[3]).unwrap();
+    assert_eq!(key.as_ref(), tarantool::msgpack::encode(&("b", 3)));
+
+    // collations are supported
+    let key_def = KeyDef::builder()
+        .part_with_collation(0, FieldType::String, "unicode_ci")
+        .build()
+        .unwrap();
+    let a = Tuple::new(&("FOO",)).unwrap();
+    let b = Tuple::new(&("foo",)).unwrap();
+    assert_eq!(key_def.compare(&a, &b), Ordering::Equal);
+
+    let err = KeyDef::builder()
+        .part_with_collation(0, FieldType::String, "no_such_collation")
+        .build()
+        .unwrap_err();
+    assert!(err.to_string().contains("no_such_collation"), "{}", err);
+}
+
+pub fn tuple_hash() {
+    let key_def = KeyDef::new([
+        &KeyDefPart {
+            field_no: 0,
+            field_type: FieldType::Unsigned,
+            ..Default::default()
+        },
+        &KeyDefPart {
+            field_no: 1,
+            field_type: FieldType::String,
+            ..Default::default()
+        },
+    ])
+    .unwrap();
+
+    let tuple_a = Tuple::new(&(1, "foo", "bar")).unwrap();
+    let tuple_b = Tuple::new(&(1, "foo", "baz")).unwrap();
+    let tuple_c = Tuple::new(&(2, "foo", "bar")).unwrap();
+
+    let hash_a = tuple_a.hash(&key_def);
+    // stable across calls
+    assert_eq!(tuple_a.hash(&key_def), hash_a);
+    assert_eq!(key_def.hash(&tuple_a), hash_a);
+    // equal keys hash equally
+    assert_eq!(tuple_b.hash(&key_def), hash_a);
+    assert_ne!(tuple_c.hash(&key_def), hash_a);
+}
+
 pub fn to_and_from_lua() {
     let svp = unsafe { ffi::box_region_used() };
     let tuple = Tuple::new(&S2Record {
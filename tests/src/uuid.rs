@@ -1,3 +1,4 @@
+use tarantool::space::{Field, Space};
 use tarantool::{tlua::LuaFunction, tuple::Tuple, uuid::Uuid};
 
 const UUID_STR: &str = "30de7784-33e2-4393-a8cd-b67534db2432";
@@ -36,3 +37,78 @@ pub fn from_lua() {
     let s: String = tostring.call_with_args(uuid).unwrap();
     assert_eq!(s, UUID_STR);
 }
+
+pub fn lua_insert_into_space() {
+    let space = Space::builder("uuid_lua_insert_test")
+        .field(Field::unsigned("id"))
+        .field(Field::uuid("value"))
+        .create()
+        .unwrap();
+    space.index_builder("pk").create().unwrap();
+
+    let uuid = Uuid::parse_str(UUID_STR).unwrap();
+    let lua = tarantool::lua_state();
+
+    // Pushed as the native uuid cdata
+    let is_uuid: bool = lua
+        .eval_with("return require('uuid').is_uuid(...)", uuid)
+        .unwrap();
+    assert!(is_uuid);
+
+    // So the field type check passes
+    lua.exec_with(
+        "local u = ...; box.space.uuid_lua_insert_test:insert{1, u}",
+        uuid,
+    )
+    .unwrap();
+
+    let (_, stored): (u32, Uuid) = space.get(&(1,)).unwrap().unwrap().decode().unwrap();
+    assert_eq!(stored.as_bytes(), uuid.as_bytes());
+
+    let read: Uuid = lua
+        .eval("return box.space.uuid_lua_insert_test:get(1).value")
+        .unwrap();
+    assert_eq!(read.as_bytes(), uuid.as_bytes());
+
+    space.drop().unwrap();
+}
+
+pub fn primary_key() {
+    let space = Space::builder("uuid_primary_key_test")
+        .field(Field::uuid("id"))
+        .field(Field::string("value"))
+        .create()
+        .unwrap();
+    space.index_builder("pk").part("id").create().unwrap();
+
+    let ids: Vec<_> = [UUID_STR, "00000000-0000-0000-0000-000000000001"]
+        .iter()
+        .map(|s| Uuid::parse_str(s).unwrap())
+        .collect();
+    space.insert(&(ids[0], "first")).unwrap();
+    space.insert(&(ids[1], "second")).unwrap();
+
+    // The primary key is unique
+    let err = space.insert(&(ids[0], "duplicate")).unwrap_err();
+    assert!(err.to_string().contains("Duplicate key exists"), "{}", err);
+
+    let (id, value): (Uuid, String) = space.get(&(ids[0],)).unwrap().unwrap().decode().unwrap();
+    assert_eq!(id, ids[0]);
+    assert_eq!(value, "first");
+
+    // Tuples are ordered the same way as the uuid strings
+    let selected: Vec<(Uuid, String)> = space
+        .select(tarantool::index::IteratorType::All, &())
+        .unwrap()
+        .map(|t| t.decode().unwrap())
+        .collect();
+    assert_eq!(
+        selected,
+        [
+            (ids[1], "second".to_string()),
+            (ids[0], "first".to_string())
+        ]
+    );
+
+    space.drop().unwrap();
+}
@@ -1,3 +1,5 @@
+use std::ffi::CStr;
+use std::fmt;
 use std::io::Write;
 
 use crate::{c_ptr, ffi, AsLua, LuaState, Push, PushGuard, PushOne, Void};
@@ -56,3 +58,67 @@ pub unsafe fn dump_stack_raw_to(lua: LuaState, mut out: impl Write) -> std::io::
 pub unsafe fn dump_stack_raw(lua: LuaState) {
     dump_stack_raw_to(lua, std::io::stderr()).unwrap()
 }
+
+/// Description of a single slot of the lua stack returned by [`dump_stack`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackEntry {
+    /// Absolute index of the slot, starting at 1.
+    pub index: i32,
+    /// Name of the value's type as returned by lua's `type` function.
+    pub type_name: &'static str,
+    /// Best-effort string representation of the value. Strings and numbers
+    /// are rendered as is, other values are rendered as `<type>: <address>`.
+    /// Metamethods (e.g. `__tostring`) are never called.
+    pub value: String,
+}
+
+impl fmt::Display for StackEntry {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}({})", self.index, self.type_name, self.value)
+    }
+}
+
+/// Returns a description of each value on the lua stack, starting from the
+/// bottom.
+///
+/// The stack is left unchanged. Useful for debugging the push/pop balance.
+///
+/// See also [`Lua::dump_stack`](crate::Lua::dump_stack).
+pub fn dump_stack(lua: impl AsLua) -> Vec<StackEntry> {
+    let lua = lua.as_lua();
+    unsafe {
+        let top = ffi::lua_gettop(lua);
+        let mut res = Vec::with_capacity(top as _);
+        for index in 1..=top {
+            let t = ffi::lua_type(lua, index);
+            let type_name = CStr::from_ptr(ffi::lua_typename(lua, t));
+            let type_name = type_name.to_str().unwrap_or("?");
+            let value = match t {
+                ffi::LUA_TNIL => "nil".into(),
+                ffi::LUA_TBOOLEAN => (ffi::lua_toboolean(lua, index) != 0).to_string(),
+                ffi::LUA_TNUMBER | ffi::LUA_TSTRING => {
+                    // Convert a copy, because `lua_tolstring` replaces
+                    // numbers with strings in place
+                    ffi::lua_pushvalue(lua, index);
+                    let mut len = 0;
+                    let ptr = ffi::lua_tolstring(lua, -1, &mut len);
+                    let bytes = std::slice::from_raw_parts(ptr as *const u8, len);
+                    let value = String::from_utf8_lossy(bytes).into_owned();
+                    ffi::lua_pop(lua, 1);
+                    value
+                }
+                ffi::LUA_TLIGHTUSERDATA => {
+                    format!("{}: {:?}", type_name, ffi::lua_touserdata(lua, index))
+                }
+                _ => format!("{}: {:?}", type_name, ffi::lua_topointer(lua, index)),
+            };
+            res.push(StackEntry {
+                index,
+                type_name,
+                value,
+            });
+        }
+        res
+    }
+}
@@ -222,12 +222,29 @@ extern "C" {
     /// from the stack.
     pub fn lua_tolstring(l: *mut lua_State, index: c_int, len: *mut usize) -> *const c_char;
 
+    /// Returns the "length" of the value at the given acceptable index: for
+    /// strings, this is the string length; for tables, this is the result of
+    /// the length operator (`#`); for userdata, this is the size of the block
+    /// of memory allocated for the userdata; for other values, it is 0.
+    /// *[-0, +0, -]*
+    pub fn lua_objlen(l: *mut lua_State, index: c_int) -> usize;
+
     /// If the value at the given acceptable `index` is a full userdata, returns
     /// its block address. If the value is a light userdata, returns its
     /// pointer. Otherwise, returns `NULL`.
     /// *[-0, +0, -]*
     pub fn lua_touserdata(l: *mut lua_State, index: c_int) -> *mut libc::c_void;
 
+    /// Converts the value at the given acceptable `index` to a generic C
+    /// pointer (`void*`). The value can be a userdata, a table, a thread, or a
+    /// function; otherwise, `lua_topointer` returns `NULL`. Different objects
+    /// will give different pointers. There is no way to convert the pointer
+    /// back to its original value.
+    /// *[-0, +0, -]*
+    ///
+    /// Typically this function is used only for debug information.
+    pub fn lua_topointer(l: *mut lua_State, index: c_int) -> *const c_void;
+
     /// Does the equivalent to `t[k] = v`, where `t` is the value at the given
     /// valid index and `v` is the value at the top of the stack.
     /// *[-1, +0, e]*
@@ -337,6 +354,18 @@ extern "C" {
     pub fn lua_setmetatable(l: *mut lua_State, index: c_int) -> c_int;
     pub fn lua_getmetatable(l: *mut lua_State, index: c_int) -> c_int;
 
+    /// Pops a table from the stack and sets it as the new environment for the
+    /// value at the given `index`. If the value at the given index is neither
+    /// a function nor a thread nor a userdata, returns 0. Otherwise it returns
+    /// 1.
+    /// *[-1, +0, -]*
+    pub fn lua_setfenv(l: *mut lua_State, index: c_int) -> c_int;
+
+    /// Pushes onto the stack the environment table of the value at the given
+    /// `index`.
+    /// *[-0, +1, -]*
+    pub fn lua_getfenv(l: *mut lua_State, index: c_int);
+
     pub fn lua_tonumber(l: *mut lua_State, index: c_int) -> lua_Number;
     pub fn lua_tonumberx(l: *mut lua_State, index: c_int, isnum: *mut c_int) -> lua_Number;
     pub fn lua_tointegerx(l: *mut lua_State, index: c_int, isnum: *mut c_int) -> lua_Integer;
@@ -1,5 +1,5 @@
 use crate::{
-    error, ffi, values::ToString, AsLua, LuaError, LuaRead, LuaState, Nil, Push, PushGuard,
+    c_ptr, error, ffi, values::ToString, AsLua, LuaError, LuaRead, LuaState, Nil, Push, PushGuard,
     PushInto, PushOne, PushOneInto, StaticLua, Void, WrongType,
 };
 
@@ -183,6 +183,91 @@ impl<F, P, R> Function<F, P, R> {
     }
 }
 
+impl Function<(), (), ()> {
+    /// Wraps a function which accepts any number of arguments, all of which
+    /// are read into a `Vec<T>`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use tlua::{AnyLuaValue, Function, Lua};
+    ///
+    /// let lua = Lua::new();
+    /// lua.set("count", Function::variadic(|args: Vec<AnyLuaValue>| args.len()));
+    /// assert_eq!(lua.eval::<usize>("return count(1, 'two', {}, nil)").unwrap(), 4);
+    /// ```
+    ///
+    /// See also [`Variadic`].
+    #[allow(clippy::type_complexity)]
+    pub fn variadic<F, T, R>(
+        mut function: F,
+    ) -> Function<impl FnMut(Variadic<T>) -> R, (Variadic<T>,), R>
+    where
+        F: FnMut(Vec<T>) -> R,
+    {
+        Function::new(move |Variadic(args)| function(args))
+    }
+}
+
+/// A callback parameter which receives all the remaining arguments the
+/// function was called with.
+///
+/// See [`Function::variadic`].
+///
+/// # Example
+/// ```no_run
+/// use tlua::{Function, Lua, Variadic};
+///
+/// let lua = Lua::new();
+/// lua.set("sum", Function::new(|first: i32, Variadic(rest): Variadic<i32>| {
+///     first + rest.into_iter().sum::<i32>()
+/// }));
+/// assert_eq!(lua.eval::<i32>("return sum(1, 2, 3)").unwrap(), 6);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Variadic<T>(pub Vec<T>);
+
+impl<L, T> LuaRead<L> for Variadic<T>
+where
+    L: AsLua,
+    T: for<'a> LuaRead<&'a L>,
+{
+    #[inline]
+    fn lua_read_at_maybe_zero_position(lua: L, index: i32) -> crate::ReadResult<Self, L> {
+        match std::num::NonZeroI32::new(index) {
+            Some(index) => Self::lua_read_at_position(lua, index),
+            // No arguments
+            None => Ok(Self(Vec::new())),
+        }
+    }
+
+    fn lua_read_at_position(lua: L, index: std::num::NonZeroI32) -> crate::ReadResult<Self, L> {
+        let top = unsafe { ffi::lua_gettop(lua.as_lua()) };
+        let first = if index.get() > 0 {
+            index.get()
+        } else {
+            top + index.get() + 1
+        };
+        let mut values = Vec::with_capacity((top - first + 1).max(0) as _);
+        for position in first..=top {
+            let position = std::num::NonZeroI32::new(position).expect("stack positions start at 1");
+            match T::lua_read_at_position(&lua, position) {
+                Ok(v) => values.push(v),
+                Err((_, e)) => {
+                    // Inside a callback the arguments are the only values on
+                    // the stack, so the stack position is also the number of
+                    // the lua argument, fixed parameters included.
+                    let e = WrongType::info("reading variadic arguments")
+                        .expected_type::<T>()
+                        .actual(format!("argument #{} of wrong type", position))
+                        .subtype(e);
+                    return Err((lua, e));
+                }
+            }
+        }
+        Ok(Self(values))
+    }
+}
+
 /// Trait implemented on `Function` to mimic `FnMut`.
 ///
 /// We could in theory use the `FnMut` trait instead of this one, but it is still unstable.
@@ -444,6 +529,162 @@ where
     }
 }
 
+impl<E> Throw<E> {
+    /// Attach a numeric error `code` to the error, see [`ThrowWithCode`].
+    #[inline(always)]
+    pub fn with_code(self, code: u32) -> ThrowWithCode<E> {
+        ThrowWithCode {
+            code,
+            error: self.0,
+        }
+    }
+}
+
+/// A wrapper type for throwing lua errors with a numeric error code from a
+/// rust callback's result.
+///
+/// If the lua state has `box.error.new` (i.e. it's tarantool's lua state), the
+/// error is thrown as a `box.error` object with the given code and message.
+/// Otherwise it's thrown as a lua table with the same fields `code` and
+/// `message`, so that lua code can handle it the same way. In both cases
+/// `tostring` returns the message, so if the error isn't caught in lua, it's
+/// converted to [`LuaError::ExecutionError`] with just the message.
+///
+/// # Example
+/// ```no_run
+/// use tlua::{Function, Lua, ThrowWithCode};
+///
+/// let lua = Lua::new();
+/// lua.set("callback", Function::new(|| -> Result<(), ThrowWithCode<&str>> {
+///     Err(ThrowWithCode { code: 42, error: "failed" })
+/// }));
+/// let code: u32 = lua.eval("
+///     local ok, err = pcall(callback)
+///     return err.code
+/// ").unwrap();
+/// assert_eq!(code, 42);
+/// ```
+///
+/// [`LuaError::ExecutionError`]: crate::LuaError::ExecutionError
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ThrowWithCode<E> {
+    pub code: u32,
+    pub error: E,
+}
+
+impl<T, E> PushInto<InsideCallback> for Result<T, ThrowWithCode<E>>
+where
+    T: PushInto<InsideCallback>,
+    E: Display,
+{
+    type Err = T::Err;
+
+    #[inline]
+    fn push_into_lua(
+        self,
+        lua: InsideCallback,
+    ) -> Result<PushGuard<InsideCallback>, (T::Err, InsideCallback)> {
+        let ThrowWithCode { code, error } = match self {
+            Ok(ok) => return ok.push_into_lua(lua),
+            Err(err) => err,
+        };
+        let message = error.to_string();
+        // Drop everything before `lua_error`, because it never returns
+        drop(error);
+        unsafe {
+            let l = lua.as_lua();
+            if !push_box_error(l, code, &message) {
+                ffi::lua_createtable(l, 0, 2);
+                ffi::lua_pushinteger(l, code as _);
+                ffi::lua_setfield(l, -2, c_ptr!("code"));
+                ffi::lua_pushlstring(l, message.as_ptr().cast(), message.len());
+                ffi::lua_setfield(l, -2, c_ptr!("message"));
+                ffi::lua_createtable(l, 0, 1);
+                ffi::lua_pushcfunction(l, coded_error_tostring);
+                ffi::lua_setfield(l, -2, c_ptr!("__tostring"));
+                ffi::lua_setmetatable(l, -2);
+            }
+            drop(message);
+            ffi::lua_error(l);
+        }
+        unreachable!("lua_error never returns")
+    }
+}
+
+/// Pushes `box.error.new { code = code, reason = message }` onto the stack.
+/// Returns `false` and leaves the stack unchanged if `box.error.new` isn't
+/// available or fails.
+unsafe fn push_box_error(l: LuaState, code: u32, message: &str) -> bool {
+    let top = ffi::lua_gettop(l);
+    ffi::lua_getglobal(l, c_ptr!("box"));
+    if ffi::lua_istable(l, -1) {
+        ffi::lua_getfield(l, -1, c_ptr!("error"));
+        if ffi::lua_istable(l, -1) {
+            ffi::lua_getfield(l, -1, c_ptr!("new"));
+            if ffi::lua_isfunction(l, -1) {
+                ffi::lua_createtable(l, 0, 2);
+                ffi::lua_pushinteger(l, code as _);
+                ffi::lua_setfield(l, -2, c_ptr!("code"));
+                ffi::lua_pushlstring(l, message.as_ptr().cast(), message.len());
+                ffi::lua_setfield(l, -2, c_ptr!("reason"));
+                if ffi::lua_pcall(l, 1, 1, 0) == 0 {
+                    // stack: box, box.error, error
+                    ffi::lua_insert(l, top + 1);
+                    ffi::lua_settop(l, top + 1);
+                    return true;
+                }
+            }
+        }
+    }
+    ffi::lua_settop(l, top);
+    false
+}
+
+unsafe extern "C-unwind" fn coded_error_tostring(l: LuaState) -> libc::c_int {
+    ffi::lua_getfield(l, 1, c_ptr!("message"));
+    1
+}
+
+/// Calls `f` catching a panic and converting it into a [`Throw`], so that
+/// when called inside a rust callback the panic is turned into a lua error
+/// instead of unwinding into the lua runtime.
+///
+/// The error message is `"rust callback panicked: <panic message>"`.
+///
+/// This isn't done for every callback automatically, because lua errors (e.g.
+/// ones raised via [`error!`]) are propagated by unwinding the stack as well,
+/// and a lua error unwinding through [`std::panic::catch_unwind`] aborts the
+/// process. So `f` must not raise lua errors.
+///
+/// # Example
+/// ```no_run
+/// use tlua::{Function, Lua};
+///
+/// let lua = Lua::new();
+/// lua.set("callback", Function::new(|x: i32| {
+///     tlua::catch_panic(|| 100 / x)
+/// }));
+/// let err = lua.eval::<()>("return callback(0)").unwrap_err();
+/// assert!(err.to_string().starts_with("rust callback panicked"));
+/// ```
+///
+/// [`error!`]: crate::error
+pub fn catch_panic<F, R>(f: F) -> Result<R, Throw<String>>
+where
+    F: FnOnce() -> R,
+{
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).map_err(|payload| {
+        let message = if let Some(s) = payload.downcast_ref::<&str>() {
+            s
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            s.as_str()
+        } else {
+            "Box<dyn Any>"
+        };
+        Throw(format!("rust callback panicked: {}", message))
+    })
+}
+
 impl<T, E> PushOneInto<InsideCallback> for Result<T, E>
 where
     T: PushOneInto<InsideCallback>,
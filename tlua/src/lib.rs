@@ -136,11 +136,12 @@ pub use ::tlua_derive::test;
 pub use any::{AnyHashableLuaValue, AnyLuaString, AnyLuaValue};
 pub use cdata::{AsCData, CData, CDataOnStack};
 pub use functions_write::{
-    function0, function1, function10, function2, function3, function4, function5, function6,
-    function7, function8, function9, protected_call, CFunction, Function, InsideCallback, Throw,
+    catch_panic, function0, function1, function10, function2, function3, function4, function5,
+    function6, function7, function8, function9, protected_call, CFunction, Function,
+    InsideCallback, Throw, ThrowWithCode, Variadic,
 };
-pub use lua_functions::LuaFunction;
 pub use lua_functions::{LuaCode, LuaCodeFromReader};
+pub use lua_functions::{LuaFunction, LuaFunctionIter};
 pub use lua_tables::{LuaTable, LuaTableIterator};
 pub use object::{
     Call, CallError, Callable, Index, Indexable, IndexableRW, MethodCallError, NewIndex, Object,
@@ -203,6 +204,10 @@ pub type LuaThread = Lua<on_drop::Unref>;
 /// This type isn't panic safe. This means that if a panic happens while you were using the `Lua`,
 /// then it will probably stay in a corrupt state. Trying to use the `Lua` again will most likely
 /// result in another panic but shouldn't result in unsafety.
+///
+/// Lua errors raised outside of a protected call invoke the panic handler.
+/// Use [`Lua::eval_protected`] and [`Lua::exec_protected`] to turn them into
+/// a [`LuaError`] instead.
 #[derive(Debug)]
 pub struct Lua<OnDrop>
 where
@@ -482,6 +487,46 @@ pub trait AsLua {
         rust_tables::push_iter(self, iterator)
     }
 
+    /// Push `iterator` onto the lua stack as a lua function, which returns the
+    /// next element of the iterator every time it's called and `nil` once the
+    /// iterator is exhausted.
+    ///
+    /// Unlike [`AsLua::push_iter`] the elements are not collected into a table
+    /// but are produced lazily, so this can be used with large or infinite
+    /// iterators. The resulting function can be used in a generic lua `for`
+    /// loop:
+    ///
+    /// ```no_run
+    /// use tlua::{AsLua, Lua, LuaFunction};
+    /// let lua = Lua::new();
+    /// let gen: LuaFunction<_> = (&lua).push_generator(1..).read().unwrap();
+    /// let sum: i32 = lua
+    ///     .eval_with("local sum = 0
+    ///         for i in ... do
+    ///             if i > 10 then break end
+    ///             sum = sum + i
+    ///         end
+    ///         return sum", &gen)
+    ///     .unwrap();
+    /// assert_eq!(sum, 55);
+    /// ```
+    ///
+    /// The iterator is moved into a lua userdata and is dropped when the
+    /// function is garbage collected.
+    ///
+    /// Returns a `PushGuard` which captures `self` by value and stores the
+    /// amount of values pushed onto the stack (exactly 1 -- lua function).
+    #[inline(always)]
+    fn push_generator<I>(self, iterator: I) -> PushGuard<Self>
+    where
+        Self: Sized,
+        I: Iterator + 'static,
+        <I as Iterator>::Item: PushInto<InsideCallback> + 'static,
+    {
+        let mut iterator = iterator;
+        self.push(function0(move || iterator.next()))
+    }
+
     #[inline(always)]
     fn read<T>(self) -> ReadResult<T, Self>
     where
@@ -1267,6 +1312,220 @@ where
         LuaFunction::load(self, code)?.into_call_with_args(args)
     }
 
+    /// Evaluates some Lua code with `env` as its global environment.
+    ///
+    /// `env` must be pushed as a table, otherwise a [`LuaError::WrongType`]
+    /// is returned. The global variables accessed by the code are looked up
+    /// in `env` instead of the global table, and the assignments to the global
+    /// variables go into `env`, so the code can only use the functions which
+    /// were explicitly put into `env`. This is useful for running untrusted
+    /// code with a restricted set of functions (e.g. without `os` or `io`).
+    ///
+    /// Note that the functions in `env` are still executed in their own
+    /// environment, so whitelisting a function which accesses the globals
+    /// gives the code indirect access to those globals. Also the metatables
+    /// of builtin types (e.g. the string methods) are shared with the rest of
+    /// the lua state.
+    ///
+    /// Precompiled bytecode (e.g. produced by `string.dump`) is rejected with
+    /// a [`LuaError::SyntaxError`], because malicious bytecode can escape the
+    /// environment.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlua::{Lua, LuaTable};
+    /// let lua = Lua::new();
+    /// lua.openlibs();
+    /// let env = LuaTable::empty(&lua);
+    /// env.set("double", tlua::function1(|x: i32| x * 2));
+    /// let res: i32 = lua.eval_in_env("return double(21)", &env).unwrap();
+    /// assert_eq!(res, 42);
+    /// let res = lua.eval_in_env::<_, ()>("os.exit(1)", &env);
+    /// assert!(res.is_err());
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn eval_in_env<'lua, E, T>(&'lua self, code: &str, env: E) -> Result<T, LuaError>
+    where
+        E: PushOneInto<LuaState>,
+        E::Err: Into<Void>,
+        T: LuaRead<PushGuard<LuaFunction<PushGuard<&'lua Self>>>>,
+    {
+        let f = LuaFunction::load_text(self, code)?;
+        unsafe {
+            let l = self.as_lua();
+            l.push_one(env).assert_one_and_forget();
+            if !ffi::lua_istable(l, -1) {
+                let e = WrongType::info("setting environment of lua chunk")
+                    .expected("table")
+                    .actual_single_lua(l, crate::NEGATIVE_ONE);
+                ffi::lua_pop(l, 1);
+                return Err(e.into());
+            }
+            ffi::lua_setfenv(l, -2);
+        }
+        f.into_call()
+    }
+
+    /// Executes some Lua code with `env` as its global environment.
+    ///
+    /// See [`Lua::eval_in_env`] for details.
+    #[track_caller]
+    #[inline(always)]
+    pub fn exec_in_env<E>(&self, code: &str, env: E) -> Result<(), LuaError>
+    where
+        E: PushOneInto<LuaState>,
+        E::Err: Into<Void>,
+    {
+        self.eval_in_env(code, env)
+    }
+
+    /// Same as [`Lua::eval`], but everything printed by the code via the
+    /// global `print` function is captured and returned along with the result
+    /// instead of being written to stdout.
+    ///
+    /// The global `print` is temporarily replaced for the duration of the
+    /// evaluation and is restored afterwards, even if the evaluation fails.
+    /// Same as the builtin `print`, the replacement converts the values using
+    /// the global `tostring` function.
+    ///
+    /// **NOTE**: the global `print` is shared by all the code running in the
+    /// lua state, so if the evaluated code yields (e.g. in tarantool), the
+    /// output printed by other fibers in the meantime is captured as well.
+    /// For the same reason this function must not be used from several
+    /// fibers at the same time, otherwise the original `print` may not be
+    /// restored correctly.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlua::Lua;
+    /// let lua = Lua::new();
+    /// lua.openlibs();
+    /// let (res, output) = lua.eval_capturing_print::<i32>("print('hi', 1); return 5");
+    /// assert_eq!(res.unwrap(), 5);
+    /// assert_eq!(output, "hi\t1\n");
+    /// ```
+    #[track_caller]
+    pub fn eval_capturing_print<'lua, T>(&'lua self, code: &str) -> (Result<T, LuaError>, String)
+    where
+        T: LuaRead<PushGuard<LuaFunction<PushGuard<&'lua Self>>>>,
+    {
+        let output = std::rc::Rc::new(std::cell::RefCell::new(String::new()));
+        let l = self.as_lua();
+        let old_print = unsafe {
+            ffi::lua_getglobal(l, c_ptr!("print"));
+            ffi::luaL_ref(l, ffi::LUA_REGISTRYINDEX)
+        };
+        self.set(
+            "print",
+            Function::new({
+                let output = output.clone();
+                move |lua: StaticLua| -> Result<(), String> {
+                    // NOTE: the output must not be borrowed while `tostring`
+                    // is running, as it may call `print` recursively
+                    let line = print_to_string(lua.as_lua())?;
+                    output.borrow_mut().push_str(&line);
+                    Ok(())
+                }
+            }),
+        );
+
+        let res = self.eval(code);
+
+        unsafe {
+            ffi::lua_rawgeti(l, ffi::LUA_REGISTRYINDEX, old_print);
+            ffi::lua_setglobal(l, c_ptr!("print"));
+            ffi::luaL_unref(l, ffi::LUA_REGISTRYINDEX, old_print);
+        }
+        let output = output.take();
+        return (res, output);
+
+        /// Converts the arguments on the stack to a line of text the same way
+        /// the builtin `print` does.
+        fn print_to_string(l: LuaState) -> Result<String, String> {
+            let mut line = String::new();
+            unsafe {
+                let n = ffi::lua_gettop(l);
+                for i in 1..=n {
+                    ffi::lua_getglobal(l, c_ptr!("tostring"));
+                    ffi::lua_pushvalue(l, i);
+                    let rc = ffi::lua_pcall(l, 1, 1, 0);
+                    let mut len = 0;
+                    let ptr = ffi::lua_tolstring(l, -1, &mut len);
+                    let s = (!ptr.is_null()).then(|| {
+                        String::from_utf8_lossy(std::slice::from_raw_parts(ptr.cast(), len))
+                    });
+                    let s = match (rc, s) {
+                        (0, Some(s)) => s.into_owned(),
+                        (0, None) => {
+                            ffi::lua_pop(l, 1);
+                            return Err("'tostring' must return a string to 'print'".into());
+                        }
+                        (_, s) => {
+                            let e = s.map_or_else(|| "unknown error".into(), |s| s.into_owned());
+                            ffi::lua_pop(l, 1);
+                            return Err(e);
+                        }
+                    };
+                    ffi::lua_pop(l, 1);
+                    if i > 1 {
+                        line.push('\t');
+                    }
+                    line.push_str(&s);
+                }
+            }
+            line.push('\n');
+            Ok(line)
+        }
+    }
+
+    /// Same as [`Lua::eval`], but the whole evaluation including the
+    /// conversion of the result is done inside a `lua_cpcall` boundary.
+    ///
+    /// [`Lua::eval`] only protects the execution of the code itself. Lua
+    /// errors raised outside of it (for example by a metamethod invoked while
+    /// reading the result) end up in the panic handler, which is not
+    /// recoverable. With this method any such error becomes a
+    /// [`LuaError::ExecutionError`].
+    ///
+    /// The result type cannot borrow from the lua stack, because the stack
+    /// frame of the protected call is gone by the time the function returns.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlua::{Lua, LuaError};
+    /// let lua = Lua::new();
+    /// let res = lua.eval_protected::<i32>("error('oops')");
+    /// assert!(matches!(res, Err(LuaError::ExecutionError(_))));
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn eval_protected<T>(&self, code: &str) -> Result<T, LuaError>
+    where
+        T: for<'lua> LuaRead<PushGuard<LuaFunction<PushGuard<&'lua StaticLua>>>>,
+    {
+        self.pcall(|lua| lua.eval(code))?
+    }
+
+    /// Same as [`Lua::exec`], but runs inside a `lua_cpcall` boundary, see
+    /// [`Lua::eval_protected`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlua::Lua;
+    /// let lua = Lua::new();
+    /// lua.exec_protected("error('oops')").unwrap_err();
+    /// ```
+    #[track_caller]
+    #[inline]
+    pub fn exec_protected(&self, code: &str) -> Result<(), LuaError> {
+        self.pcall(|lua| lua.exec(code))?
+    }
+
     /// Executes some Lua code on the context.
     ///
     /// This does the same thing as [the `eval` method](#method.eval), but the
@@ -1433,6 +1692,80 @@ where
         }
     }
 
+    /// Registers `module` as a lua module with the given `name`, i.e. sets
+    /// `package.loaded[name] = module`, so that any subsequent
+    /// `require(name)` returns it.
+    ///
+    /// This is useful for modules which set themselves up from inside a
+    /// running instance, rather than being loaded via `require`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` contains a nul byte.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tlua::Lua;
+    /// let lua = Lua::new();
+    /// lua.openlibs();
+    ///
+    /// let module: tlua::LuaTable<_> = lua.eval("return { answer = 42 }").unwrap();
+    /// lua.register_module("my_module", &module);
+    /// drop(module);
+    ///
+    /// let answer: i32 = lua.eval("return require('my_module').answer").unwrap();
+    /// assert_eq!(answer, 42);
+    /// ```
+    #[inline]
+    pub fn register_module<'lua, V>(&'lua self, name: &str, module: V)
+    where
+        V: PushOneInto<&'lua Self>,
+        <V as PushInto<&'lua Self>>::Err: Into<Void>,
+    {
+        let name = CString::new(name).unwrap();
+        unsafe {
+            // `package.loaded` is the same table as `registry._LOADED`
+            ffi::lua_getfield(self.lua, ffi::LUA_REGISTRYINDEX, c_ptr!("_LOADED"));
+            if !ffi::lua_istable(self.lua, -1) {
+                // The package library isn't loaded yet
+                ffi::lua_pop(self.lua, 1);
+                ffi::lua_newtable(self.lua);
+                ffi::lua_pushvalue(self.lua, -1);
+                ffi::lua_setfield(self.lua, ffi::LUA_REGISTRYINDEX, c_ptr!("_LOADED"));
+            }
+            match self.try_push(module) {
+                Ok(pushed) => {
+                    assert_eq!(pushed.size, 1);
+                    pushed.forget()
+                }
+                Err(_) => unreachable!(),
+            };
+            ffi::lua_setfield(self.lua, -2, name.as_ptr());
+            ffi::lua_pop(self.lua, 1);
+        }
+    }
+
+    /// Returns a description of each value currently on the lua stack,
+    /// starting from the bottom. The stack is left unchanged.
+    ///
+    /// This is useful for debugging unbalanced pushes and pops.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tlua::{AsLua, Lua};
+    /// let lua = Lua::new();
+    /// let _guard = (&lua).push("hello").push(42);
+    /// let stack = lua.dump_stack();
+    /// assert_eq!(stack[0].type_name, "string");
+    /// assert_eq!(stack[1].value, "42");
+    /// ```
+    #[inline]
+    pub fn dump_stack(&self) -> Vec<debug::StackEntry> {
+        debug::dump_stack(self)
+    }
+
     /// Sets the value of a global variable to an empty array, then loads it.
     ///
     /// This is the function you should use if you want to set the value of a global variable to
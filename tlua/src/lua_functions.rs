@@ -2,11 +2,12 @@ use std::ffi::CString;
 use std::io::Cursor;
 use std::io::Error as IoError;
 use std::io::Read;
+use std::marker::PhantomData;
 use std::num::NonZeroI32;
 use std::panic::Location;
 
 use crate::{
-    ffi, impl_object, nzi32,
+    c_ptr, ffi, impl_object, nzi32,
     object::{Call, CallError, FromObject, Object},
     AsLua, LuaError, LuaRead, LuaState, Push, PushGuard, PushInto, PushOne, PushOneInto,
 };
@@ -74,6 +75,7 @@ impl<L> PushOne<L> for LuaCode<'_> where L: AsLua {}
 pub struct LuaCodeFromReader<R> {
     reader: R,
     location: &'static Location<'static>,
+    text_only: bool,
 }
 
 impl<R> LuaCodeFromReader<R> {
@@ -82,8 +84,17 @@ impl<R> LuaCodeFromReader<R> {
         Self {
             reader,
             location: Location::caller(),
+            text_only: false,
         }
     }
+
+    /// Makes the loading fail with a [`LuaError::SyntaxError`] if the chunk
+    /// is precompiled bytecode instead of lua source code.
+    #[inline(always)]
+    pub(crate) fn text_only(mut self) -> Self {
+        self.text_only = true;
+        self
+    }
 }
 
 impl<L, R> PushInto<L> for LuaCodeFromReader<R>
@@ -140,11 +151,17 @@ where
             let (load_return_value, pushed_value) = {
                 let location = format!("=[{}:{}]\0", self.location.file(), self.location.line());
                 let location = CString::from_vec_with_nul_unchecked(location.into());
-                let code = ffi::lua_load(
+                let mode = if self.text_only {
+                    c_ptr!("t")
+                } else {
+                    c_ptr!("bt")
+                };
+                let code = ffi::lua_loadx(
                     lua.as_lua(),
                     reader::<R>,
                     &mut read_data as *mut ReadData<_> as *mut _,
                     location.as_ptr(),
+                    mode,
                 );
                 (code, PushGuard::new(lua, 1))
             };
@@ -374,6 +391,86 @@ where
     {
         Call::into_call_with(self, args)
     }
+
+    /// Turns the function into an iterator, which calls it repeatedly without
+    /// arguments and yields the returned values until the function returns
+    /// `nil`.
+    ///
+    /// This is useful for consuming stateful Lua iterators, e.g. the ones
+    /// created by closures or coroutines. `T` can be a tuple, if the function
+    /// returns multiple values, in which case the iteration stops once the
+    /// first of the values is `nil`.
+    ///
+    /// If the call fails or the returned value can't be converted into `T`,
+    /// the error is yielded and the iteration stops.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// let lua = tlua::Lua::new();
+    /// let f: tlua::LuaFunction<_> = lua
+    ///     .eval("local i = 0; return function() i = i + 1; if i <= 3 then return i end end")
+    ///     .unwrap();
+    /// let values: Vec<i32> = f.into_iter().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(values, [1, 2, 3]);
+    /// ```
+    #[allow(clippy::should_implement_trait)]
+    #[inline]
+    pub fn into_iter<T>(self) -> LuaFunctionIter<L, T>
+    where
+        T: for<'a> LuaRead<PushGuard<&'a L>>,
+    {
+        LuaFunctionIter {
+            function: self,
+            finished: false,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Iterator over the values returned by repeatedly calling a Lua function.
+///
+/// See [`LuaFunction::into_iter`] for more info.
+#[derive(Debug)]
+pub struct LuaFunctionIter<L, T> {
+    function: LuaFunction<L>,
+    finished: bool,
+    marker: PhantomData<T>,
+}
+
+impl<L, T> LuaFunctionIter<L, T> {
+    /// Returns the underlying function.
+    #[inline(always)]
+    pub fn into_function(self) -> LuaFunction<L> {
+        self.function
+    }
+}
+
+impl<L, T> Iterator for LuaFunctionIter<L, T>
+where
+    L: AsLua,
+    T: for<'a> LuaRead<PushGuard<&'a L>>,
+{
+    type Item = Result<T, LuaError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        match self.function.call::<Option<T>>() {
+            Ok(Some(v)) => Some(Ok(v)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
 }
 
 impl<L> LuaFunction<PushGuard<L>>
@@ -416,4 +513,19 @@ where
         let reader = Cursor::new(code.as_bytes());
         Self::load_from_reader(lua, reader)
     }
+
+    /// Same as [`LuaFunction::load`], but fails if `code` is precompiled
+    /// bytecode.
+    #[track_caller]
+    #[inline]
+    pub(crate) fn load_text(lua: L, code: &str) -> Result<Self, LuaError> {
+        let reader = Cursor::new(code.as_bytes());
+        match LuaCodeFromReader::new(reader)
+            .text_only()
+            .push_into_lua(lua)
+        {
+            Ok(pushed) => unsafe { Ok(Self::new(pushed, nzi32!(-1))) },
+            Err((err, _)) => Err(err),
+        }
+    }
 }
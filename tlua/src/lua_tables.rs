@@ -83,6 +83,37 @@ where
         }
     }
 
+    /// Returns the length of the table as computed by the lua `#` operator.
+    ///
+    /// For a sequence (a table whose positive integer keys are exactly
+    /// `1..=n`) this is `n`. If the table has holes (`nil` values between the
+    /// positive integer keys), the result is any of its *borders*, i.e. any
+    /// `n` such that `t[n] ~= nil` and `t[n + 1] == nil` (or 0 if `t[1]` is
+    /// `nil`), so it shouldn't be relied upon for such tables. Non integer keys
+    /// are not counted.
+    ///
+    /// The `__len` metamethod is not called, same as for the `#` operator
+    /// applied to a table in LuaJIT.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// let lua = tlua::Lua::new();
+    /// let table: tlua::LuaTable<_> = lua.eval("return { 1, 2, 3, x = 4 }").unwrap();
+    /// assert_eq!(table.len(), 3);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        unsafe { ffi::lua_objlen(self.as_lua(), self.as_ref().index().into()) }
+    }
+
+    /// Returns `true` if [`Self::len`] is 0. Note that this doesn't mean
+    /// the table has no entries, as non integer keys are not counted.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Loads a value in the table given its index.
     ///
     /// The index must implement the [`PushOneInto`] trait and the return type
@@ -195,6 +226,94 @@ where
         NewIndex::checked_set(self, index, value)
     }
 
+    /// Inserts `value` at position `pos` of the sequence shifting up the
+    /// elements `t[pos], t[pos + 1], ..., t[#t]`, same as lua
+    /// `table.insert(t, pos, value)`.
+    ///
+    /// The table is accessed directly, i.e. the metamethods are not called.
+    ///
+    /// # Panics
+    /// Panics if `pos` is not in range `1..=self.len() + 1`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// let lua = tlua::Lua::new();
+    /// let table: tlua::LuaTable<_> = lua.eval("return { 1, 3 }").unwrap();
+    /// table.insert(2, 2);
+    /// assert_eq!(table.get::<i32, _>(2), Some(2));
+    /// assert_eq!(table.get::<i32, _>(3), Some(3));
+    /// ```
+    #[track_caller]
+    pub fn insert<V>(&self, pos: usize, value: V)
+    where
+        V: PushOneInto<LuaState>,
+        V::Err: Into<Void>,
+    {
+        let len = self.len();
+        assert!(
+            (1..=len + 1).contains(&pos),
+            "insertion position (is {}) should be in range 1..={}",
+            pos,
+            len + 1
+        );
+        let lua = self.as_lua();
+        let index = self.as_ref().index().into();
+        unsafe {
+            for i in (pos..=len).rev() {
+                ffi::lua_rawgeti(lua, index, i as _);
+                ffi::lua_rawseti(lua, index, (i + 1) as _);
+            }
+            lua.push_one(value).assert_one_and_forget();
+            ffi::lua_rawseti(lua, index, pos as _);
+        }
+    }
+
+    /// Removes the element at position `pos` of the sequence shifting down
+    /// the elements `t[pos + 1], t[pos + 2], ..., t[#t]`, same as lua
+    /// `table.remove(t, pos)`.
+    ///
+    /// Returns `None` and leaves the table unchanged if `pos` is not in range
+    /// `1..=self.len()` or if the element couldn't be read as `V`.
+    ///
+    /// The table is accessed directly, i.e. the metamethods are not called.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// let lua = tlua::Lua::new();
+    /// let table: tlua::LuaTable<_> = lua.eval("return { 1, 2, 3 }").unwrap();
+    /// assert_eq!(table.remove::<i32>(1), Some(1));
+    /// assert_eq!(table.len(), 2);
+    /// assert_eq!(table.get::<i32, _>(1), Some(2));
+    /// ```
+    pub fn remove<V>(&'lua self, pos: usize) -> Option<V>
+    where
+        V: LuaRead<PushGuard<&'lua L>>,
+    {
+        let len = self.len();
+        if !(1..=len).contains(&pos) {
+            return None;
+        }
+        let guard = self.as_ref().guard();
+        let lua = guard.as_lua();
+        let index = self.as_ref().index().into();
+        unsafe {
+            ffi::lua_rawgeti(lua, index, pos as _);
+            let value =
+                V::lua_read_at_position(PushGuard::new(guard, 1), crate::NEGATIVE_ONE).ok()?;
+            // The value stays on the stack until it's dropped, so all the
+            // stack operations below must be balanced
+            for i in pos..len {
+                ffi::lua_rawgeti(lua, index, (i + 1) as _);
+                ffi::lua_rawseti(lua, index, i as _);
+            }
+            ffi::lua_pushnil(lua);
+            ffi::lua_rawseti(lua, index, len as _);
+            Some(value)
+        }
+    }
+
     pub fn call_method<R, A>(&'lua self, name: &str, args: A) -> Result<R, MethodCallError<A::Err>>
     where
         L: std::fmt::Debug,
@@ -234,6 +353,42 @@ where
         }
     }
 
+    /// Sets `mt` as the metatable of this table, replacing the existing one
+    /// if any.
+    ///
+    /// Unlike [`Self::metatable`] this allows attaching a prepared metatable
+    /// (possibly shared between many tables) in one call. The stack is left
+    /// unchanged.
+    ///
+    /// `mt` must belong to the same lua state as this table.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tlua::{Lua, LuaTable};
+    ///
+    /// let lua = Lua::new();
+    /// let defaults = LuaTable::empty(&lua);
+    /// defaults.set("x", 1);
+    /// let mt = LuaTable::empty(&lua);
+    /// mt.set("__index", &defaults);
+    ///
+    /// let table = LuaTable::empty(&lua);
+    /// table.set_metatable(&mt);
+    /// assert_eq!(table.get::<i32, _>("x"), Some(1));
+    /// ```
+    #[inline]
+    pub fn set_metatable<M>(&self, mt: &LuaTable<M>)
+    where
+        M: AsLua,
+    {
+        unsafe {
+            ffi::lua_pushvalue(self.as_lua(), mt.as_ref().index().into());
+            // pops the metatable
+            ffi::lua_setmetatable(self.as_lua(), self.as_ref().index().into());
+        }
+    }
+
     /// Obtains or creates the metatable of the table.
     ///
     /// A metatable is an additional table that can be attached to a table or a userdata. It can
@@ -464,7 +464,7 @@ macro_rules! tuple_impl {
     };
 }
 
-tuple_impl!(A, B, C, D, E, F, G, H, I, J, K, L, M);
+tuple_impl!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
 
 fn convert_as_table_read_error<T, L>(table: Indexable<L>, i: i32, err: LuaError) -> ReadResult<T, L>
 where
@@ -562,7 +562,7 @@ macro_rules! impl_tuple_push_error {
     };
 }
 
-impl_tuple_push_error! {A B C D E F G H I J K L M}
+impl_tuple_push_error! {A B C D E F G H I J K L M N O P}
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 /// A wrapper type for pushing and reading rust tuples as lua tables.